@@ -284,19 +284,23 @@ async function run_pipeline() {
   // Make rebalance decisions.
   var rebalance_infos = [];
   position_infos = position_infos.map((batch_position_info) => {
-    const rebalance_info = handleRebalance(
-      batch_position_info,
-      asset_timestamps,
-      asset_required_crs,
-      delta_tolerance,
-      balance_tolerance,
-      time_tolerance,
-      wallet
-    );
-    // If a position doesn't need rebalance, `handleRebalance` will return
-    // undefined.
-    if (rebalance_info) {
-      rebalance_infos.push(rebalance_info);
+    // We always query batch API with batch size of one; a diversified position
+    // has one item per leg, and each leg is rebalanced independently.
+    for (const current_position of batch_position_info.items) {
+      const rebalance_info = handleRebalance(
+        current_position,
+        asset_timestamps,
+        asset_required_crs,
+        delta_tolerance,
+        balance_tolerance,
+        time_tolerance,
+        wallet
+      );
+      // If a position doesn't need rebalance, `handleRebalance` will return
+      // undefined.
+      if (rebalance_info) {
+        rebalance_infos.push(rebalance_info);
+      }
     }
   });
 
@@ -542,7 +546,7 @@ async function getPositionInfos(
 }
 
 function handleRebalance(
-  batch_position_info,
+  current_position,
   asset_timestamps,
   asset_required_crs,
   delta_tolerance,
@@ -552,8 +556,6 @@ function handleRebalance(
 ) {
   metrics[NUM_PROCESSED_POSITION]++;
 
-  // We always query batch API with batch size of one.
-  const current_position = batch_position_info.items[0];
   const position_info = current_position.info;
   const position_id = current_position.position.position_id;
   const position_addr = current_position.contract;
@@ -692,20 +694,21 @@ function shouldRebalance(
 
   // Check balance.
   const uusd_value = new Big(detailed_info.uusd_value);
-  const uusd_balance = new Big(
-    detailed_info.claimable_short_proceeds_uusd_amount
-  )
+  const has_locked_proceeds =
+    !new Big(detailed_info.unclaimed_short_proceeds_uusd_amount).eq(0) &&
+    new Big(detailed_info.claimable_short_proceeds_uusd_amount).eq(0);
+  // While short proceeds are locked, liquid UST is parked in Anchor Earn; parked UST is reinvested after unlock.
+  let uusd_balance = new Big(detailed_info.claimable_short_proceeds_uusd_amount)
     .plus(detailed_info.claimable_mir_reward_uusd_value)
     .plus(detailed_info.claimable_spec_reward_uusd_value)
     .plus(detailed_info.state.uusd_balance);
+  if (!has_locked_proceeds) {
+    uusd_balance = uusd_balance.plus(
+      detailed_info.anchor_earn_parked_uusd_value
+    );
+  }
   logging += `balance percentage: ${uusd_balance.div(uusd_value).toString()}\n`;
-  const has_locked_proceeds =
-    !new Big(detailed_info.unclaimed_short_proceeds_uusd_amount).eq(0) &&
-    new Big(detailed_info.claimable_short_proceeds_uusd_amount).eq(0);
-  if (
-    uusd_balance.div(uusd_value).gt(balance_tolerance) &&
-    !has_locked_proceeds
-  ) {
+  if (uusd_balance.div(uusd_value).gt(balance_tolerance)) {
     logging += "Should rebalance due to: Balance too big.\n";
     return { result: true, logging: logging, reason: "BAL" };
   }
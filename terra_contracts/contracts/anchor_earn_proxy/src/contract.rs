@@ -56,7 +56,9 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                     recipient,
                     proportion,
                 } => withdraw(deps, env, &admin_config, &position, proportion, recipient),
-                Action::ClosePosition { recipient } => withdraw(
+                // Anchor Earn does not depend on an oracle, so an emergency close is a regular close.
+                Action::ClosePosition { recipient }
+                | Action::EmergencyClosePosition { recipient } => withdraw(
                     deps,
                     env,
                     &admin_config,
@@ -0,0 +1,432 @@
+use aperture_common::{
+    anchor_util::get_anchor_ust_exchange_rate, delta_neutral_position::LiquidityVenue,
+    delta_neutral_position_manager::Context,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, Env, QuerierWrapper, StdError, StdResult,
+    Storage, Uint128, WasmMsg,
+};
+use mirror_protocol::collateral_oracle::{CollateralInfosResponse, CollateralPriceResponse};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    dex_util::{create_uusd_swap_message, simulate_terraswap_swap, swap_cw20_token_for_uusd},
+    state::COLLATERAL_ASSET_INFO,
+};
+
+pub fn get_anchor_ust_asset_info(context: &Context) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: context.anchor_ust_cw20_addr.to_string(),
+    }
+}
+
+fn is_anchor_ust(context: &Context, asset_info: &AssetInfo) -> bool {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => *contract_addr == context.anchor_ust_cw20_addr,
+        AssetInfo::NativeToken { .. } => false,
+    }
+}
+
+fn is_uusd(asset_info: &AssetInfo) -> bool {
+    match asset_info {
+        AssetInfo::Token { .. } => false,
+        AssetInfo::NativeToken { denom } => denom == "uusd",
+    }
+}
+
+// Returns the asset posted as collateral to the Mirror CDP of this position.
+// Positions opened before the collateral asset became configurable use aUST.
+pub fn get_collateral_asset_info(storage: &dyn Storage, context: &Context) -> StdResult<AssetInfo> {
+    Ok(COLLATERAL_ASSET_INFO
+        .may_load(storage)?
+        .unwrap_or_else(|| get_anchor_ust_asset_info(context)))
+}
+
+// Checks that `collateral_asset_info` is registered in Mirror collateral oracle and has not been revoked.
+pub fn validate_collateral_asset(
+    querier: &QuerierWrapper,
+    context: &Context,
+    collateral_asset_info: &AssetInfo,
+) -> StdResult<()> {
+    let collateral_infos_response: CollateralInfosResponse = querier.query_wasm_smart(
+        &context.mirror_collateral_oracle_addr,
+        &mirror_protocol::collateral_oracle::QueryMsg::CollateralAssetInfos {},
+    )?;
+    let asset = collateral_asset_info.to_string();
+    match collateral_infos_response
+        .collaterals
+        .iter()
+        .find(|info| info.asset == asset)
+    {
+        None => Err(StdError::generic_err(
+            "collateral asset not registered in Mirror collateral oracle",
+        )),
+        Some(info) if info.is_revoked => {
+            Err(StdError::generic_err("collateral asset has been revoked"))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+// Queries Mirror collateral oracle for the price and the multiplier of `collateral_asset_info`.
+pub fn get_collateral_price_response(
+    querier: &QuerierWrapper,
+    context: &Context,
+    collateral_asset_info: &AssetInfo,
+) -> StdResult<CollateralPriceResponse> {
+    querier.query_wasm_smart(
+        &context.mirror_collateral_oracle_addr,
+        &mirror_protocol::collateral_oracle::QueryMsg::CollateralPrice {
+            asset: collateral_asset_info.to_string(),
+            timeframe: None,
+        },
+    )
+}
+
+// Describes how uusd is converted into the collateral asset when opening or increasing the CDP.
+pub enum UusdToCollateralConversion {
+    // uusd is deposited to Anchor Earn in exchange for aUST at `exchange_rate`.
+    AnchorDeposit {
+        exchange_rate: Decimal256,
+    },
+    // uusd itself is the collateral.
+    Identity,
+    // uusd is swapped for the collateral in the Terraswap collateral-UST pair.
+    TerraswapSwap {
+        pair_addr: String,
+        pool_collateral_amount: Uint128,
+        pool_uusd_amount: Uint128,
+    },
+}
+
+impl UusdToCollateralConversion {
+    pub fn new(
+        deps: Deps,
+        env: &Env,
+        context: &Context,
+        collateral_asset_info: &AssetInfo,
+    ) -> StdResult<Self> {
+        if is_anchor_ust(context, collateral_asset_info) {
+            return Ok(UusdToCollateralConversion::AnchorDeposit {
+                exchange_rate: get_anchor_ust_exchange_rate(
+                    deps,
+                    env,
+                    &context.anchor_market_addr,
+                )?,
+            });
+        }
+        if is_uusd(collateral_asset_info) {
+            return Ok(UusdToCollateralConversion::Identity);
+        }
+        let pair_asset_infos = [
+            collateral_asset_info.clone(),
+            AssetInfo::NativeToken {
+                denom: String::from("uusd"),
+            },
+        ];
+        let pair_info = terraswap::querier::query_pair_info(
+            &deps.querier,
+            context.terraswap_factory_addr.clone(),
+            &pair_asset_infos,
+        )?;
+        let pair_addr = Addr::unchecked(pair_info.contract_addr.clone());
+        Ok(UusdToCollateralConversion::TerraswapSwap {
+            pool_collateral_amount: pair_asset_infos[0].query_pool(
+                &deps.querier,
+                deps.api,
+                pair_addr.clone(),
+            )?,
+            pool_uusd_amount: pair_asset_infos[1].query_pool(&deps.querier, deps.api, pair_addr)?,
+            pair_addr: pair_info.contract_addr,
+        })
+    }
+
+    // Returns the amount of collateral obtained by converting `uusd_amount` amount of uusd.
+    pub fn simulate(&self, uusd_amount: Uint128) -> Uint128 {
+        match self {
+            UusdToCollateralConversion::AnchorDeposit { exchange_rate } => {
+                Uint128::from(Uint256::from(uusd_amount) / *exchange_rate)
+            }
+            UusdToCollateralConversion::Identity => uusd_amount,
+            UusdToCollateralConversion::TerraswapSwap {
+                pool_collateral_amount,
+                pool_uusd_amount,
+                ..
+            } => simulate_terraswap_swap(*pool_uusd_amount, *pool_collateral_amount, uusd_amount).2,
+        }
+    }
+
+    // Returns the uusd price per unit of collateral used to compute the amount of mAsset minted against the collateral.
+    // For aUST, this is the Anchor exchange rate which Mirror collateral oracle sources the aUST price from.
+    pub fn collateral_uusd_price(
+        &self,
+        collateral_price_response: &CollateralPriceResponse,
+    ) -> Decimal {
+        match self {
+            UusdToCollateralConversion::AnchorDeposit { exchange_rate } => {
+                Decimal::from(*exchange_rate)
+            }
+            _ => collateral_price_response.rate,
+        }
+    }
+
+    // Returns messages that convert `uusd_amount` amount of uusd into collateral; a swap fails if its spread exceeds `max_spread`.
+    pub fn create_messages(
+        &self,
+        context: &Context,
+        uusd_amount: Uint128,
+        max_spread: Option<Decimal>,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        match self {
+            UusdToCollateralConversion::AnchorDeposit { .. } => {
+                Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: context.anchor_market_addr.to_string(),
+                    msg: to_binary(&moneymarket::market::ExecuteMsg::DepositStable {})?,
+                    funds: vec![Coin {
+                        denom: String::from("uusd"),
+                        amount: uusd_amount,
+                    }],
+                })])
+            }
+            UusdToCollateralConversion::Identity => Ok(vec![]),
+            UusdToCollateralConversion::TerraswapSwap { pair_addr, .. } => {
+                Ok(vec![create_uusd_swap_message(
+                    LiquidityVenue::Terraswap,
+                    pair_addr,
+                    uusd_amount,
+                    max_spread,
+                )?])
+            }
+        }
+    }
+}
+
+// Withdraws `collateral_amount` amount of collateral from the CDP and converts it to uusd:
+// aUST is redeemed at Anchor Market; other cw20 collateral is swapped at the better of Terraswap and Astroport; native collateral is swapped at Terraswap.
+pub fn increase_uusd_balance_from_collateral(
+    querier: &QuerierWrapper,
+    context: &Context,
+    collateral_asset_info: &AssetInfo,
+    cdp_idx: Uint128,
+    collateral_amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: context.mirror_mint_addr.to_string(),
+        funds: vec![],
+        msg: to_binary(&mirror_protocol::mint::ExecuteMsg::Withdraw {
+            position_idx: cdp_idx,
+            collateral: Some(Asset {
+                info: collateral_asset_info.clone(),
+                amount: collateral_amount,
+            }),
+        })?,
+    })];
+    match collateral_asset_info {
+        _ if is_anchor_ust(context, collateral_asset_info) => {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: context.anchor_ust_cw20_addr.to_string(),
+                funds: vec![],
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: context.anchor_market_addr.to_string(),
+                    amount: collateral_amount,
+                    msg: to_binary(&moneymarket::market::Cw20HookMsg::RedeemStable {})?,
+                })?,
+            }));
+        }
+        _ if is_uusd(collateral_asset_info) => {}
+        AssetInfo::Token { contract_addr } => {
+            let (swap_message, _) = swap_cw20_token_for_uusd(
+                querier,
+                &context.terraswap_factory_addr,
+                &context.astroport_factory_addr,
+                &Addr::unchecked(contract_addr),
+                collateral_amount,
+                None,
+            )?;
+            messages.push(swap_message);
+        }
+        AssetInfo::NativeToken { denom } => {
+            let pair_info = terraswap::querier::query_pair_info(
+                querier,
+                context.terraswap_factory_addr.clone(),
+                &[
+                    collateral_asset_info.clone(),
+                    AssetInfo::NativeToken {
+                        denom: String::from("uusd"),
+                    },
+                ],
+            )?;
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: pair_info.contract_addr,
+                msg: to_binary(&terraswap::pair::ExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: collateral_asset_info.clone(),
+                        amount: collateral_amount,
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    to: None,
+                })?,
+                funds: vec![Coin {
+                    denom: denom.clone(),
+                    amount: collateral_amount,
+                }],
+            }));
+        }
+    }
+    Ok(messages)
+}
+
+#[test]
+fn test_collateral_asset() {
+    use crate::mock_querier::{mock_context, mock_deps};
+    let deps = mock_deps(
+        "mock_cw20_addr",
+        Uint128::from(1000000u128),
+        Uint128::from(9000000u128),
+        Uint128::zero(),
+    );
+    let env = cosmwasm_std::testing::mock_env();
+    let context = mock_context();
+    let querier = QuerierWrapper::new(&deps.querier);
+    let cw20_collateral = AssetInfo::Token {
+        contract_addr: String::from("mock_cw20_addr"),
+    };
+    let uusd_collateral = AssetInfo::NativeToken {
+        denom: String::from("uusd"),
+    };
+
+    // Collateral registration in Mirror collateral oracle.
+    assert_eq!(
+        get_collateral_asset_info(&deps.storage, &context).unwrap(),
+        get_anchor_ust_asset_info(&context)
+    );
+    assert!(
+        validate_collateral_asset(&querier, &context, &get_anchor_ust_asset_info(&context)).is_ok()
+    );
+    assert!(validate_collateral_asset(&querier, &context, &cw20_collateral).is_ok());
+    assert_eq!(
+        validate_collateral_asset(&querier, &context, &uusd_collateral).unwrap_err(),
+        StdError::generic_err("collateral asset not registered in Mirror collateral oracle")
+    );
+    assert_eq!(
+        validate_collateral_asset(
+            &querier,
+            &context,
+            &AssetInfo::Token {
+                contract_addr: String::from("revoked_cw20"),
+            }
+        )
+        .unwrap_err(),
+        StdError::generic_err("collateral asset has been revoked")
+    );
+
+    // uusd -> collateral conversion.
+    let conversion =
+        UusdToCollateralConversion::new(deps.as_ref(), &env, &context, &cw20_collateral).unwrap();
+    assert_eq!(
+        conversion.simulate(Uint128::from(9000u128)),
+        simulate_terraswap_swap(
+            Uint128::from(9000000u128),
+            Uint128::from(1000000u128),
+            Uint128::from(9000u128)
+        )
+        .2
+    );
+    assert_eq!(
+        conversion
+            .create_messages(&context, Uint128::from(9000u128), Some(Decimal::percent(1)))
+            .unwrap(),
+        vec![create_uusd_swap_message(
+            LiquidityVenue::Terraswap,
+            "mock_terraswap_pair",
+            Uint128::from(9000u128),
+            Some(Decimal::percent(1))
+        )
+        .unwrap()]
+    );
+    let conversion =
+        UusdToCollateralConversion::new(deps.as_ref(), &env, &context, &uusd_collateral).unwrap();
+    assert_eq!(
+        conversion.simulate(Uint128::from(9000u128)),
+        Uint128::from(9000u128)
+    );
+    assert!(conversion
+        .create_messages(&context, Uint128::from(9000u128), None)
+        .unwrap()
+        .is_empty());
+
+    // Collateral -> uusd conversion.
+    let withdraw_message = |info: &AssetInfo| {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("mirror_mint"),
+            funds: vec![],
+            msg: to_binary(&mirror_protocol::mint::ExecuteMsg::Withdraw {
+                position_idx: Uint128::from(1u128),
+                collateral: Some(Asset {
+                    info: info.clone(),
+                    amount: Uint128::from(100u128),
+                }),
+            })
+            .unwrap(),
+        })
+    };
+    assert_eq!(
+        increase_uusd_balance_from_collateral(
+            &querier,
+            &context,
+            &get_anchor_ust_asset_info(&context),
+            Uint128::from(1u128),
+            Uint128::from(100u128)
+        )
+        .unwrap(),
+        vec![
+            withdraw_message(&get_anchor_ust_asset_info(&context)),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("anchor_ust_cw20"),
+                funds: vec![],
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: String::from("anchor_market"),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&moneymarket::market::Cw20HookMsg::RedeemStable {}).unwrap(),
+                })
+                .unwrap(),
+            })
+        ]
+    );
+    assert_eq!(
+        increase_uusd_balance_from_collateral(
+            &querier,
+            &context,
+            &uusd_collateral,
+            Uint128::from(1u128),
+            Uint128::from(100u128)
+        )
+        .unwrap(),
+        vec![withdraw_message(&uusd_collateral)]
+    );
+    assert_eq!(
+        increase_uusd_balance_from_collateral(
+            &querier,
+            &context,
+            &cw20_collateral,
+            Uint128::from(1u128),
+            Uint128::from(100u128)
+        )
+        .unwrap(),
+        vec![
+            withdraw_message(&cw20_collateral),
+            swap_cw20_token_for_uusd(
+                &querier,
+                &context.terraswap_factory_addr,
+                &context.astroport_factory_addr,
+                &Addr::unchecked("mock_cw20_addr"),
+                Uint128::from(100u128),
+                None
+            )
+            .unwrap()
+            .0
+        ]
+    );
+}
@@ -1,40 +1,61 @@
 use std::cmp::min;
-use std::str::FromStr;
 
-use crate::dex_util::compute_terraswap_liquidity_token_mint_amount;
+use crate::collateral_util::{
+    get_anchor_ust_asset_info, get_collateral_asset_info, increase_uusd_balance_from_collateral,
+    validate_collateral_asset,
+};
+use crate::dex_util::{
+    compute_terraswap_liquidity_token_mint_amount, create_provide_liquidity_messages,
+};
 use crate::math::{decimal_division, decimal_multiplication, reverse_decimal};
-use crate::open::delta_neutral_invest;
+use crate::open::{delta_neutral_invest, open_or_increase_cdp_with_collateral_balance};
 use crate::rebalance::achieve_delta_neutral;
-use crate::spectrum_util::check_spectrum_mirror_farm_existence;
+use crate::snapshot_util::{
+    create_position_snapshot_from_detailed_info, query_position_snapshots,
+    record_position_snapshot, should_record_rebalance_snapshot,
+};
+use crate::spectrum_util::{
+    check_spectrum_mirror_farm_existence, unstake_spec_from_spectrum_gov_and_swap_for_uusd,
+};
 use crate::state::{
-    CDP_IDX, CDP_PREEMPTIVELY_CLOSED, CODE_ID, LAST_FEE_COLLECTION_POSITION_UUSD_VALUE, MANAGER,
-    MIRROR_ASSET_CW20_ADDR, POSITION_CLOSE_INFO, POSITION_OPEN_INFO, TARGET_COLLATERAL_RATIO_RANGE,
+    CDP_IDX, CDP_PREEMPTIVELY_CLOSED, CODE_ID, COLLATERAL_ASSET_INFO,
+    LAST_FEE_COLLECTION_POSITION_UUSD_VALUE, LIQUIDITY_VENUE, MANAGER, MIRROR_ASSET_CW20_ADDR,
+    PARKED_ANCHOR_UST_AMOUNT, POSITION_CLOSE_INFO, POSITION_OPEN_INFO, SPEC_REWARD_POLICY,
+    TARGET_COLLATERAL_RATIO_RANGE,
 };
 use crate::util::{
-    get_cdp_uusd_lock_info_result, get_fee_collection_config_from_manager, get_position_state,
-    get_uusd_asset_from_amount, get_uusd_balance, get_uusd_coin_from_amount,
-    increase_mirror_asset_balance_from_long_farm, increase_uusd_balance_from_aust_collateral,
-    query_position_info, should_close_cdp, MIN_TARGET_CR_RANGE_WIDTH,
+    check_long_farm_existence, create_position_status_update_message,
+    get_cdp_uusd_lock_info_result, get_emergency_close_config_from_manager,
+    get_fee_collection_config_from_manager, get_mirror_asset_strategy_params,
+    get_parked_anchor_ust_amount, get_parked_anchor_ust_uusd_value, get_position_state,
+    get_position_state_at_pool_price, get_spec_reward_policy, get_uusd_asset_from_amount,
+    get_uusd_balance, get_uusd_coin_from_amount, increase_mirror_asset_balance_from_long_farm,
+    query_position_info, redeem_parked_anchor_ust, should_close_cdp, stake_lp_in_long_farm,
+};
+use aperture_common::anchor_util::{
+    get_anchor_ust_balance_with_uusd_value, get_anchor_ust_exchange_rate,
 };
-use aperture_common::anchor_util::get_anchor_ust_balance_with_uusd_value;
 use aperture_common::common::Recipient;
 use aperture_common::delta_neutral_position::{
-    ControllerExecuteMsg, ExecuteMsg, InstantiateMsg, InternalExecuteMsg, MigrateMsg,
-    PositionActionInfo, QueryMsg, TargetCollateralRatioRange,
+    ControllerExecuteMsg, ExecuteMsg, InstantiateMsg, InternalExecuteMsg, LiquidityVenue,
+    MigrateMsg, PositionActionInfo, PositionInfoResponse, PositionSnapshot, PositionSnapshotKind,
+    QueryMsg, SpecRewardPolicy, TargetCollateralRatioRange,
 };
 use aperture_common::delta_neutral_position_manager::QueryMsg as ManagerQueryMsg;
-use aperture_common::delta_neutral_position_manager::{self, Context, DeltaNeutralParams};
+use aperture_common::delta_neutral_position_manager::{
+    self, Context, DeltaNeutralParams, EmergencyCloseConfig, PositionStatus,
+};
 use aperture_common::mirror_util::{
     get_mirror_asset_config_response, get_mirror_asset_fresh_oracle_uusd_rate,
-    get_mirror_cdp_response,
+    get_mirror_asset_oracle_uusd_price_response, get_mirror_cdp_response,
 };
 use aperture_common::terra_manager;
+use cosmwasm_bignumber::Uint256;
 use cosmwasm_std::{
     entry_point, to_binary, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
     Response, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw_storage_plus::Item;
-use terraswap::asset::{Asset, AssetInfo};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -71,8 +92,12 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
     match msg {
         ExecuteMsg::OpenPosition { params } => open_position(deps, env, context, params),
         ExecuteMsg::ClosePosition { recipient } => {
-            close_position(deps.as_ref(), env, context, recipient)
+            close_position(deps, env, context, recipient, None)
+        }
+        ExecuteMsg::EmergencyClosePosition { recipient } => {
+            emergency_close_position(deps, env, context, recipient)
         }
+        ExecuteMsg::PreemptivelyCloseCdp {} => preemptively_close_cdp(deps, env),
         ExecuteMsg::Controller(controller_msg) => match controller_msg {
             ControllerExecuteMsg::RebalanceAndReinvest {} => {
                 rebalance_and_reinvest(deps, env, context)
@@ -83,7 +108,10 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                 achieve_safe_collateral_ratios(deps, env, context)
             }
             InternalExecuteMsg::CloseCdpAndDisburseUusd { recipient } => {
-                close_cdp_and_disburse_uusd(deps, env, context, recipient)
+                close_cdp_and_disburse_uusd(deps, env, context, recipient, false)
+            }
+            InternalExecuteMsg::EmergencyCloseCdpAndDisburseUusd { recipient } => {
+                close_cdp_and_disburse_uusd(deps, env, context, recipient, true)
             }
             InternalExecuteMsg::CloseCdpAndDepositToAnchorEarn {} => {
                 close_cdp_and_deposit_to_anchor_earn(deps, env, context)
@@ -91,6 +119,9 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             InternalExecuteMsg::DepositUusdBalanceToAnchorEarn {} => {
                 deposit_uusd_balance_to_anchor_earn(deps, env, context)
             }
+            InternalExecuteMsg::ParkUusdBalanceInAnchorEarn {} => {
+                park_uusd_balance_in_anchor_earn(deps, env, context)
+            }
             InternalExecuteMsg::WithdrawCollateralAndRedeemForUusd { proportion } => {
                 withdraw_collateral_and_redeem_for_uusd(deps.as_ref(), context, proportion)
             }
@@ -114,10 +145,16 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                     TARGET_COLLATERAL_RATIO_RANGE.load(deps.storage)?;
                 let uusd_amount = get_uusd_balance(&deps.querier, &env)?;
                 if uusd_amount >= context.min_reinvest_uusd_amount {
+                    let strategy_params = get_mirror_asset_strategy_params(
+                        deps.as_ref(),
+                        &context,
+                        &mirror_asset_cw20_addr,
+                    )?;
                     Ok(Response::new().add_messages(delta_neutral_invest(
                         deps,
                         &env,
                         context,
+                        &strategy_params,
                         uusd_amount,
                         &target_collateral_ratio_range,
                         &mirror_asset_cw20_addr,
@@ -128,6 +165,26 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                     Ok(Response::default())
                 }
             }
+            InternalExecuteMsg::OpenOrIncreaseCdpWithCollateralBalance {
+                collateral_balance_before,
+                max_collateral_amount,
+                collateral_ratio,
+                collateral_uusd_price,
+                mirror_asset_oracle_uusd_rate,
+                cdp_idx,
+                max_spread,
+            } => open_or_increase_cdp_with_collateral_balance(
+                deps.as_ref(),
+                env,
+                context,
+                collateral_balance_before,
+                max_collateral_amount,
+                collateral_ratio,
+                collateral_uusd_price,
+                mirror_asset_oracle_uusd_rate,
+                cdp_idx,
+                max_spread,
+            ),
             InternalExecuteMsg::OpenPositionSanityCheck {} => {
                 open_position_sanity_check(deps.as_ref(), env, context)
             }
@@ -157,7 +214,13 @@ pub fn open_position_sanity_check(deps: Deps, env: Env, context: Context) -> Std
         },
     )?;
     if cdp_response.is_short && mirror_asset_balance == cdp_response.asset.amount {
-        Ok(Response::default())
+        // The delta-neutral position has been set up, either upon position open or upon the first rebalance after an off-market open.
+        Ok(
+            Response::new().add_message(create_position_status_update_message(
+                deps.storage,
+                PositionStatus::Active,
+            )?),
+        )
     } else {
         Err(StdError::generic_err(format!(
             "unexpected non-neutral position opened: long amount {}, cdp response {:?}",
@@ -167,20 +230,33 @@ pub fn open_position_sanity_check(deps: Deps, env: Env, context: Context) -> Std
 }
 
 pub fn get_reinvest_internal_messages(
-    deps: Deps,
+    deps: DepsMut,
     env: &Env,
     context: &Context,
     fresh_oracle_uusd_rate: Decimal,
-) -> Vec<CosmosMsg> {
+) -> StdResult<Vec<CosmosMsg>> {
     // If there is still short proceeds pending unlock, we don't reinvest as this could reset the locking period.
-    if let Ok(lock_info_response) = get_cdp_uusd_lock_info_result(deps, context) {
+    // Instead, we pair uusd with liquid mAsset as LP, which leaves the CDP untouched, and park the remaining uusd in Anchor Earn until unlock.
+    if let Ok(lock_info_response) = get_cdp_uusd_lock_info_result(deps.as_ref(), context) {
         if !lock_info_response.locked_amount.is_zero()
             && lock_info_response.unlock_time > env.block.time.seconds()
         {
-            return vec![];
+            return Ok(vec![
+                create_internal_execute_message(
+                    env,
+                    InternalExecuteMsg::PairUusdWithMirrorAssetToProvideLiquidityAndStake {},
+                ),
+                create_internal_execute_message(
+                    env,
+                    InternalExecuteMsg::ParkUusdBalanceInAnchorEarn {},
+                ),
+            ]);
         }
     }
-    vec![
+
+    // Redeem uusd parked in Anchor Earn so that it is reinvested along with the rest of the uusd balance.
+    let mut messages = redeem_parked_anchor_ust(deps, env, context)?;
+    messages.extend(vec![
         create_internal_execute_message(
             env,
             InternalExecuteMsg::PairUusdWithMirrorAssetToProvideLiquidityAndStake {},
@@ -191,7 +267,8 @@ pub fn get_reinvest_internal_messages(
                 mirror_asset_fresh_oracle_uusd_rate: fresh_oracle_uusd_rate,
             },
         ),
-    ]
+    ]);
+    Ok(messages)
 }
 
 pub fn rebalance_and_reinvest(deps: DepsMut, env: Env, context: Context) -> StdResult<Response> {
@@ -203,39 +280,76 @@ pub fn rebalance_and_reinvest(deps: DepsMut, env: Env, context: Context) -> StdR
         return Ok(response);
     }
 
+    // Record the position state prior to this rebalance, at most once per snapshot interval.
+    // Valuation may fail if the CDP has been liquidated, in which case we skip the snapshot so that the position can still degenerate into Anchor Earn below.
+    if should_record_rebalance_snapshot(deps.storage, &env)? {
+        if let Ok(PositionInfoResponse {
+            detailed_info: Some(detailed_info),
+            ..
+        }) = query_position_info(deps.as_ref(), &env, &context)
+        {
+            record_position_snapshot(
+                deps.storage,
+                &create_position_snapshot_from_detailed_info(
+                    &env,
+                    PositionSnapshotKind::Rebalance,
+                    &detailed_info,
+                ),
+            )?;
+        }
+    }
+
+    let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
+    let fresh_oracle_uusd_rate =
+        get_mirror_asset_fresh_oracle_uusd_rate(&deps.querier, &context, &mirror_asset_cw20_addr);
+    let should_close_cdp = fresh_oracle_uusd_rate.is_some()
+        && should_close_cdp(
+            deps.as_ref(),
+            &context.mirror_mint_addr,
+            &mirror_asset_cw20_addr,
+        )?;
+
     let cdp_idx = CDP_IDX.may_load(deps.storage)?;
     if let Some(cdp_idx) = cdp_idx {
         // The CDP was active at the time of the previous `rebalance_and_reinvest` execution.
-        response = response.add_messages(achieve_delta_neutral(deps.as_ref(), &env, &context)?);
+        // Limit orders are priced at the oracle price, so we only submit them if the price is fresh and the CDP is not about to be closed.
+        response = response.add_messages(achieve_delta_neutral(
+            deps.as_ref(),
+            &env,
+            &context,
+            fresh_oracle_uusd_rate.is_some() && !should_close_cdp,
+            None,
+        )?);
 
         if get_mirror_cdp_response(&deps.querier, &context, cdp_idx).is_err() {
             // The CDP most likely has been fully closed due to liquidation, so we degenerate this position into pure Anchor Earn.
             CDP_PREEMPTIVELY_CLOSED.save(deps.storage, &true)?;
-            return Ok(response.add_message(create_internal_execute_message(
-                &env,
-                InternalExecuteMsg::CloseCdpAndDepositToAnchorEarn {},
-            )));
+            return Ok(response
+                .add_message(create_internal_execute_message(
+                    &env,
+                    InternalExecuteMsg::CloseCdpAndDepositToAnchorEarn {},
+                ))
+                .add_message(create_position_status_update_message(
+                    deps.storage,
+                    PositionStatus::CdpPreemptivelyClosed,
+                )?));
         }
     }
 
-    let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
-    let fresh_oracle_uusd_rate =
-        get_mirror_asset_fresh_oracle_uusd_rate(&deps.querier, &context, &mirror_asset_cw20_addr);
-
     // Since the operations below require fresh oracle price in order to modify the CDP, we skip these steps if the current oracle price is not fresh.
     if let Some(rate) = fresh_oracle_uusd_rate {
-        let should_close_cdp = should_close_cdp(
-            deps.as_ref(),
-            &context.mirror_mint_addr,
-            &mirror_asset_cw20_addr,
-        )?;
         if cdp_idx.is_some() {
             if should_close_cdp {
                 CDP_PREEMPTIVELY_CLOSED.save(deps.storage, &true)?;
-                return Ok(response.add_message(create_internal_execute_message(
-                    &env,
-                    InternalExecuteMsg::CloseCdpAndDepositToAnchorEarn {},
-                )));
+                return Ok(response
+                    .add_message(create_internal_execute_message(
+                        &env,
+                        InternalExecuteMsg::CloseCdpAndDepositToAnchorEarn {},
+                    ))
+                    .add_message(create_position_status_update_message(
+                        deps.storage,
+                        PositionStatus::CdpPreemptivelyClosed,
+                    )?));
             }
 
             Ok(response
@@ -243,22 +357,22 @@ pub fn rebalance_and_reinvest(deps: DepsMut, env: Env, context: Context) -> StdR
                     &env,
                     InternalExecuteMsg::AchieveSafeCollateralRatio {},
                 ))
-                .add_messages(get_reinvest_internal_messages(
-                    deps.as_ref(),
-                    &env,
-                    &context,
-                    rate,
-                )))
+                .add_messages(get_reinvest_internal_messages(deps, &env, &context, rate)?))
         } else {
             // Position was opened when oracle price was stale. However, since then the mAsset has been added to the should-close list by the admin, or if the mAsset has been delisted from Mirror.
             // Thus, we set CDP_PREEMPTIVELY_CLOSED and do nothing else to keep funds in Anchor Earn.
             if should_close_cdp {
                 CDP_PREEMPTIVELY_CLOSED.save(deps.storage, &true)?;
-                return Ok(response);
+                return Ok(response.add_message(create_position_status_update_message(
+                    deps.storage,
+                    PositionStatus::CdpPreemptivelyClosed,
+                )?));
             }
 
             // Position was opened when oracle price was stale. Now that oracle price is fresh, we set up the DN position.
             let target_collateral_ratio_range = TARGET_COLLATERAL_RATIO_RANGE.load(deps.storage)?;
+            let strategy_params =
+                get_mirror_asset_strategy_params(deps.as_ref(), &context, &mirror_asset_cw20_addr)?;
             let (anchor_ust_balance, uusd_value) = get_anchor_ust_balance_with_uusd_value(
                 deps.as_ref(),
                 &env,
@@ -279,6 +393,7 @@ pub fn rebalance_and_reinvest(deps: DepsMut, env: Env, context: Context) -> StdR
                     deps,
                     &env,
                     context,
+                    &strategy_params,
                     uusd_value,
                     &target_collateral_ratio_range,
                     &mirror_asset_cw20_addr,
@@ -310,23 +425,30 @@ pub fn achieve_safe_collateral_ratios(
 
     // Increase target CR range if the current minimum required has been raised by Mirror governance.
     let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
+    let strategy_params =
+        get_mirror_asset_strategy_params(deps.as_ref(), &context, &mirror_asset_cw20_addr)?;
     let mirror_asset_config_response = get_mirror_asset_config_response(
         &deps.querier,
         &context.mirror_mint_addr,
         mirror_asset_cw20_addr.as_str(),
     )?;
+    // Mirror scales the minimum required by the collateral multiplier, which may also be raised by Mirror governance.
+    let min_collateral_ratio = decimal_multiplication(
+        mirror_asset_config_response.min_collateral_ratio,
+        state.collateral_multiplier,
+    );
     if target_collateral_ratio_range.min
-        < mirror_asset_config_response.min_collateral_ratio + context.collateral_ratio_safety_margin
+        < min_collateral_ratio + strategy_params.collateral_ratio_safety_margin
     {
         // Update `target_collateral_ratio_range.min` to the new, higher value.
-        target_collateral_ratio_range.min = mirror_asset_config_response.min_collateral_ratio
-            + context.collateral_ratio_safety_margin;
+        target_collateral_ratio_range.min =
+            min_collateral_ratio + strategy_params.collateral_ratio_safety_margin;
 
         // Update `target_collateral_ratio_range.max` if the new minimum plus the required width is higher.
-        let min_target_collater_ratio_range_width = Decimal::from_str(MIN_TARGET_CR_RANGE_WIDTH)?;
         target_collateral_ratio_range.max = std::cmp::max(
             target_collateral_ratio_range.max,
-            target_collateral_ratio_range.min + min_target_collater_ratio_range_width,
+            target_collateral_ratio_range.min
+                + strategy_params.min_target_collateral_ratio_range_width,
         );
 
         // Save updated target CR range.
@@ -346,7 +468,7 @@ pub fn achieve_safe_collateral_ratios(
             state.mirror_asset_short_amount - target_short_mirror_asset_amount;
         response = response.add_messages(increase_mirror_asset_balance_from_long_farm(
             &state,
-            &context.spectrum_mirror_farms_addr,
+            &context,
             &mirror_asset_cw20_addr,
             burn_mirror_asset_amount,
         ));
@@ -362,7 +484,7 @@ pub fn achieve_safe_collateral_ratios(
             })?,
         }));
     } else if collateral_ratio > target_collateral_ratio_range.max {
-        // If there is short proceeds pending unlock in the CDP, we do not withdraw aUST collateral from the CDP.
+        // If there is short proceeds pending unlock in the CDP, we do not withdraw collateral from the CDP.
         let cdp_uusd_lock_info = get_cdp_uusd_lock_info_result(deps.as_ref(), &context);
         if let Ok(cdp_uusd_lock_info) = cdp_uusd_lock_info {
             if !cdp_uusd_lock_info.locked_amount.is_zero() {
@@ -370,21 +492,22 @@ pub fn achieve_safe_collateral_ratios(
             }
         }
 
-        let target_anchor_ust_collateral_amount = state.mirror_asset_short_amount
+        let target_collateral_amount = state.mirror_asset_short_amount
             * state.mirror_asset_oracle_price
             * decimal_division(
                 target_collateral_ratio_range.midpoint(),
-                state.anchor_ust_oracle_price,
+                state.collateral_oracle_price,
             );
 
-        // Withdraw aUST collateral and redeem for UST.
-        let withdraw_anchor_ust_collateral_amount =
-            state.collateral_anchor_ust_amount - target_anchor_ust_collateral_amount;
-        response = response.add_messages(increase_uusd_balance_from_aust_collateral(
+        // Withdraw collateral and convert to UST.
+        let withdraw_collateral_amount = state.collateral_amount - target_collateral_amount;
+        response = response.add_messages(increase_uusd_balance_from_collateral(
+            &deps.querier,
             &context,
+            &get_collateral_asset_info(deps.storage, &context)?,
             CDP_IDX.load(deps.storage)?,
-            withdraw_anchor_ust_collateral_amount,
-        ));
+            withdraw_collateral_amount,
+        )?);
     }
     Ok(response)
 }
@@ -399,8 +522,11 @@ pub fn open_position(
         return Err(StdError::generic_err("position is already open"));
     }
 
+    let mirror_asset_cw20_addr = deps.api.addr_validate(&params.mirror_asset_cw20_addr)?;
+    let strategy_params =
+        get_mirror_asset_strategy_params(deps.as_ref(), &context, &mirror_asset_cw20_addr)?;
     let uusd_balance = get_uusd_balance(&deps.querier, &env)?;
-    if uusd_balance < context.min_open_uusd_amount {
+    if uusd_balance < strategy_params.min_open_uusd_amount {
         return Err(StdError::generic_err(
             "UST amount too small to open a delta-neutral position",
         ));
@@ -415,8 +541,21 @@ pub fn open_position(
             uusd_amount: uusd_balance,
         },
     )?;
+    record_position_snapshot(
+        deps.storage,
+        &PositionSnapshot {
+            kind: PositionSnapshotKind::Open,
+            height: env.block.height,
+            time_nanoseconds: env.block.time.nanos(),
+            uusd_value: uusd_balance,
+            collateral_ratio: None,
+            mirror_asset_long_amount: Uint128::zero(),
+            mirror_asset_short_amount: Uint128::zero(),
+            rewards_harvested_uusd_value: Uint128::zero(),
+            fees_charged_uusd_amount: Uint128::zero(),
+        },
+    )?;
 
-    let mirror_asset_cw20_addr = deps.api.addr_validate(&params.mirror_asset_cw20_addr)?;
     MIRROR_ASSET_CW20_ADDR.save(deps.storage, &mirror_asset_cw20_addr)?;
 
     let target_collateral_ratio_range = TargetCollateralRatioRange {
@@ -424,6 +563,21 @@ pub fn open_position(
         max: params.target_max_collateral_ratio,
     };
     TARGET_COLLATERAL_RATIO_RANGE.save(deps.storage, &target_collateral_ratio_range)?;
+    LIQUIDITY_VENUE.save(
+        deps.storage,
+        &params.liquidity_venue.unwrap_or(LiquidityVenue::Terraswap),
+    )?;
+
+    SPEC_REWARD_POLICY.save(
+        deps.storage,
+        &params.spec_reward_policy.unwrap_or(SpecRewardPolicy::Sell),
+    )?;
+
+    let collateral_asset_info = params
+        .collateral_asset_info
+        .unwrap_or_else(|| get_anchor_ust_asset_info(&context));
+    validate_collateral_asset(&deps.querier, &context, &collateral_asset_info)?;
+    COLLATERAL_ASSET_INFO.save(deps.storage, &collateral_asset_info)?;
 
     let fresh_oracle_uusd_rate =
         get_mirror_asset_fresh_oracle_uusd_rate(&deps.querier, &context, &mirror_asset_cw20_addr);
@@ -440,6 +594,7 @@ pub fn open_position(
                 deps,
                 &env,
                 context,
+                &strategy_params,
                 uusd_balance,
                 &target_collateral_ratio_range,
                 &mirror_asset_cw20_addr,
@@ -478,16 +633,62 @@ pub fn open_position(
     }
 }
 
+// Checks that the mAsset oracle price, last updated at `oracle_last_updated_seconds` (None if the oracle cannot be queried), has been stale long enough for an emergency close.
+fn check_emergency_close_allowed(
+    env: &Env,
+    config: &EmergencyCloseConfig,
+    oracle_last_updated_seconds: Option<u64>,
+) -> StdResult<()> {
+    if let Some(last_updated) = oracle_last_updated_seconds {
+        let stale_seconds = env.block.time.seconds().saturating_sub(last_updated);
+        if stale_seconds < config.min_oracle_stale_seconds {
+            return Err(StdError::generic_err(format!(
+                "oracle price has been stale for {} seconds; emergency close requires {}",
+                stale_seconds, config.min_oracle_stale_seconds
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn emergency_close_position(
+    deps: DepsMut,
+    env: Env,
+    context: Context,
+    recipient: Recipient,
+) -> StdResult<Response> {
+    let config = get_emergency_close_config_from_manager(deps.as_ref())?;
+    let oracle_last_updated_seconds = get_mirror_asset_oracle_uusd_price_response(
+        &deps.querier,
+        &context,
+        &MIRROR_ASSET_CW20_ADDR.load(deps.storage)?,
+    )
+    .ok()
+    .map(|price_response| price_response.last_updated);
+    check_emergency_close_allowed(&env, &config, oracle_last_updated_seconds)?;
+    close_position(deps, env, context, recipient, Some(&config))
+}
+
+// If `emergency_close_config` is specified, the position is unwound at pool prices with swaps bounded by `EmergencyCloseConfig.max_haircut`.
 pub fn close_position(
-    deps: Deps,
+    mut deps: DepsMut,
     env: Env,
     context: Context,
     recipient: Recipient,
+    emergency_close_config: Option<&EmergencyCloseConfig>,
 ) -> StdResult<Response> {
     if POSITION_CLOSE_INFO.may_load(deps.storage)?.is_some() {
         return Err(StdError::generic_err("position is already closed"));
     }
 
+    // Unstake SPEC from Spectrum governance and sell it so that the proceeds are disbursed along with the rest of the position.
+    let unstake_spec_messages = match get_spec_reward_policy(deps.storage)? {
+        SpecRewardPolicy::Sell => vec![],
+        SpecRewardPolicy::Stake => {
+            unstake_spec_from_spectrum_gov_and_swap_for_uusd(&deps.querier, &env, &context)?.0
+        }
+    };
+
     // If the CDP is not active (either the CDP has not yet been set up, or the CDP has been preemptively closed), then all funds are invested in Anchor Earn, so we redeem aUST for UST and disburse.
     if CDP_IDX.may_load(deps.storage)?.is_none()
         || CDP_PREEMPTIVELY_CLOSED.may_load(deps.storage)? == Some(true)
@@ -498,6 +699,7 @@ pub fn close_position(
             env.contract.address.clone(),
         )?;
         return Ok(Response::new()
+            .add_messages(unstake_spec_messages)
             .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: context.anchor_ust_cw20_addr.to_string(),
                 msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
@@ -517,10 +719,22 @@ pub fn close_position(
     }
 
     Ok(Response::new()
-        .add_messages(achieve_delta_neutral(deps, &env, &context)?)
+        .add_messages(unstake_spec_messages)
+        .add_messages(redeem_parked_anchor_ust(deps.branch(), &env, &context)?)
+        // Outstanding limit orders are cancelled, and the position is brought to delta-neutral with pool swaps so that the CDP can be closed right after.
+        .add_messages(achieve_delta_neutral(
+            deps.as_ref(),
+            &env,
+            &context,
+            false,
+            emergency_close_config,
+        )?)
         .add_message(create_internal_execute_message(
             &env,
-            InternalExecuteMsg::CloseCdpAndDisburseUusd { recipient },
+            match emergency_close_config {
+                Some(_) => InternalExecuteMsg::EmergencyCloseCdpAndDisburseUusd { recipient },
+                None => InternalExecuteMsg::CloseCdpAndDisburseUusd { recipient },
+            },
         )))
 }
 
@@ -531,6 +745,7 @@ pub fn send_uusd_to_recipient(
     recipient: Recipient,
 ) -> StdResult<Response> {
     let amount = get_uusd_balance(&deps.querier, &env)? * proportion;
+    let mut response = Response::new();
 
     // Record POSITION_CLOSE_INFO if the position is being closed.
     if proportion == Decimal::one() {
@@ -542,10 +757,14 @@ pub fn send_uusd_to_recipient(
                 uusd_amount: amount,
             },
         )?;
+        response = response.add_message(create_position_status_update_message(
+            deps.storage,
+            PositionStatus::Closed,
+        )?);
     }
 
     if amount.is_zero() {
-        return Ok(Response::default());
+        return Ok(response);
     }
 
     // Initiate transfer of `amount` uusd to the recipient.
@@ -554,16 +773,14 @@ pub fn send_uusd_to_recipient(
             MANAGER.load(deps.storage)?,
             &delta_neutral_position_manager::QueryMsg::GetAdminConfig {},
         )?;
-    Ok(
-        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: position_manager_admin_config.terra_manager.to_string(),
-            msg: to_binary(&terra_manager::ExecuteMsg::InitiateOutgoingTokenTransfer {
-                assets: vec![get_uusd_asset_from_amount(amount)],
-                recipient,
-            })?,
-            funds: vec![get_uusd_coin_from_amount(amount)],
-        })),
-    )
+    Ok(response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: position_manager_admin_config.terra_manager.to_string(),
+        msg: to_binary(&terra_manager::ExecuteMsg::InitiateOutgoingTokenTransfer {
+            assets: vec![get_uusd_asset_from_amount(amount)],
+            recipient,
+        })?,
+        funds: vec![get_uusd_coin_from_amount(amount)],
+    })))
 }
 
 pub fn withdraw_collateral_and_redeem_for_uusd(
@@ -580,26 +797,38 @@ pub fn withdraw_collateral_and_redeem_for_uusd(
             },
         )?;
     Ok(
-        Response::new().add_messages(increase_uusd_balance_from_aust_collateral(
+        Response::new().add_messages(increase_uusd_balance_from_collateral(
+            &deps.querier,
             &context,
+            &position_response.collateral.info,
             cdp_idx,
             position_response.collateral.amount * proportion,
-        )),
+        )?),
     )
 }
 
+// If `use_pool_price` is true, the mAsset is valued at the pool price instead of the oracle price.
 fn close_cdp_and_collect_fees(
     deps: DepsMut,
     env: &Env,
     context: Context,
+    use_pool_price: bool,
 ) -> StdResult<Vec<CosmosMsg>> {
-    let state = get_position_state(deps.as_ref(), env, &context)?;
+    let state = if use_pool_price {
+        get_position_state_at_pool_price(deps.as_ref(), env, &context)?
+    } else {
+        get_position_state(deps.as_ref(), env, &context)?
+    };
     let mut position_value =
         state.collateral_uusd_value + state.uusd_balance + state.uusd_long_farm;
     if let Ok(lock_info_response) = get_cdp_uusd_lock_info_result(deps.as_ref(), &context) {
         // There may still be uusd short sale proceeds locked in the CDP, so we add this amount to position value.
         position_value += lock_info_response.locked_amount;
     }
+    // There may be uusd parked in Anchor Earn pending unlock of short proceeds.
+    // Once the CDP is closed, any remaining aUST is no longer parked but part of the funds kept in Anchor Earn.
+    position_value += get_parked_anchor_ust_uusd_value(deps.as_ref(), env, &context)?;
+    PARKED_ANCHOR_UST_AMOUNT.remove(deps.storage);
 
     let last_fee_collection_position_uusd_value =
         LAST_FEE_COLLECTION_POSITION_UUSD_VALUE.load(deps.storage)?;
@@ -618,7 +847,7 @@ fn close_cdp_and_collect_fees(
         let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
         messages.extend(increase_mirror_asset_balance_from_long_farm(
             &state,
-            &context.spectrum_mirror_farms_addr,
+            &context,
             &mirror_asset_cw20_addr,
             state.mirror_asset_short_amount,
         ));
@@ -652,6 +881,27 @@ fn close_cdp_and_collect_fees(
         LAST_FEE_COLLECTION_POSITION_UUSD_VALUE
             .save(deps.storage, &(position_value - fee_amount))?;
     }
+    record_position_snapshot(
+        deps.storage,
+        &PositionSnapshot {
+            kind: PositionSnapshotKind::FeeCollection,
+            height: env.block.height,
+            time_nanoseconds: env.block.time.nanos(),
+            uusd_value: position_value - fee_amount,
+            collateral_ratio: if state.mirror_asset_short_amount.is_zero() {
+                None
+            } else {
+                Some(Decimal::from_ratio(
+                    state.collateral_uusd_value,
+                    state.mirror_asset_short_amount * state.mirror_asset_oracle_price,
+                ))
+            },
+            mirror_asset_long_amount: state.mirror_asset_long_amount,
+            mirror_asset_short_amount: state.mirror_asset_short_amount,
+            rewards_harvested_uusd_value: Uint128::zero(),
+            fees_charged_uusd_amount: fee_amount,
+        },
+    )?;
 
     Ok(messages)
 }
@@ -662,9 +912,15 @@ pub fn close_cdp_and_disburse_uusd(
     env: Env,
     context: Context,
     recipient: Recipient,
+    use_pool_price: bool,
 ) -> StdResult<Response> {
     Ok(Response::new()
-        .add_messages(close_cdp_and_collect_fees(deps, &env, context)?)
+        .add_messages(close_cdp_and_collect_fees(
+            deps,
+            &env,
+            context,
+            use_pool_price,
+        )?)
         .add_message(create_internal_execute_message(
             &env,
             InternalExecuteMsg::SendUusdToRecipient {
@@ -675,13 +931,35 @@ pub fn close_cdp_and_disburse_uusd(
 }
 
 // Closes the CDP, send Aperture fees to the collector, and deposit the remaining funds into Anchor Earn.
+pub fn preemptively_close_cdp(deps: DepsMut, env: Env) -> StdResult<Response> {
+    if POSITION_CLOSE_INFO.may_load(deps.storage)?.is_some()
+        || CDP_PREEMPTIVELY_CLOSED.may_load(deps.storage)? == Some(true)
+    {
+        return Ok(Response::default());
+    }
+    CDP_PREEMPTIVELY_CLOSED.save(deps.storage, &true)?;
+
+    // A position opened when oracle price was stale has no CDP yet; its funds are already in Anchor Earn.
+    let mut response = Response::new();
+    if CDP_IDX.may_load(deps.storage)?.is_some() {
+        response = response.add_message(create_internal_execute_message(
+            &env,
+            InternalExecuteMsg::CloseCdpAndDepositToAnchorEarn {},
+        ));
+    }
+    Ok(response.add_message(create_position_status_update_message(
+        deps.storage,
+        PositionStatus::CdpPreemptivelyClosed,
+    )?))
+}
+
 pub fn close_cdp_and_deposit_to_anchor_earn(
     deps: DepsMut,
     env: Env,
     context: Context,
 ) -> StdResult<Response> {
     Ok(Response::new()
-        .add_messages(close_cdp_and_collect_fees(deps, &env, context)?)
+        .add_messages(close_cdp_and_collect_fees(deps, &env, context, false)?)
         .add_message(create_internal_execute_message(
             &env,
             InternalExecuteMsg::DepositUusdBalanceToAnchorEarn {},
@@ -703,6 +981,33 @@ pub fn deposit_uusd_balance_to_anchor_earn(
     )
 }
 
+// Parks the uusd balance in Anchor Earn while short proceeds are pending unlock; see `get_reinvest_internal_messages()`.
+pub fn park_uusd_balance_in_anchor_earn(
+    deps: DepsMut,
+    env: Env,
+    context: Context,
+) -> StdResult<Response> {
+    let uusd_balance = get_uusd_balance(&deps.querier, &env)?;
+    if uusd_balance < context.min_reinvest_uusd_amount {
+        return Ok(Response::default());
+    }
+    // Anchor mints aUST at the current exchange rate.
+    let anchor_ust_mint_amount = Uint128::from(
+        Uint256::from(uusd_balance)
+            / get_anchor_ust_exchange_rate(deps.as_ref(), &env, &context.anchor_market_addr)?,
+    );
+    let parked_anchor_ust_amount =
+        get_parked_anchor_ust_amount(deps.storage)?.checked_add(anchor_ust_mint_amount)?;
+    PARKED_ANCHOR_UST_AMOUNT.save(deps.storage, &parked_anchor_ust_amount)?;
+    Ok(
+        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: context.anchor_market_addr.to_string(),
+            msg: to_binary(&moneymarket::market::ExecuteMsg::DepositStable {})?,
+            funds: vec![get_uusd_coin_from_amount(uusd_balance)],
+        })),
+    )
+}
+
 pub fn pair_uusd_with_mirror_asset_to_provide_liquidity_and_stake(
     deps: Deps,
     env: Env,
@@ -711,90 +1016,58 @@ pub fn pair_uusd_with_mirror_asset_to_provide_liquidity_and_stake(
     let state = get_position_state(deps, &env, &context)?;
     let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
 
-    // Stop if either UST or mAsset balance is zero, or if the Spectrum mAsset-UST vault doesn't exist.
+    // Stop if either UST or mAsset balance is zero, or if the long farm (Spectrum mAsset-UST vault or Mirror staking) doesn't accept the LP token.
     if state.mirror_asset_balance.is_zero()
         || state.uusd_balance.is_zero()
-        || !check_spectrum_mirror_farm_existence(deps, &context, &mirror_asset_cw20_addr)
+        || !check_long_farm_existence(deps, &context, &state.pool_info, &mirror_asset_cw20_addr)
     {
         return Ok(Response::default());
     }
 
     // Find amount of uusd and mAsset to pair together and provide liquidity.
-    let info = &state.terraswap_pool_info;
-    let uusd_ratio = Decimal::from_ratio(state.uusd_balance, info.terraswap_pool_uusd_amount);
-    let mirror_asset_ratio = Decimal::from_ratio(
-        state.mirror_asset_balance,
-        info.terraswap_pool_mirror_asset_amount,
-    );
+    let info = &state.pool_info;
+    let uusd_ratio = Decimal::from_ratio(state.uusd_balance, info.pool_uusd_amount);
+    let mirror_asset_ratio =
+        Decimal::from_ratio(state.mirror_asset_balance, info.pool_mirror_asset_amount);
     let ratio = min(uusd_ratio, mirror_asset_ratio);
-    let uusd_provide_amount = info.terraswap_pool_uusd_amount * ratio;
-    let mirror_asset_provide_amount = info.terraswap_pool_mirror_asset_amount * ratio;
+    let uusd_provide_amount = info.pool_uusd_amount * ratio;
+    let mirror_asset_provide_amount = info.pool_mirror_asset_amount * ratio;
 
     // Stop if either the calculated UST or mAsset provide amount is zero due to rounding.
     if uusd_provide_amount.is_zero() || mirror_asset_provide_amount.is_zero() {
         return Ok(Response::default());
     }
 
-    // Find amount of Terraswap mAsset-UST LP tokens that will be minted and returned to us for providing liquidity.
+    // Find amount of mAsset-UST LP tokens that will be minted and returned to us for providing liquidity.
+    // Astroport XYK pairs mint LP tokens in the same way as Terraswap pairs.
     let return_lp_token_amount = compute_terraswap_liquidity_token_mint_amount(
         uusd_provide_amount,
         mirror_asset_provide_amount,
-        info.terraswap_pool_uusd_amount,
-        info.terraswap_pool_mirror_asset_amount,
+        info.pool_uusd_amount,
+        info.pool_mirror_asset_amount,
         info.lp_token_total_supply,
     );
     if return_lp_token_amount.is_zero() {
-        // Stop if `return_lp_token_amount` is zero; otherwise terraswap::pair::ExecuteMsg::ProvideLiquidity would fail with InvalidZeroAmount error.
+        // Stop if `return_lp_token_amount` is zero; otherwise ProvideLiquidity would fail with InvalidZeroAmount error.
         return Ok(Response::default());
     }
 
-    Ok(Response::new().add_messages(vec![
-        // Allow Terraswap mAsset-UST pair contract to transfer mAsset tokens from us.
-        CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: mirror_asset_cw20_addr.to_string(),
-            msg: to_binary(&cw20::Cw20ExecuteMsg::IncreaseAllowance {
-                spender: info.terraswap_pair_addr.clone(),
-                amount: mirror_asset_provide_amount,
-                expires: None,
-            })?,
-            funds: vec![],
-        }),
-        // Provide liquidity to Terraswap mAsset-UST pool.
-        CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: info.terraswap_pair_addr.clone(),
-            msg: to_binary(&terraswap::pair::ExecuteMsg::ProvideLiquidity {
-                assets: [
-                    Asset {
-                        info: AssetInfo::Token {
-                            contract_addr: mirror_asset_cw20_addr.to_string(),
-                        },
-                        amount: mirror_asset_provide_amount,
-                    },
-                    get_uusd_asset_from_amount(uusd_provide_amount),
-                ],
-                slippage_tolerance: None,
-                receiver: None,
-            })?,
-            funds: vec![get_uusd_coin_from_amount(uusd_provide_amount)],
-        }),
-        // Stake Terraswap LP tokens at Spectrum Mirror Vault.
-        // Note that Spectrum contract will round the deposit fee down; at 0.1% fee rate, an LP deposit amount of <= 999 will result in no fees being deducted as the fee amount rounds down to zero.
-        // Thus, as long as `return_lp_token_amount` is positive, the after-fee amount is never zero.
-        // Reference: https://github.com/spectrumprotocol/contracts/blob/ddf4a90794ccba45d1781b96b49787eed3d43ff4/contracts/farms/spectrum_mirror_farm/src/bond.rs#L67
-        CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: info.lp_token_cw20_addr.clone(),
-            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
-                contract: context.spectrum_mirror_farms_addr.to_string(),
-                amount: return_lp_token_amount,
-                msg: to_binary(&spectrum_protocol::mirror_farm::Cw20HookMsg::bond {
-                    asset_token: mirror_asset_cw20_addr.to_string(),
-                    compound_rate: Some(Decimal::one()),
-                    staker_addr: None,
-                })?,
-            })?,
-            funds: vec![],
-        }),
-    ]))
+    Ok(Response::new()
+        // Allow the mAsset-UST pair contract to transfer mAsset tokens from us, and provide liquidity.
+        .add_messages(create_provide_liquidity_messages(
+            info.venue,
+            &info.pair_addr,
+            &mirror_asset_cw20_addr,
+            mirror_asset_provide_amount,
+            uusd_provide_amount,
+        )?)
+        // Stake LP tokens at Spectrum Mirror Vault (Terraswap) or Mirror staking (Astroport).
+        .add_message(stake_lp_in_long_farm(
+            &context,
+            info,
+            &mirror_asset_cw20_addr,
+            return_lp_token_amount,
+        )?))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -812,6 +1085,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             &context,
             &deps.api.addr_validate(&mirror_asset_cw20_addr)?,
         )),
+        QueryMsg::GetPositionSnapshots { start_after, limit } => {
+            to_binary(&query_position_snapshots(deps.storage, start_after, limit)?)
+        }
     }
 }
 
@@ -820,3 +1096,129 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response>
     CODE_ID.save(deps.storage, &msg.new_code_id)?;
     Ok(Response::default())
 }
+
+#[test]
+fn test_get_reinvest_internal_messages() {
+    use crate::mock_querier::{mock_context, mock_deps};
+    use cosmwasm_std::testing::mock_env;
+    use cosmwasm_std::{Addr, Timestamp};
+
+    // The mock cw20 token is aUST so that the mock cw20 balance query returns the aUST balance of 30, which also includes aUST that is not parked.
+    let mut deps = mock_deps(
+        "anchor_ust_cw20",
+        Uint128::from(30u128),
+        Uint128::from(9000000u128),
+        Uint128::from(1u128),
+    );
+    CDP_IDX
+        .save(deps.as_mut().storage, &Uint128::from(1u128))
+        .unwrap();
+    let context = mock_context();
+    let rate = Decimal::from_ratio(10u128, 1u128);
+    let redeem_message = |amount: u128| {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("anchor_ust_cw20"),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: String::from("anchor_market"),
+                amount: Uint128::from(amount),
+                msg: to_binary(&moneymarket::market::Cw20HookMsg::RedeemStable {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+    let reinvest_messages = |env: &Env| {
+        vec![
+            create_internal_execute_message(
+                env,
+                InternalExecuteMsg::PairUusdWithMirrorAssetToProvideLiquidityAndStake {},
+            ),
+            create_internal_execute_message(
+                env,
+                InternalExecuteMsg::DeltaNeutralReinvest {
+                    mirror_asset_fresh_oracle_uusd_rate: rate,
+                },
+            ),
+        ]
+    };
+
+    // Short proceeds are locked until 12345; uusd is paired as LP and the rest is parked in Anchor Earn.
+    let mut env = mock_env();
+    env.contract.address = Addr::unchecked("this");
+    env.block.time = Timestamp::from_seconds(12344);
+    assert_eq!(
+        get_reinvest_internal_messages(deps.as_mut(), &env, &context, rate).unwrap(),
+        vec![
+            create_internal_execute_message(
+                &env,
+                InternalExecuteMsg::PairUusdWithMirrorAssetToProvideLiquidityAndStake {},
+            ),
+            create_internal_execute_message(
+                &env,
+                InternalExecuteMsg::ParkUusdBalanceInAnchorEarn {}
+            ),
+        ]
+    );
+
+    // Parking the uusd balance of 10 twice at an exchange rate of 1.1 yields 9 aUST each time.
+    park_uusd_balance_in_anchor_earn(deps.as_mut(), env.clone(), context.clone()).unwrap();
+    park_uusd_balance_in_anchor_earn(deps.as_mut(), env.clone(), context.clone()).unwrap();
+    assert_eq!(
+        get_parked_anchor_ust_amount(deps.as_ref().storage),
+        Ok(Uint128::from(18u128))
+    );
+    assert_eq!(
+        get_parked_anchor_ust_uusd_value(deps.as_ref(), &env, &context),
+        Ok(Uint128::from(19u128))
+    );
+
+    // Short proceeds are unlocked; only the parked aUST is redeemed and reinvested.
+    env.block.time = Timestamp::from_seconds(12345);
+    let mut expected_messages = vec![redeem_message(18)];
+    expected_messages.extend(reinvest_messages(&env));
+    assert_eq!(
+        get_reinvest_internal_messages(deps.as_mut(), &env, &context, rate).unwrap(),
+        expected_messages
+    );
+    assert_eq!(
+        get_parked_anchor_ust_amount(deps.as_ref().storage),
+        Ok(Uint128::zero())
+    );
+    assert_eq!(
+        get_reinvest_internal_messages(deps.as_mut(), &env, &context, rate).unwrap(),
+        reinvest_messages(&env)
+    );
+
+    // The redeemed amount never exceeds the aUST balance.
+    PARKED_ANCHOR_UST_AMOUNT
+        .save(deps.as_mut().storage, &Uint128::from(50u128))
+        .unwrap();
+    let mut expected_messages = vec![redeem_message(30)];
+    expected_messages.extend(reinvest_messages(&env));
+    assert_eq!(
+        get_reinvest_internal_messages(deps.as_mut(), &env, &context, rate).unwrap(),
+        expected_messages
+    );
+}
+
+#[test]
+fn test_check_emergency_close_allowed() {
+    use cosmwasm_std::testing::mock_env;
+    use cosmwasm_std::Timestamp;
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(100000);
+    let config = EmergencyCloseConfig {
+        min_oracle_stale_seconds: 3600,
+        max_haircut: Decimal::percent(5),
+    };
+    assert_eq!(
+        check_emergency_close_allowed(&env, &config, Some(100000 - 3599)),
+        Err(StdError::generic_err(
+            "oracle price has been stale for 3599 seconds; emergency close requires 3600"
+        ))
+    );
+    assert!(check_emergency_close_allowed(&env, &config, Some(100000 - 3600)).is_ok());
+    // An oracle that cannot be queried counts as stale.
+    assert!(check_emergency_close_allowed(&env, &config, None).is_ok());
+}
@@ -1,8 +1,11 @@
 use std::convert::TryFrom;
 
+use aperture_common::{
+    delta_neutral_position::LiquidityVenue, delta_neutral_position_manager::Context,
+};
 use cosmwasm_std::{
-    to_binary, Addr, CosmosMsg, Decimal256, Deps, QuerierWrapper, StdError, StdResult, Uint128,
-    Uint256, WasmMsg,
+    to_binary, Addr, Coin, CosmosMsg, Decimal, Decimal256, Deps, QuerierWrapper, StdError,
+    StdResult, Uint128, Uint256, WasmMsg,
 };
 use integer_sqrt::IntegerSquareRoot;
 use terraswap::asset::PairInfo;
@@ -80,12 +83,14 @@ fn test_create_cw20_uusd_pair_asset_info() {
 /// * `astroport_factory_addr` - Address of the Astroport factory contract
 /// * `cw20_token_addr` - Contract address of the cw20 token to be swapped
 /// * `amount` - Amount of the cw20 token to be swapped
+/// * `max_spread` - maximum spread against the pool price before the swap; unlimited if None
 pub fn swap_cw20_token_for_uusd(
     querier: &QuerierWrapper,
     terraswap_factory_addr: &Addr,
     astroport_factory_addr: &Addr,
     cw20_token_addr: &Addr,
     amount: Uint128,
+    max_spread: Option<Decimal>,
 ) -> StdResult<(CosmosMsg, Uint128)> {
     let terraswap_pair_info = terraswap::querier::query_pair_info(
         querier,
@@ -137,7 +142,7 @@ pub fn swap_cw20_token_for_uusd(
                     amount,
                     msg: to_binary(&terraswap::pair::Cw20HookMsg::Swap {
                         belief_price: None,
-                        max_spread: None,
+                        max_spread,
                         to: None,
                     })?,
                 },
@@ -150,7 +155,7 @@ pub fn swap_cw20_token_for_uusd(
                     amount,
                     msg: to_binary(&astroport::pair::Cw20HookMsg::Swap {
                         belief_price: None,
-                        max_spread: None,
+                        max_spread,
                         to: None,
                     })?,
                 },
@@ -206,7 +211,8 @@ fn test_swap_cw20_token_for_uusd() {
             &terraswap_factory_addr,
             &astroport_factory_addr,
             &cw20_token_addr,
-            amount
+            amount,
+            None
         )
         .unwrap(),
         (
@@ -234,7 +240,8 @@ fn test_swap_cw20_token_for_uusd() {
             &terraswap_factory_addr,
             &astroport_factory_addr,
             &cw20_token_addr,
-            amount
+            amount,
+            Some(Decimal::percent(5))
         )
         .unwrap(),
         (
@@ -245,7 +252,7 @@ fn test_swap_cw20_token_for_uusd() {
                     amount,
                     msg: to_binary(&astroport::pair::Cw20HookMsg::Swap {
                         belief_price: None,
-                        max_spread: None,
+                        max_spread: Some(Decimal::percent(5)),
                         to: None,
                     })
                     .unwrap(),
@@ -259,6 +266,7 @@ fn test_swap_cw20_token_for_uusd() {
 }
 
 /// Simulates a Terraswap pair contract's swap operation (a constant-product AMM w/ a fixed 0.3% commission).
+/// Astroport XYK pairs follow the same formula and commission rate, so this also applies to Astroport mAsset-UST pools.
 /// Returns (offer_pool_amount_after_swap, ask_pool_amount_after_swap, return_amount).
 ///
 /// We need to simulate swaps in hypothetical pool states, so we can't directly query Terraswap pair contract.
@@ -507,28 +515,16 @@ pub fn get_terraswap_mirror_asset_uusd_liquidity_info(
 
 #[test]
 fn test_get_terraswap_mirror_asset_uusd_liquidity_info() {
+    use crate::mock_querier::mock_deps;
     let terraswap_factory_addr = Addr::unchecked("mock_terraswap_factory");
-    let astroport_factory_addr = Addr::unchecked("mock_astroport_factory");
     let cw20_token_addr = Addr::unchecked("mock_cw20_addr");
     let terraswap_pair_addr = Addr::unchecked("mock_terraswap_pair");
-    let astroport_pair_addr = Addr::unchecked("mock_astroport_pair");
-    let querier = crate::mock_querier::WasmMockQuerier::new(
-        terraswap_factory_addr.to_string(),
-        astroport_factory_addr.to_string(),
-        terraswap_pair_addr.to_string(),
-        astroport_pair_addr.to_string(),
-        Uint128::from(10u128),
-        Uint128::from(9u128),
-        cw20_token_addr.to_string(),
+    let deps = mock_deps(
+        cw20_token_addr.as_str(),
         Uint128::from(1000u128),
         Uint128::from(100u128),
         Uint128::zero(),
     );
-    let deps = cosmwasm_std::OwnedDeps {
-        storage: cosmwasm_std::testing::MockStorage::default(),
-        api: cosmwasm_std::testing::MockApi::default(),
-        querier,
-    };
     assert_eq!(
         get_terraswap_mirror_asset_uusd_liquidity_info(
             deps.as_ref(),
@@ -554,3 +550,274 @@ fn test_get_terraswap_mirror_asset_uusd_liquidity_info() {
         )
     );
 }
+
+/// Obtains Astroport mAsset-UST liquidity information.
+/// Returns a tuple consisting of the following components:
+/// * PairInfo for the mAsset-UST pair.
+/// * Amount of mAsset in the pool.
+/// * Amount of uusd in the pool.
+///
+/// # Arguments
+///
+/// * `deps` - reference to dependencies
+/// * `astroport_factory_addr` - astroport factory contract address
+/// * `mirror_asset_cw20_addr` - mAsset cw20 contract address
+pub fn get_astroport_mirror_asset_uusd_liquidity_info(
+    deps: Deps,
+    astroport_factory_addr: &Addr,
+    mirror_asset_cw20_addr: &Addr,
+) -> StdResult<(astroport::asset::PairInfo, Uint128, Uint128)> {
+    let astroport_pair_asset_info =
+        create_astroport_cw20_uusd_pair_asset_info(mirror_asset_cw20_addr);
+    let astroport_pair_info = astroport::querier::query_pair_info(
+        &deps.querier,
+        astroport_factory_addr.clone(),
+        &astroport_pair_asset_info,
+    )?;
+    let pool_mirror_asset_amount = astroport_pair_asset_info[0]
+        .query_pool(&deps.querier, astroport_pair_info.contract_addr.clone())?;
+    let pool_uusd_amount = astroport_pair_asset_info[1]
+        .query_pool(&deps.querier, astroport_pair_info.contract_addr.clone())?;
+    Ok((
+        astroport_pair_info,
+        pool_mirror_asset_amount,
+        pool_uusd_amount,
+    ))
+}
+
+/// Obtains mAsset-UST liquidity information from the pool on `venue`.
+/// Returns a tuple consisting of the following components:
+/// * Address of the mAsset-UST pair contract.
+/// * Address of the LP cw20 token contract.
+/// * Amount of mAsset in the pool.
+/// * Amount of uusd in the pool.
+///
+/// # Arguments
+///
+/// * `deps` - reference to dependencies
+/// * `context` - context containing Terraswap and Astroport factory contract addresses
+/// * `venue` - the DEX hosting the pool
+/// * `mirror_asset_cw20_addr` - mAsset cw20 contract address
+pub fn get_mirror_asset_uusd_liquidity_info(
+    deps: Deps,
+    context: &Context,
+    venue: LiquidityVenue,
+    mirror_asset_cw20_addr: &Addr,
+) -> StdResult<(String, String, Uint128, Uint128)> {
+    match venue {
+        LiquidityVenue::Terraswap => {
+            let (pair_info, pool_mirror_asset_amount, pool_uusd_amount) =
+                get_terraswap_mirror_asset_uusd_liquidity_info(
+                    deps,
+                    &context.terraswap_factory_addr,
+                    mirror_asset_cw20_addr,
+                )?;
+            Ok((
+                pair_info.contract_addr,
+                pair_info.liquidity_token,
+                pool_mirror_asset_amount,
+                pool_uusd_amount,
+            ))
+        }
+        LiquidityVenue::Astroport => {
+            let (pair_info, pool_mirror_asset_amount, pool_uusd_amount) =
+                get_astroport_mirror_asset_uusd_liquidity_info(
+                    deps,
+                    &context.astroport_factory_addr,
+                    mirror_asset_cw20_addr,
+                )?;
+            Ok((
+                pair_info.contract_addr.to_string(),
+                pair_info.liquidity_token.to_string(),
+                pool_mirror_asset_amount,
+                pool_uusd_amount,
+            ))
+        }
+    }
+}
+
+#[test]
+fn test_get_astroport_mirror_asset_uusd_liquidity_info() {
+    use crate::mock_querier::mock_deps;
+    let astroport_factory_addr = Addr::unchecked("mock_astroport_factory");
+    let cw20_token_addr = Addr::unchecked("mock_cw20_addr");
+    let astroport_pair_addr = Addr::unchecked("mock_astroport_pair");
+    let deps = mock_deps(
+        cw20_token_addr.as_str(),
+        Uint128::from(1000u128),
+        Uint128::from(100u128),
+        Uint128::zero(),
+    );
+    let (pair_info, pool_mirror_asset_amount, pool_uusd_amount) =
+        get_astroport_mirror_asset_uusd_liquidity_info(
+            deps.as_ref(),
+            &astroport_factory_addr,
+            &cw20_token_addr,
+        )
+        .unwrap();
+    assert_eq!(pair_info.contract_addr, astroport_pair_addr);
+    assert_eq!(
+        pair_info.liquidity_token,
+        Addr::unchecked("astroport_lp_token")
+    );
+    assert_eq!(pool_mirror_asset_amount, Uint128::from(1000u128));
+    assert_eq!(pool_uusd_amount, Uint128::from(100u128));
+}
+
+/// Returns a Wasm execute message that swaps `uusd_amount` amount of uusd for the other asset in the pair `pair_addr` on `venue`.
+///
+/// # Arguments
+///
+/// * `venue` - the DEX hosting the pair
+/// * `pair_addr` - address of the pair contract
+/// * `uusd_amount` - amount of uusd to offer
+/// * `max_spread` - maximum spread against the pool price before the swap; unlimited if None
+pub fn create_uusd_swap_message(
+    venue: LiquidityVenue,
+    pair_addr: &str,
+    uusd_amount: Uint128,
+    max_spread: Option<Decimal>,
+) -> StdResult<CosmosMsg> {
+    let msg = match venue {
+        LiquidityVenue::Terraswap => to_binary(&terraswap::pair::ExecuteMsg::Swap {
+            offer_asset: terraswap::asset::Asset {
+                info: terraswap::asset::AssetInfo::NativeToken {
+                    denom: String::from("uusd"),
+                },
+                amount: uusd_amount,
+            },
+            belief_price: None,
+            max_spread,
+            to: None,
+        })?,
+        LiquidityVenue::Astroport => to_binary(&astroport::pair::ExecuteMsg::Swap {
+            offer_asset: astroport::asset::Asset {
+                info: astroport::asset::AssetInfo::NativeToken {
+                    denom: String::from("uusd"),
+                },
+                amount: uusd_amount,
+            },
+            belief_price: None,
+            max_spread,
+            to: None,
+        })?,
+    };
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pair_addr.to_string(),
+        msg,
+        funds: vec![Coin {
+            denom: String::from("uusd"),
+            amount: uusd_amount,
+        }],
+    }))
+}
+
+/// Returns Wasm execute messages that provide `mirror_asset_amount` amount of mAsset and `uusd_amount` amount of uusd as liquidity to the pair `pair_addr` on `venue`.
+/// The first message allows the pair contract to transfer mAsset from us; the second message provides liquidity.
+///
+/// # Arguments
+///
+/// * `venue` - the DEX hosting the pair
+/// * `pair_addr` - address of the mAsset-UST pair contract
+/// * `mirror_asset_cw20_addr` - mAsset cw20 contract address
+/// * `mirror_asset_amount` - amount of mAsset to provide
+/// * `uusd_amount` - amount of uusd to provide
+pub fn create_provide_liquidity_messages(
+    venue: LiquidityVenue,
+    pair_addr: &str,
+    mirror_asset_cw20_addr: &Addr,
+    mirror_asset_amount: Uint128,
+    uusd_amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let msg = match venue {
+        LiquidityVenue::Terraswap => to_binary(&terraswap::pair::ExecuteMsg::ProvideLiquidity {
+            assets: [
+                terraswap::asset::Asset {
+                    info: terraswap::asset::AssetInfo::Token {
+                        contract_addr: mirror_asset_cw20_addr.to_string(),
+                    },
+                    amount: mirror_asset_amount,
+                },
+                terraswap::asset::Asset {
+                    info: terraswap::asset::AssetInfo::NativeToken {
+                        denom: String::from("uusd"),
+                    },
+                    amount: uusd_amount,
+                },
+            ],
+            slippage_tolerance: None,
+            receiver: None,
+        })?,
+        LiquidityVenue::Astroport => to_binary(&astroport::pair::ExecuteMsg::ProvideLiquidity {
+            assets: [
+                astroport::asset::Asset {
+                    info: astroport::asset::AssetInfo::Token {
+                        contract_addr: mirror_asset_cw20_addr.clone(),
+                    },
+                    amount: mirror_asset_amount,
+                },
+                astroport::asset::Asset {
+                    info: astroport::asset::AssetInfo::NativeToken {
+                        denom: String::from("uusd"),
+                    },
+                    amount: uusd_amount,
+                },
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        })?,
+    };
+    Ok(vec![
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: mirror_asset_cw20_addr.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair_addr.to_string(),
+                amount: mirror_asset_amount,
+                expires: None,
+            })?,
+            funds: vec![],
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: pair_addr.to_string(),
+            msg,
+            funds: vec![Coin {
+                denom: String::from("uusd"),
+                amount: uusd_amount,
+            }],
+        }),
+    ])
+}
+
+/// Returns a Wasm execute message that redeems `lp_token_amount` amount of LP tokens at the pair `pair_addr` on `venue` for the underlying assets.
+///
+/// # Arguments
+///
+/// * `venue` - the DEX hosting the pair
+/// * `lp_token_cw20_addr` - address of the LP cw20 token contract
+/// * `pair_addr` - address of the pair contract
+/// * `lp_token_amount` - amount of LP tokens to redeem
+pub fn create_withdraw_liquidity_message(
+    venue: LiquidityVenue,
+    lp_token_cw20_addr: &str,
+    pair_addr: &str,
+    lp_token_amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let msg = match venue {
+        LiquidityVenue::Terraswap => {
+            to_binary(&terraswap::pair::Cw20HookMsg::WithdrawLiquidity {})?
+        }
+        LiquidityVenue::Astroport => {
+            to_binary(&astroport::pair::Cw20HookMsg::WithdrawLiquidity {})?
+        }
+    };
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: lp_token_cw20_addr.to_string(),
+        msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+            contract: pair_addr.to_string(),
+            amount: lp_token_amount,
+            msg,
+        })?,
+        funds: vec![],
+    }))
+}
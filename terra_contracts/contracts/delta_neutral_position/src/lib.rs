@@ -1,10 +1,13 @@
 pub mod contract;
 pub mod state;
 
+mod collateral_util;
 mod dex_util;
+mod limit_order_util;
 mod math;
 mod open;
 mod rebalance;
+mod snapshot_util;
 mod spectrum_util;
 mod util;
 
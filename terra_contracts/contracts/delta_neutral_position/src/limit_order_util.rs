@@ -0,0 +1,255 @@
+use aperture_common::delta_neutral_position_manager::Context;
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, Env, QuerierWrapper, StdError, StdResult, Uint128, WasmMsg,
+};
+use mirror_protocol::{
+    common::OrderBy,
+    limit_order::{OrderResponse, OrdersResponse},
+};
+use terraswap::asset::{Asset, AssetInfo};
+
+// Maximum number of orders returned by a single Mirror limit order `Orders` query.
+const ORDERS_QUERY_LIMIT: u32 = 30;
+
+// Returns Mirror limit orders submitted by this position that are neither fully filled nor cancelled.
+// Mirror limit order contract indexes orders by bidder, which is how a position keeps track of its own orders.
+// Orders are queried page by page in ascending order id until a page comes back short.
+pub fn get_outstanding_limit_orders(
+    querier: &QuerierWrapper,
+    env: &Env,
+    context: &Context,
+) -> StdResult<Vec<OrderResponse>> {
+    let limit_order_addr = match &context.mirror_limit_order_addr {
+        Some(limit_order_addr) => limit_order_addr,
+        None => return Ok(vec![]),
+    };
+    let mut orders: Vec<OrderResponse> = vec![];
+    loop {
+        let orders_response: OrdersResponse = querier.query_wasm_smart(
+            limit_order_addr,
+            &mirror_protocol::limit_order::QueryMsg::Orders {
+                bidder_addr: Some(env.contract.address.to_string()),
+                start_after: orders.last().map(|order| order.order_id),
+                limit: Some(ORDERS_QUERY_LIMIT),
+                order_by: Some(OrderBy::Asc),
+            },
+        )?;
+        let page_len = orders_response.orders.len();
+        orders.extend(orders_response.orders);
+        if page_len < ORDERS_QUERY_LIMIT as usize {
+            return Ok(orders);
+        }
+    }
+}
+
+// Returns the unfilled mAsset and uusd amounts offered in `orders`; these amounts are refunded to the position when the orders are cancelled.
+pub fn get_limit_order_unfilled_offer_amounts(
+    orders: &[OrderResponse],
+    mirror_asset_cw20_addr: &Addr,
+) -> StdResult<(Uint128, Uint128)> {
+    let mut mirror_asset_amount = Uint128::zero();
+    let mut uusd_amount = Uint128::zero();
+    for order in orders.iter() {
+        let unfilled_amount = order
+            .offer_asset
+            .amount
+            .checked_sub(order.filled_offer_amount)?;
+        match &order.offer_asset.info {
+            AssetInfo::Token { contract_addr } => {
+                if *contract_addr == *mirror_asset_cw20_addr {
+                    mirror_asset_amount += unfilled_amount;
+                }
+            }
+            AssetInfo::NativeToken { denom } => {
+                if denom == "uusd" {
+                    uusd_amount += unfilled_amount;
+                }
+            }
+        }
+    }
+    Ok((mirror_asset_amount, uusd_amount))
+}
+
+pub fn create_cancel_limit_order_messages(
+    context: &Context,
+    orders: &[OrderResponse],
+) -> StdResult<Vec<CosmosMsg>> {
+    if orders.is_empty() {
+        return Ok(vec![]);
+    }
+    let limit_order_addr = context
+        .mirror_limit_order_addr
+        .as_ref()
+        .ok_or_else(|| StdError::generic_err("Mirror limit order contract not configured"))?;
+    orders
+        .iter()
+        .map(|order| {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: limit_order_addr.to_string(),
+                msg: to_binary(&mirror_protocol::limit_order::ExecuteMsg::CancelOrder {
+                    order_id: order.order_id,
+                })?,
+                funds: vec![],
+            }))
+        })
+        .collect()
+}
+
+// Returns a Wasm execute message that submits a Mirror limit order offering `offer_asset` in exchange for `ask_asset`.
+// A cw20 offer is sent to the limit order contract with a `SubmitOrder` hook; a native offer is attached as funds.
+pub fn create_submit_limit_order_message(
+    limit_order_addr: &Addr,
+    offer_asset: Asset,
+    ask_asset: Asset,
+) -> StdResult<CosmosMsg> {
+    match offer_asset.info {
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: limit_order_addr.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&mirror_protocol::limit_order::Cw20HookMsg::SubmitOrder {
+                    ask_asset,
+                })?,
+            })?,
+            funds: vec![],
+        })),
+        AssetInfo::NativeToken { ref denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: limit_order_addr.to_string(),
+            funds: vec![Coin {
+                denom: denom.clone(),
+                amount: offer_asset.amount,
+            }],
+            msg: to_binary(&mirror_protocol::limit_order::ExecuteMsg::SubmitOrder {
+                offer_asset,
+                ask_asset,
+            })?,
+        })),
+    }
+}
+
+#[test]
+fn test_get_limit_order_unfilled_offer_amounts() {
+    let mirror_asset_cw20_addr = Addr::unchecked("mirror_asset_cw20");
+    let create_order =
+        |order_id: u64, offer_asset_info: AssetInfo, filled_offer_amount: u128| OrderResponse {
+            order_id,
+            bidder_addr: String::from("this"),
+            offer_asset: Asset {
+                info: offer_asset_info,
+                amount: Uint128::from(100u128),
+            },
+            ask_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: String::from("uluna"),
+                },
+                amount: Uint128::from(1u128),
+            },
+            filled_offer_amount: Uint128::from(filled_offer_amount),
+            filled_ask_amount: Uint128::zero(),
+        };
+    let orders = vec![
+        create_order(
+            1,
+            AssetInfo::Token {
+                contract_addr: mirror_asset_cw20_addr.to_string(),
+            },
+            30,
+        ),
+        create_order(
+            2,
+            AssetInfo::NativeToken {
+                denom: String::from("uusd"),
+            },
+            0,
+        ),
+        create_order(
+            3,
+            AssetInfo::Token {
+                contract_addr: String::from("other_cw20"),
+            },
+            0,
+        ),
+    ];
+    assert_eq!(
+        get_limit_order_unfilled_offer_amounts(&orders, &mirror_asset_cw20_addr).unwrap(),
+        (Uint128::from(70u128), Uint128::from(100u128))
+    );
+
+    // An order reporting a filled amount above its offer amount is rejected rather than underflowing.
+    assert!(get_limit_order_unfilled_offer_amounts(
+        &[create_order(
+            4,
+            AssetInfo::NativeToken {
+                denom: String::from("uusd"),
+            },
+            101,
+        )],
+        &mirror_asset_cw20_addr
+    )
+    .is_err());
+}
+
+#[test]
+fn test_get_outstanding_limit_orders() {
+    use crate::mock_querier::{mock_context, mock_deps};
+    use cosmwasm_std::testing::mock_env;
+
+    let mut deps = mock_deps(
+        "mock_cw20_addr",
+        Uint128::from(1000000u128),
+        Uint128::from(9000000u128),
+        Uint128::zero(),
+    );
+    let env = mock_env();
+    let context = Context {
+        mirror_limit_order_addr: Some(Addr::unchecked("mirror_limit_order")),
+        ..mock_context()
+    };
+    let create_orders = |count: u64| {
+        (1..=count)
+            .map(|order_id| OrderResponse {
+                order_id,
+                bidder_addr: env.contract.address.to_string(),
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: String::from("uusd"),
+                    },
+                    amount: Uint128::from(100u128),
+                },
+                ask_asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: String::from("mock_cw20_addr"),
+                    },
+                    amount: Uint128::from(1u128),
+                },
+                filled_offer_amount: Uint128::zero(),
+                filled_ask_amount: Uint128::zero(),
+            })
+            .collect::<Vec<OrderResponse>>()
+    };
+
+    // Orders beyond the first page are fetched, including when the last page is full.
+    for count in [0u64, 29, 30, 65, 90].iter() {
+        deps.querier.limit_orders = create_orders(*count);
+        let orders =
+            get_outstanding_limit_orders(&QuerierWrapper::new(&deps.querier), &env, &context)
+                .unwrap();
+        assert_eq!(orders, deps.querier.limit_orders);
+        assert_eq!(
+            create_cancel_limit_order_messages(&context, &orders)
+                .unwrap()
+                .len(),
+            *count as usize
+        );
+    }
+
+    // Without a configured limit order contract, there are no outstanding orders.
+    assert!(get_outstanding_limit_orders(
+        &QuerierWrapper::new(&deps.querier),
+        &env,
+        &mock_context()
+    )
+    .unwrap()
+    .is_empty());
+}
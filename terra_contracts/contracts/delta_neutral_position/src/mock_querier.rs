@@ -1,7 +1,9 @@
+use aperture_common::delta_neutral_position_manager::Context;
 use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{MockApi, MockStorage};
 use cosmwasm_std::{
     from_binary, from_slice, to_binary, Addr, BalanceResponse, BankQuery, CanonicalAddr, Coin,
-    ContractResult, Decimal, Empty, Querier, QuerierResult, QueryRequest, SystemError,
+    ContractResult, Decimal, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError,
     SystemResult, Uint128, WasmQuery,
 };
 use terraswap::asset::AssetInfo;
@@ -26,6 +28,8 @@ pub struct WasmMockQuerier {
     pub anchor_market: String,
     pub spectrum_mirror_farms: String,
     spectrum_mirror_pool_lp_balance: Uint128,
+    pub mirror_limit_order: String,
+    pub limit_orders: Vec<mirror_protocol::limit_order::OrderResponse>,
 }
 
 impl Querier for WasmMockQuerier {
@@ -47,7 +51,9 @@ impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match request {
             QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
-                if *address == self.terraswap_pair && denom == "uusd" {
+                if (*address == self.terraswap_pair || *address == self.astroport_pair)
+                    && denom == "uusd"
+                {
                     SystemResult::Ok(ContractResult::Ok(
                         to_binary(&BalanceResponse {
                             amount: Coin {
@@ -104,7 +110,7 @@ impl WasmMockQuerier {
                                     },
                                 ],
                                 contract_addr: Addr::unchecked(self.astroport_pair.clone()),
-                                liquidity_token: Addr::unchecked("unused"),
+                                liquidity_token: Addr::unchecked("astroport_lp_token"),
                             }),
                         )
                         .unwrap(),
@@ -247,6 +253,35 @@ impl WasmMockQuerier {
                             )
                             .unwrap(),
                         )),
+                        mirror_protocol::collateral_oracle::QueryMsg::CollateralAssetInfos {} => {
+                            SystemResult::Ok(ContractResult::Ok(
+                                to_binary(
+                                    &(mirror_protocol::collateral_oracle::CollateralInfosResponse {
+                                        collaterals: vec![
+                                            mirror_protocol::collateral_oracle::CollateralInfoResponse {
+                                                asset: String::from("anchor_ust_cw20"),
+                                                multiplier: Decimal::one(),
+                                                source_type: String::from("anchor_market"),
+                                                is_revoked: false,
+                                            },
+                                            mirror_protocol::collateral_oracle::CollateralInfoResponse {
+                                                asset: self.cw20_token.clone(),
+                                                multiplier: Decimal::from_ratio(4u128, 3u128),
+                                                source_type: String::from("terraswap"),
+                                                is_revoked: false,
+                                            },
+                                            mirror_protocol::collateral_oracle::CollateralInfoResponse {
+                                                asset: String::from("revoked_cw20"),
+                                                multiplier: Decimal::one(),
+                                                source_type: String::from("terraswap"),
+                                                is_revoked: true,
+                                            },
+                                        ],
+                                    }),
+                                )
+                                .unwrap(),
+                            ))
+                        }
                         _ => panic!(),
                     }
                 } else if contract_addr == &self.mirror_staking {
@@ -328,6 +363,24 @@ impl WasmMockQuerier {
                         }
                         _ => panic!(),
                     }
+                } else if contract_addr == &String::from("spectrum_gov") {
+                    let msg: spectrum_protocol::gov::QueryMsg = from_binary(msg).unwrap();
+                    match msg {
+                        spectrum_protocol::gov::QueryMsg::balance { .. } => {
+                            SystemResult::Ok(ContractResult::Ok(
+                                to_binary(
+                                    &(spectrum_protocol::gov::BalanceResponse {
+                                        balance: Uint128::from(7u128),
+                                        share: Uint128::from(6u128),
+                                        locked_balance: vec![],
+                                        pools: vec![],
+                                    }),
+                                )
+                                .unwrap(),
+                            ))
+                        }
+                        _ => panic!(),
+                    }
                 } else if contract_addr == &String::from("lp_token") {
                     let msg: cw20::Cw20QueryMsg = from_binary(&msg).unwrap();
                     match msg {
@@ -346,6 +399,26 @@ impl WasmMockQuerier {
                         }
                         _ => panic!(),
                     }
+                } else if contract_addr == &self.mirror_limit_order {
+                    match from_binary(msg).unwrap() {
+                        mirror_protocol::limit_order::QueryMsg::Orders {
+                            start_after,
+                            limit,
+                            ..
+                        } => SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&mirror_protocol::limit_order::OrdersResponse {
+                                orders: self
+                                    .limit_orders
+                                    .iter()
+                                    .filter(|order| Some(order.order_id) > start_after)
+                                    .take(limit.unwrap_or(10) as usize)
+                                    .cloned()
+                                    .collect(),
+                            })
+                            .unwrap(),
+                        )),
+                        _ => panic!(),
+                    }
                 } else {
                     panic!()
                 }
@@ -409,6 +482,59 @@ impl WasmMockQuerier {
             anchor_market: String::from("anchor_market"),
             spectrum_mirror_farms: String::from("spectrum_mirror_farms"),
             spectrum_mirror_pool_lp_balance,
+            mirror_limit_order: String::from("mirror_limit_order"),
+            limit_orders: vec![],
         }
     }
 }
+
+// Context whose contract addresses match those served by `WasmMockQuerier` from `mock_deps`.
+pub fn mock_context() -> Context {
+    Context {
+        controller: Addr::unchecked("controller"),
+        anchor_ust_cw20_addr: Addr::unchecked("anchor_ust_cw20"),
+        mirror_cw20_addr: Addr::unchecked("mirror_cw20"),
+        spectrum_cw20_addr: Addr::unchecked("spectrum_cw20"),
+        anchor_market_addr: Addr::unchecked("anchor_market"),
+        mirror_collateral_oracle_addr: Addr::unchecked("mirror_collateral_oracle"),
+        mirror_limit_order_addr: None,
+        mirror_lock_addr: Addr::unchecked("mirror_lock"),
+        mirror_mint_addr: Addr::unchecked("mirror_mint"),
+        mirror_oracle_addr: Addr::unchecked("mirror_oracle"),
+        mirror_staking_addr: Addr::unchecked("mirror_staking"),
+        spectrum_gov_addr: Addr::unchecked("spectrum_gov"),
+        spectrum_mirror_farms_addr: Addr::unchecked("spectrum_mirror_farms"),
+        spectrum_staker_addr: Addr::unchecked("spectrum_staker"),
+        terraswap_factory_addr: Addr::unchecked("mock_terraswap_factory"),
+        astroport_factory_addr: Addr::unchecked("mock_astroport_factory"),
+        collateral_ratio_safety_margin: Decimal::from_ratio(3u128, 10u128),
+        min_open_uusd_amount: Uint128::from(500u128),
+        min_reinvest_uusd_amount: Uint128::from(10u128),
+        limit_order_imbalance_threshold: Decimal::zero(),
+    }
+}
+
+// Dependencies whose querier serves `cw20_token`-uusd pairs on both venues, where swap simulations return 10 uusd on Terraswap and 9 uusd on Astroport.
+pub fn mock_deps(
+    cw20_token: &str,
+    terraswap_pool_cw20_balance: Uint128,
+    terraswap_pool_uusd_balance: Uint128,
+    spectrum_mirror_pool_lp_balance: Uint128,
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: WasmMockQuerier::new(
+            String::from("mock_terraswap_factory"),
+            String::from("mock_astroport_factory"),
+            String::from("mock_terraswap_pair"),
+            String::from("mock_astroport_pair"),
+            Uint128::from(10u128),
+            Uint128::from(9u128),
+            String::from(cw20_token),
+            terraswap_pool_cw20_balance,
+            terraswap_pool_uusd_balance,
+            spectrum_mirror_pool_lp_balance,
+        ),
+    }
+}
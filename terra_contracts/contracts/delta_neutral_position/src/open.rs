@@ -1,26 +1,115 @@
-use std::str::FromStr;
-
 use aperture_common::{
-    anchor_util::get_anchor_ust_exchange_rate,
-    delta_neutral_position::TargetCollateralRatioRange,
-    delta_neutral_position_manager::Context,
+    delta_neutral_position::{InternalExecuteMsg, LiquidityVenue, TargetCollateralRatioRange},
+    delta_neutral_position_manager::{Context, MirrorAssetStrategyParams},
     mirror_util::{get_mirror_asset_config_response, is_mirror_asset_delisted},
 };
-use cosmwasm_bignumber::Uint256;
 use cosmwasm_std::{
-    to_binary, Addr, Coin, CosmosMsg, Decimal, DepsMut, Env, StdError, StdResult, Uint128, WasmMsg,
+    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, Response, StdError, StdResult,
+    Uint128, WasmMsg,
 };
 use terraswap::asset::{Asset, AssetInfo};
 
 use crate::{
+    collateral_util::{
+        get_collateral_asset_info, get_collateral_price_response, UusdToCollateralConversion,
+    },
+    contract::create_internal_execute_message,
     dex_util::{
-        compute_terraswap_offer_amount, get_terraswap_mirror_asset_uusd_liquidity_info,
-        simulate_terraswap_swap,
+        compute_terraswap_offer_amount, create_uusd_swap_message,
+        get_astroport_mirror_asset_uusd_liquidity_info,
+        get_terraswap_mirror_asset_uusd_liquidity_info, simulate_terraswap_swap,
     },
-    math::{decimal_division, reverse_decimal},
-    util::{get_uusd_asset_from_amount, get_uusd_coin_from_amount, MIN_TARGET_CR_RANGE_WIDTH},
+    math::{decimal_division, decimal_multiplication, reverse_decimal},
+    state::MIRROR_ASSET_CW20_ADDR,
+    util::get_liquidity_venue,
 };
 
+// The mAsset-UST pools involved in the short sale and the long swap of a delta-neutral investment.
+struct MirrorAssetPools {
+    venue: LiquidityVenue,
+    terraswap_pool_balances: (Uint128, Uint128),
+    long_swap_pair_addr: String,
+    // `None` means that the long swap happens in the Terraswap pool after the short sale.
+    long_swap_pool_balances: Option<(Uint128, Uint128)>,
+}
+
+impl MirrorAssetPools {
+    fn query(deps: Deps, context: &Context, mirror_asset_cw20_addr: &Addr) -> StdResult<Self> {
+        let (terraswap_pair_info, pool_mirror_asset_balance, pool_uusd_balance) =
+            get_terraswap_mirror_asset_uusd_liquidity_info(
+                deps,
+                &context.terraswap_factory_addr,
+                mirror_asset_cw20_addr,
+            )?;
+
+        // Find the pool where the long swap takes place.
+        let venue = get_liquidity_venue(deps.storage)?;
+        let (long_swap_pair_addr, long_swap_pool_balances) = match venue {
+            LiquidityVenue::Terraswap => (terraswap_pair_info.contract_addr, None),
+            LiquidityVenue::Astroport => {
+                let (
+                    astroport_pair_info,
+                    astroport_pool_mirror_asset_balance,
+                    astroport_pool_uusd_balance,
+                ) = get_astroport_mirror_asset_uusd_liquidity_info(
+                    deps,
+                    &context.astroport_factory_addr,
+                    mirror_asset_cw20_addr,
+                )?;
+                (
+                    astroport_pair_info.contract_addr.to_string(),
+                    Some((
+                        astroport_pool_mirror_asset_balance,
+                        astroport_pool_uusd_balance,
+                    )),
+                )
+            }
+        };
+        Ok(MirrorAssetPools {
+            venue,
+            terraswap_pool_balances: (pool_mirror_asset_balance, pool_uusd_balance),
+            long_swap_pair_addr,
+            long_swap_pool_balances,
+        })
+    }
+
+    // Returns the least amount of uusd to swap for `mirror_asset_mint_amount` amount of mAsset, after Mirror swaps the same amount of mAsset for uusd via Terraswap as part of the short sale.
+    fn compute_uusd_long_swap_amount(
+        &self,
+        mirror_asset_mint_amount: Uint128,
+    ) -> StdResult<Uint128> {
+        // The Terraswap mAsset-UST pool state will become the following after the short sale.
+        let (pool_mirror_asset_balance_after_short_swap, pool_uusd_balance_after_short_swap, _) =
+            simulate_terraswap_swap(
+                self.terraswap_pool_balances.0,
+                self.terraswap_pool_balances.1,
+                mirror_asset_mint_amount,
+            );
+        let (long_swap_pool_mirror_asset_balance, long_swap_pool_uusd_balance) =
+            self.long_swap_pool_balances.unwrap_or((
+                pool_mirror_asset_balance_after_short_swap,
+                pool_uusd_balance_after_short_swap,
+            ));
+        compute_terraswap_offer_amount(
+            long_swap_pool_mirror_asset_balance,
+            long_swap_pool_uusd_balance,
+            mirror_asset_mint_amount,
+        )
+    }
+}
+
+// Returns the amount of mAsset that Mirror mints against `collateral_amount` amount of collateral at `collateral_ratio`.
+fn compute_mirror_asset_mint_amount(
+    collateral_amount: Uint128,
+    collateral_uusd_price: Decimal,
+    mirror_asset_oracle_uusd_rate: Decimal,
+    collateral_ratio: Decimal,
+) -> Uint128 {
+    collateral_amount
+        * decimal_division(collateral_uusd_price, mirror_asset_oracle_uusd_rate)
+        * reverse_decimal(collateral_ratio)
+}
+
 // Open a (or increase an existing) delta-neutral position with the following parameters:
 // (1) mAsset: `mirror_asset_cw20_addr`.
 // (2) Collateral ratio: `target_collateral_ratio_range.midpoint()`.
@@ -29,28 +118,28 @@ use crate::{
 // If `cdp_idx` is Some(idx), then there is an existing delta-neutral position; otherwise, this opens a new position.
 //
 // This process consists of three stages:
-// (1) Find `uusd_collateral_amount`, the amount of uusd to be deposited to Anchor, and the resultant aUST will be used to open a Mirror collateralized debit position (CDP) of the specified mAsset.
+// (1) Find `uusd_collateral_amount`, the amount of uusd to be converted into the position's collateral asset (deposited to Anchor for aUST, swapped for other assets), which will be used to open a Mirror collateralized debit position (CDP) of the specified mAsset.
 // (2) As part of the short position opening process, Mirror automatically swaps the minted mAsset for uusd. The uusd proceed is locked up for a period of time.
 // (3) We swap `uusd_long_swap_amount` amount of uusd for mAsset; the returned mAsset amount should match the shorted amount so the position is delta-neutral overall.
+//     This swap takes place in the mAsset-UST pool of the position's liquidity venue. Mirror always performs the short sale in stage (2) via Terraswap,
+//     so the long swap only sees the price impact of the short sale if the venue is also Terraswap.
 //
 // This function uses binary search to find the largest possible `uusd_collateral_amount` such that `uusd_collateral_amount + uusd_long_swap_amount <= uusd_amount`.
+//
+// If uusd is swapped for collateral, stages (2) and (3) are carried out by `InternalExecuteMsg::OpenOrIncreaseCdpWithCollateralBalance` after the swap, using the amount of collateral actually received.
 #[allow(clippy::too_many_arguments)]
 pub fn delta_neutral_invest(
     deps: DepsMut,
     env: &Env,
     context: Context,
+    strategy_params: &MirrorAssetStrategyParams,
     uusd_amount: Uint128,
     target_collateral_ratio_range: &TargetCollateralRatioRange,
     mirror_asset_cw20_addr: &Addr,
     mirror_asset_oracle_uusd_rate: Decimal,
     cdp_idx: Option<Uint128>,
 ) -> StdResult<Vec<CosmosMsg>> {
-    let (pair_info, pool_mirror_asset_balance, pool_uusd_balance) =
-        get_terraswap_mirror_asset_uusd_liquidity_info(
-            deps.as_ref(),
-            &context.terraswap_factory_addr,
-            mirror_asset_cw20_addr,
-        )?;
+    let pools = MirrorAssetPools::query(deps.as_ref(), &context, mirror_asset_cw20_addr)?;
 
     // Abort if mAsset is delisted.
     let mirror_asset_config_response = get_mirror_asset_config_response(
@@ -63,29 +152,39 @@ pub fn delta_neutral_invest(
     }
 
     // Check that target_min_collateral_ratio.min meets the safety margin requirement, i.e. exceeds the minimum threshold by at least the configured safety margin.
+    // Mirror scales the minimum threshold by the collateral multiplier of the collateral asset.
+    let collateral_asset_info = get_collateral_asset_info(deps.storage, &context)?;
+    let collateral_price_response =
+        get_collateral_price_response(&deps.querier, &context, &collateral_asset_info)?;
     if target_collateral_ratio_range.min
-        < mirror_asset_config_response.min_collateral_ratio + context.collateral_ratio_safety_margin
+        < decimal_multiplication(
+            mirror_asset_config_response.min_collateral_ratio,
+            collateral_price_response.multiplier,
+        ) + strategy_params.collateral_ratio_safety_margin
     {
         return Err(StdError::generic_err(
             "target_min_collateral_ratio too small",
         ));
     }
 
-    // Check that target_min_collateral_ratio.max meets the width requirement, i.e. exceeds .min by at least MIN_TARGET_CR_RANGE_WIDTH.
+    // Check that target_min_collateral_ratio.max meets the width requirement, i.e. exceeds .min by at least the configured minimum width.
     // This also reject invalid values where .max <= .min.
     if target_collateral_ratio_range.max
-        < target_collateral_ratio_range.min + Decimal::from_str(MIN_TARGET_CR_RANGE_WIDTH)?
+        < target_collateral_ratio_range.min
+            + strategy_params.min_target_collateral_ratio_range_width
     {
         return Err(StdError::generic_err(
             "target_max_collateral_ratio too small",
         ));
     }
 
-    // Query Anchor Market epoch state for aUST exchange rate.
-    let anchor_ust_exchange_rate =
-        get_anchor_ust_exchange_rate(deps.as_ref(), env, &context.anchor_market_addr)?;
+    // Find out how uusd is converted into collateral, and the uusd price of the collateral used by Mirror to determine the mint amount.
+    let collateral_conversion =
+        UusdToCollateralConversion::new(deps.as_ref(), env, &context, &collateral_asset_info)?;
+    let collateral_uusd_price =
+        collateral_conversion.collateral_uusd_price(&collateral_price_response);
 
-    // Our goal is to find the maximum amount of uusd that can be posted as collateral (in the form of aUST) such that there is enough uusd remaining that can be swapped for the minted amount of mAsset.
+    // Our goal is to find the maximum amount of uusd that can be posted as collateral (in the form of the collateral asset) such that there is enough uusd remaining that can be swapped for the minted amount of mAsset.
     // We use binary search to achieve this goal.
     let mut a = Uint128::zero();
     let mut b = uusd_amount;
@@ -95,35 +194,21 @@ pub fn delta_neutral_invest(
         // We post `uusd_collateral_amount` amount of uusd as collateral, and simulate to see what happens.
         let uusd_collateral_amount = (a + b) >> 1;
 
-        // First, we deposit `uusd_collateral_amount` amount of uusd into Anchor Market, and get back `collateral_anchor_ust_amount` amount of aUST.
-        let collateral_anchor_ust_amount =
-            Uint128::from(Uint256::from(uusd_collateral_amount) / anchor_ust_exchange_rate);
+        // First, we convert `uusd_collateral_amount` amount of uusd into `collateral_amount` amount of the collateral asset.
+        let collateral_amount = collateral_conversion.simulate(uusd_collateral_amount);
 
         // Second, we open a short position via Mirror Mint.
-        // With `collateral_anchor_ust_amount` amount of aUST collateral and `collateral_ratio`, Mirror will mint `mirror_asset_mint_amount` amount of mAsset.
-        let mirror_asset_mint_amount = collateral_anchor_ust_amount
-            * decimal_division(
-                Decimal::from(anchor_ust_exchange_rate),
-                mirror_asset_oracle_uusd_rate,
-            )
-            * reverse_decimal(collateral_ratio);
-
-        // Third, Mirror will swap `mirror_asset_mint_amount` amount of mAsset for uusd via Terraswap.
-        // The Terraswap mAsset-UST pool state will become the following after this swap.
-        let (pool_mirror_asset_balance_after_short_swap, pool_uusd_balance_after_short_swap, _) =
-            simulate_terraswap_swap(
-                pool_mirror_asset_balance,
-                pool_uusd_balance,
-                mirror_asset_mint_amount,
-            );
-
-        // Finally, we want to swap the least amount of uusd for the same `mirror_asset_mint_amount`.
-        let uusd_long_swap_amount = compute_terraswap_offer_amount(
-            pool_mirror_asset_balance_after_short_swap,
-            pool_uusd_balance_after_short_swap,
-            mirror_asset_mint_amount,
+        // With `collateral_amount` amount of collateral and `collateral_ratio`, Mirror will mint `mirror_asset_mint_amount` amount of mAsset.
+        let mirror_asset_mint_amount = compute_mirror_asset_mint_amount(
+            collateral_amount,
+            collateral_uusd_price,
+            mirror_asset_oracle_uusd_rate,
+            collateral_ratio,
         );
 
+        // Finally, Mirror will swap `mirror_asset_mint_amount` amount of mAsset for uusd via Terraswap, and we want to swap the least amount of uusd for the same `mirror_asset_mint_amount`.
+        let uusd_long_swap_amount = pools.compute_uusd_long_swap_amount(mirror_asset_mint_amount);
+
         // Determine feasibility by checking whether the sum of `uusd_collateral_amount` and `uusd_long_swap_amount` stays within our budget of `uusd_amount`.
         let feasible = uusd_long_swap_amount.map_or_else(
             |_| false,
@@ -138,106 +223,212 @@ pub fn delta_neutral_invest(
 
     // Simulate the process one final time using the final `uusd_collateral_amount` value.
     let uusd_collateral_amount = a;
-    let collateral_anchor_ust_amount =
-        Uint128::from(Uint256::from(uusd_collateral_amount) / anchor_ust_exchange_rate);
-    let mirror_asset_mint_amount = collateral_anchor_ust_amount
-        * decimal_division(
-            Decimal::from(anchor_ust_exchange_rate),
-            mirror_asset_oracle_uusd_rate,
-        )
-        * reverse_decimal(collateral_ratio);
-    let (pool_mirror_asset_balance_after_short_swap, pool_uusd_balance_after_short_swap, _) =
-        simulate_terraswap_swap(
-            pool_mirror_asset_balance,
-            pool_uusd_balance,
-            mirror_asset_mint_amount,
-        );
-    let uusd_long_swap_amount = compute_terraswap_offer_amount(
-        pool_mirror_asset_balance_after_short_swap,
-        pool_uusd_balance_after_short_swap,
-        mirror_asset_mint_amount,
+    let collateral_amount = collateral_conversion.simulate(uusd_collateral_amount);
+    let mut messages = collateral_conversion.create_messages(
+        &context,
+        uusd_collateral_amount,
+        strategy_params.slippage_tolerance,
     )?;
-
-    let mut messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: context.anchor_market_addr.to_string(),
-        msg: to_binary(&moneymarket::market::ExecuteMsg::DepositStable {})?,
-        funds: vec![Coin {
-            denom: String::from("uusd"),
-            amount: uusd_collateral_amount,
-        }],
-    })];
-    messages.extend(open_or_increase_cdp(
+    if let UusdToCollateralConversion::TerraswapSwap { .. } = collateral_conversion {
+        // The swap may return less collateral than simulated if the collateral-UST pool moves in the meantime, so the collateral is posted after the swap.
+        // The simulated amount is an upper bound of what is posted, so that the long swap stays within our budget.
+        messages.push(create_internal_execute_message(
+            env,
+            InternalExecuteMsg::OpenOrIncreaseCdpWithCollateralBalance {
+                collateral_balance_before: collateral_asset_info.query_pool(
+                    &deps.querier,
+                    deps.api,
+                    env.contract.address.clone(),
+                )?,
+                max_collateral_amount: collateral_amount,
+                collateral_ratio,
+                collateral_uusd_price,
+                mirror_asset_oracle_uusd_rate,
+                cdp_idx,
+                max_spread: strategy_params.slippage_tolerance,
+            },
+        ));
+        return Ok(messages);
+    }
+    messages.extend(open_or_increase_cdp_and_swap_long(
         &context,
+        &pools,
+        Asset {
+            info: collateral_asset_info,
+            amount: collateral_amount,
+        },
+        collateral_uusd_price,
+        mirror_asset_cw20_addr,
+        mirror_asset_oracle_uusd_rate,
+        collateral_ratio,
+        cdp_idx,
+        strategy_params.slippage_tolerance,
+    )?);
+    Ok(messages)
+}
+
+// Posts the collateral received from the uusd -> collateral swap issued by `delta_neutral_invest`, i.e. the increase of the collateral balance over `collateral_balance_before`, capped at `max_collateral_amount`.
+// Then swaps uusd for the amount of mAsset minted against the posted collateral, so the position remains delta-neutral.
+#[allow(clippy::too_many_arguments)]
+pub fn open_or_increase_cdp_with_collateral_balance(
+    deps: Deps,
+    env: Env,
+    context: Context,
+    collateral_balance_before: Uint128,
+    max_collateral_amount: Uint128,
+    collateral_ratio: Decimal,
+    collateral_uusd_price: Decimal,
+    mirror_asset_oracle_uusd_rate: Decimal,
+    cdp_idx: Option<Uint128>,
+    max_spread: Option<Decimal>,
+) -> StdResult<Response> {
+    let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
+    let collateral_asset_info = get_collateral_asset_info(deps.storage, &context)?;
+    let collateral_amount = collateral_asset_info
+        .query_pool(&deps.querier, deps.api, env.contract.address)?
+        .checked_sub(collateral_balance_before)?
+        .min(max_collateral_amount);
+    let pools = MirrorAssetPools::query(deps, &context, &mirror_asset_cw20_addr)?;
+    Ok(
+        Response::new().add_messages(open_or_increase_cdp_and_swap_long(
+            &context,
+            &pools,
+            Asset {
+                info: collateral_asset_info,
+                amount: collateral_amount,
+            },
+            collateral_uusd_price,
+            &mirror_asset_cw20_addr,
+            mirror_asset_oracle_uusd_rate,
+            collateral_ratio,
+            cdp_idx,
+            max_spread,
+        )?),
+    )
+}
+
+// Posts `collateral` to open or increase the CDP, and swaps uusd for the amount of mAsset minted against it in the long swap pool.
+#[allow(clippy::too_many_arguments)]
+fn open_or_increase_cdp_and_swap_long(
+    context: &Context,
+    pools: &MirrorAssetPools,
+    collateral: Asset,
+    collateral_uusd_price: Decimal,
+    mirror_asset_cw20_addr: &Addr,
+    mirror_asset_oracle_uusd_rate: Decimal,
+    collateral_ratio: Decimal,
+    cdp_idx: Option<Uint128>,
+    max_spread: Option<Decimal>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mirror_asset_mint_amount = compute_mirror_asset_mint_amount(
+        collateral.amount,
+        collateral_uusd_price,
+        mirror_asset_oracle_uusd_rate,
+        collateral_ratio,
+    );
+    let uusd_long_swap_amount = pools.compute_uusd_long_swap_amount(mirror_asset_mint_amount)?;
+    let mut messages = open_or_increase_cdp(
+        context,
         collateral_ratio,
-        collateral_anchor_ust_amount,
+        collateral,
         mirror_asset_cw20_addr.to_string(),
         mirror_asset_mint_amount,
         cdp_idx,
+        max_spread,
+    )?;
+    messages.push(create_uusd_swap_message(
+        pools.venue,
+        &pools.long_swap_pair_addr,
+        uusd_long_swap_amount,
+        max_spread,
     )?);
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: pair_info.contract_addr,
-        msg: to_binary(&terraswap::pair::ExecuteMsg::Swap {
-            offer_asset: get_uusd_asset_from_amount(uusd_long_swap_amount),
-            belief_price: None,
-            max_spread: None,
-            to: None,
-        })?,
-        funds: vec![get_uusd_coin_from_amount(uusd_long_swap_amount)],
-    }));
     Ok(messages)
 }
 
+// Opens a new CDP (if `cdp_idx` is None) or deposits to the existing CDP with `collateral`, and mints `mirror_asset_mint_amount` amount of mAsset for short sale.
+// cw20 collateral is sent to Mirror Mint with a hook message; native collateral is attached as funds.
+// The short sale fails if its spread exceeds `max_spread`.
 fn open_or_increase_cdp(
     context: &Context,
     collateral_ratio: Decimal,
-    collateral_anchor_ust_amount: Uint128,
+    collateral: Asset,
     mirror_asset_cw20_addr: String,
     mirror_asset_mint_amount: Uint128,
     cdp_idx: Option<Uint128>,
+    max_spread: Option<Decimal>,
 ) -> StdResult<Vec<CosmosMsg>> {
-    match cdp_idx {
-        None => Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: context.anchor_ust_cw20_addr.to_string(),
+    let asset_info = AssetInfo::Token {
+        contract_addr: mirror_asset_cw20_addr,
+    };
+    let short_params = Some(mirror_protocol::mint::ShortParams {
+        belief_price: None,
+        max_spread,
+    });
+    let deposit_message = match (&collateral.info, cdp_idx) {
+        (AssetInfo::Token { contract_addr }, None) => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.clone(),
             msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
                 contract: context.mirror_mint_addr.to_string(),
-                amount: collateral_anchor_ust_amount,
+                amount: collateral.amount,
                 msg: to_binary(&mirror_protocol::mint::Cw20HookMsg::OpenPosition {
-                    asset_info: AssetInfo::Token {
-                        contract_addr: mirror_asset_cw20_addr,
-                    },
+                    asset_info: asset_info.clone(),
                     collateral_ratio,
-                    short_params: Some(mirror_protocol::mint::ShortParams {
-                        belief_price: None,
-                        max_spread: None,
-                    }),
+                    short_params: short_params.clone(),
                 })?,
             })?,
             funds: vec![],
-        })]),
-        Some(position_idx) => Ok(vec![
+        }),
+        (AssetInfo::NativeToken { denom }, None) => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: context.mirror_mint_addr.to_string(),
+            funds: vec![Coin {
+                denom: denom.clone(),
+                amount: collateral.amount,
+            }],
+            msg: to_binary(&mirror_protocol::mint::ExecuteMsg::OpenPosition {
+                collateral: collateral.clone(),
+                asset_info: asset_info.clone(),
+                collateral_ratio,
+                short_params: short_params.clone(),
+            })?,
+        }),
+        (AssetInfo::Token { contract_addr }, Some(position_idx)) => {
             CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: context.anchor_ust_cw20_addr.to_string(),
+                contract_addr: contract_addr.clone(),
                 msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
                     contract: context.mirror_mint_addr.to_string(),
-                    amount: collateral_anchor_ust_amount,
+                    amount: collateral.amount,
                     msg: to_binary(&mirror_protocol::mint::Cw20HookMsg::Deposit { position_idx })?,
                 })?,
                 funds: vec![],
-            }),
+            })
+        }
+        (AssetInfo::NativeToken { denom }, Some(position_idx)) => {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: context.mirror_mint_addr.to_string(),
+                funds: vec![Coin {
+                    denom: denom.clone(),
+                    amount: collateral.amount,
+                }],
+                msg: to_binary(&mirror_protocol::mint::ExecuteMsg::Deposit {
+                    position_idx,
+                    collateral: collateral.clone(),
+                })?,
+            })
+        }
+    };
+    match cdp_idx {
+        None => Ok(vec![deposit_message]),
+        Some(position_idx) => Ok(vec![
+            deposit_message,
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: context.mirror_mint_addr.to_string(),
                 msg: to_binary(&mirror_protocol::mint::ExecuteMsg::Mint {
                     position_idx,
                     asset: Asset {
-                        info: AssetInfo::Token {
-                            contract_addr: mirror_asset_cw20_addr,
-                        },
+                        info: asset_info,
                         amount: mirror_asset_mint_amount,
                     },
-                    short_params: Some(mirror_protocol::mint::ShortParams {
-                        belief_price: None,
-                        max_spread: None,
-                    }),
+                    short_params,
                 })?,
                 funds: vec![],
             }),
@@ -247,53 +438,26 @@ fn open_or_increase_cdp(
 
 #[test]
 fn test_delta_neutral_invest() {
+    use crate::mock_querier::{mock_context, mock_deps};
+    use crate::state::{COLLATERAL_ASSET_INFO, LIQUIDITY_VENUE};
+    use crate::util::get_uusd_coin_from_amount;
+    use aperture_common::delta_neutral_position_manager::MirrorAssetStrategyParamOverrides;
     use cosmwasm_std::testing::mock_env;
     use cosmwasm_std::Addr;
 
-    let terraswap_factory_addr = Addr::unchecked("mock_terraswap_factory");
-    let astroport_factory_addr = Addr::unchecked("mock_astroport_factory");
     let cw20_token_addr = Addr::unchecked("mock_cw20_addr");
-    let terraswap_pair_addr = Addr::unchecked("mock_terraswap_pair");
-    let astroport_pair_addr = Addr::unchecked("mock_astroport_pair");
-    let querier = crate::mock_querier::WasmMockQuerier::new(
-        terraswap_factory_addr.to_string(),
-        astroport_factory_addr.to_string(),
-        terraswap_pair_addr.to_string(),
-        astroport_pair_addr.to_string(),
-        Uint128::from(10u128),
-        Uint128::from(9u128),
-        cw20_token_addr.to_string(),
+    let mut deps = mock_deps(
+        cw20_token_addr.as_str(),
         Uint128::from(1000000u128),
         Uint128::from(9000000u128),
         Uint128::zero(),
     );
-    let mut deps = cosmwasm_std::OwnedDeps {
-        storage: cosmwasm_std::testing::MockStorage::default(),
-        api: cosmwasm_std::testing::MockApi::default(),
-        querier,
-    };
 
     let env = mock_env();
-    let context = Context {
-        controller: Addr::unchecked("controller"),
-        anchor_ust_cw20_addr: Addr::unchecked("anchor_ust_cw20"),
-        mirror_cw20_addr: Addr::unchecked("mirror_cw20"),
-        spectrum_cw20_addr: Addr::unchecked("spectrum_cw20"),
-        anchor_market_addr: Addr::unchecked("anchor_market"),
-        mirror_collateral_oracle_addr: Addr::unchecked("mirror_collateral_oracle"),
-        mirror_lock_addr: Addr::unchecked("mirror_lock"),
-        mirror_mint_addr: Addr::unchecked("mirror_mint"),
-        mirror_oracle_addr: Addr::unchecked("mirror_oracle"),
-        mirror_staking_addr: Addr::unchecked("mirror_staking"),
-        spectrum_gov_addr: Addr::unchecked("spectrum_gov"),
-        spectrum_mirror_farms_addr: Addr::unchecked("spectrum_mirror_farms"),
-        spectrum_staker_addr: Addr::unchecked("spectrum_staker"),
-        terraswap_factory_addr,
-        astroport_factory_addr,
-        collateral_ratio_safety_margin: Decimal::from_ratio(3u128, 10u128),
-        min_open_uusd_amount: Uint128::from(500u128),
-        min_reinvest_uusd_amount: Uint128::from(10u128),
-    };
+    let context = mock_context();
+    let strategy_params = MirrorAssetStrategyParamOverrides::default()
+        .apply(&context)
+        .unwrap();
     let target_collateral_ratio_range = &TargetCollateralRatioRange {
         min: Decimal::from_ratio(18u128, 10u128),
         max: Decimal::from_ratio(22u128, 10u128),
@@ -302,6 +466,7 @@ fn test_delta_neutral_invest() {
         deps.as_mut(),
         &env,
         context.clone(),
+        &strategy_params,
         Uint128::from(600u128),
         target_collateral_ratio_range,
         &cw20_token_addr,
@@ -361,6 +526,7 @@ fn test_delta_neutral_invest() {
             deps.as_mut(),
             &env,
             context.clone(),
+            &strategy_params,
             Uint128::from(600u128),
             &TargetCollateralRatioRange {
                 min: Decimal::from_ratio(16u128, 10u128),
@@ -379,6 +545,7 @@ fn test_delta_neutral_invest() {
             deps.as_mut(),
             &env,
             context.clone(),
+            &strategy_params,
             Uint128::from(600u128),
             &TargetCollateralRatioRange {
                 min: Decimal::from_ratio(18u128, 10u128),
@@ -396,7 +563,8 @@ fn test_delta_neutral_invest() {
         delta_neutral_invest(
             deps.as_mut(),
             &env,
-            context,
+            context.clone(),
+            &strategy_params,
             Uint128::from(600u128),
             &TargetCollateralRatioRange {
                 min: Decimal::from_ratio(18u128, 10u128),
@@ -409,4 +577,273 @@ fn test_delta_neutral_invest() {
         .unwrap_err(),
         StdError::generic_err("target_max_collateral_ratio too small")
     );
+
+    // Per-mAsset overrides relax the range width requirement and bound the spread of the short sale and the long swap.
+    let messages = delta_neutral_invest(
+        deps.as_mut(),
+        &env,
+        context.clone(),
+        &MirrorAssetStrategyParamOverrides {
+            min_target_collateral_ratio_range_width: Some(Decimal::from_ratio(3u128, 10u128)),
+            slippage_tolerance: Some(Decimal::percent(1)),
+            ..MirrorAssetStrategyParamOverrides::default()
+        }
+        .apply(&context)
+        .unwrap(),
+        Uint128::from(600u128),
+        &TargetCollateralRatioRange {
+            min: Decimal::from_ratio(18u128, 10u128),
+            max: Decimal::from_ratio(21u128, 10u128),
+        },
+        &cw20_token_addr,
+        Decimal::from_ratio(10u128, 1u128),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        messages.last().unwrap(),
+        &CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("mock_terraswap_pair"),
+            msg: to_binary(&terraswap::pair::ExecuteMsg::Swap {
+                offer_asset: Asset {
+                    amount: Uint128::from(189u128),
+                    info: AssetInfo::NativeToken {
+                        denom: String::from("uusd"),
+                    }
+                },
+                belief_price: None,
+                max_spread: Some(Decimal::percent(1)),
+                to: None,
+            })
+            .unwrap(),
+            funds: vec![get_uusd_coin_from_amount(Uint128::from(189u128))],
+        })
+    );
+
+    // With Astroport as the liquidity venue, the long swap takes place in the Astroport pool, which does not benefit from the short sale lowering the Terraswap price.
+    LIQUIDITY_VENUE
+        .save(deps.as_mut().storage, &LiquidityVenue::Astroport)
+        .unwrap();
+    let messages = delta_neutral_invest(
+        deps.as_mut(),
+        &env,
+        context.clone(),
+        &strategy_params,
+        Uint128::from(600u128),
+        target_collateral_ratio_range,
+        &cw20_token_addr,
+        Decimal::from_ratio(10u128, 1u128),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        messages.last().unwrap(),
+        &CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("mock_astroport_pair"),
+            msg: to_binary(&astroport::pair::ExecuteMsg::Swap {
+                offer_asset: astroport::asset::Asset {
+                    amount: Uint128::from(181u128),
+                    info: astroport::asset::AssetInfo::NativeToken {
+                        denom: String::from("uusd"),
+                    }
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })
+            .unwrap(),
+            funds: vec![get_uusd_coin_from_amount(Uint128::from(181u128))],
+        })
+    );
+
+    // With native uusd as collateral, the collateral is attached to Mirror Mint OpenPosition as funds and valued at the collateral oracle price.
+    COLLATERAL_ASSET_INFO
+        .save(
+            deps.as_mut().storage,
+            &AssetInfo::NativeToken {
+                denom: String::from("uusd"),
+            },
+        )
+        .unwrap();
+    let messages = delta_neutral_invest(
+        deps.as_mut(),
+        &env,
+        context,
+        &strategy_params,
+        Uint128::from(600u128),
+        target_collateral_ratio_range,
+        &cw20_token_addr,
+        Decimal::from_ratio(10u128, 1u128),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        messages[0],
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("mirror_mint"),
+            msg: to_binary(&mirror_protocol::mint::ExecuteMsg::OpenPosition {
+                collateral: Asset {
+                    amount: Uint128::from(401u128),
+                    info: AssetInfo::NativeToken {
+                        denom: String::from("uusd"),
+                    },
+                },
+                asset_info: AssetInfo::Token {
+                    contract_addr: cw20_token_addr.to_string(),
+                },
+                collateral_ratio: target_collateral_ratio_range.midpoint(),
+                short_params: Some(mirror_protocol::mint::ShortParams {
+                    belief_price: None,
+                    max_spread: None,
+                }),
+            })
+            .unwrap(),
+            funds: vec![get_uusd_coin_from_amount(Uint128::from(401u128))],
+        })
+    );
+    assert_eq!(messages.len(), 2);
+}
+
+#[test]
+fn test_open_or_increase_cdp_with_collateral_balance() {
+    use crate::mock_querier::{mock_context, mock_deps};
+    use crate::state::COLLATERAL_ASSET_INFO;
+    use aperture_common::delta_neutral_position_manager::MirrorAssetStrategyParamOverrides;
+    use cosmwasm_std::testing::mock_env;
+    use cosmwasm_std::{from_binary, Addr};
+
+    let cw20_token_addr = Addr::unchecked("mock_cw20_addr");
+    let mut deps = mock_deps(
+        cw20_token_addr.as_str(),
+        Uint128::from(1000000u128),
+        Uint128::from(9000000u128),
+        Uint128::zero(),
+    );
+    let env = mock_env();
+    let context = mock_context();
+    let strategy_params = MirrorAssetStrategyParamOverrides {
+        slippage_tolerance: Some(Decimal::percent(1)),
+        ..MirrorAssetStrategyParamOverrides::default()
+    }
+    .apply(&context)
+    .unwrap();
+    let target_collateral_ratio_range = TargetCollateralRatioRange {
+        min: Decimal::from_ratio(18u128, 10u128),
+        max: Decimal::from_ratio(22u128, 10u128),
+    };
+    let collateral_asset_info = AssetInfo::Token {
+        contract_addr: cw20_token_addr.to_string(),
+    };
+    MIRROR_ASSET_CW20_ADDR
+        .save(deps.as_mut().storage, &cw20_token_addr)
+        .unwrap();
+    COLLATERAL_ASSET_INFO
+        .save(deps.as_mut().storage, &collateral_asset_info)
+        .unwrap();
+
+    // uusd is swapped for collateral with the strategy's slippage tolerance, and the collateral is posted afterwards by an internal message.
+    let messages = delta_neutral_invest(
+        deps.as_mut(),
+        &env,
+        context.clone(),
+        &strategy_params,
+        Uint128::from(600u128),
+        &target_collateral_ratio_range,
+        &cw20_token_addr,
+        Decimal::from_ratio(10u128, 1u128),
+        None,
+    )
+    .unwrap();
+    assert_eq!(messages.len(), 2);
+    match &messages[0] {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, "mock_terraswap_pair");
+            match from_binary(msg).unwrap() {
+                terraswap::pair::ExecuteMsg::Swap { max_spread, .. } => {
+                    assert_eq!(max_spread, Some(Decimal::percent(1)))
+                }
+                _ => panic!("unexpected swap message"),
+            }
+        }
+        _ => panic!("unexpected message"),
+    }
+    let (collateral_balance_before, max_collateral_amount) = match &messages[1] {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, env.contract.address.as_str());
+            match from_binary(msg).unwrap() {
+                aperture_common::delta_neutral_position::ExecuteMsg::Internal(
+                    InternalExecuteMsg::OpenOrIncreaseCdpWithCollateralBalance {
+                        collateral_balance_before,
+                        max_collateral_amount,
+                        collateral_ratio,
+                        cdp_idx,
+                        max_spread,
+                        ..
+                    },
+                ) => {
+                    assert_eq!(collateral_ratio, target_collateral_ratio_range.midpoint());
+                    assert_eq!(cdp_idx, None);
+                    assert_eq!(max_spread, Some(Decimal::percent(1)));
+                    (collateral_balance_before, max_collateral_amount)
+                }
+                _ => panic!("unexpected internal message"),
+            }
+        }
+        _ => panic!("unexpected message"),
+    };
+    assert_eq!(collateral_balance_before, Uint128::from(1000000u128));
+    assert!(!max_collateral_amount.is_zero());
+
+    // Only the collateral actually received from the swap is posted, and the long swap buys the amount of mAsset minted against it.
+    let posted_collateral_amount = |deps: Deps| {
+        let response = open_or_increase_cdp_with_collateral_balance(
+            deps,
+            env.clone(),
+            context.clone(),
+            collateral_balance_before,
+            max_collateral_amount,
+            target_collateral_ratio_range.midpoint(),
+            Decimal::one(),
+            Decimal::from_ratio(10u128, 1u128),
+            None,
+            Some(Decimal::percent(1)),
+        )
+        .unwrap();
+        assert_eq!(response.messages.len(), 2);
+        match &response.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) => {
+                assert_eq!(contract_addr, cw20_token_addr.as_str());
+                match from_binary(msg).unwrap() {
+                    cw20::Cw20ExecuteMsg::Send {
+                        contract, amount, ..
+                    } => {
+                        assert_eq!(contract, "mirror_mint");
+                        amount
+                    }
+                    _ => panic!("unexpected cw20 message"),
+                }
+            }
+            _ => panic!("unexpected message"),
+        }
+    };
+    deps.querier.terraswap_pool_cw20_balance =
+        collateral_balance_before + max_collateral_amount - Uint128::from(1u128);
+    assert_eq!(
+        posted_collateral_amount(deps.as_ref()),
+        max_collateral_amount - Uint128::from(1u128)
+    );
+
+    // Any collateral received beyond the simulated amount is not posted.
+    deps.querier.terraswap_pool_cw20_balance =
+        collateral_balance_before + max_collateral_amount + Uint128::from(1u128);
+    assert_eq!(
+        posted_collateral_amount(deps.as_ref()),
+        max_collateral_amount
+    );
 }
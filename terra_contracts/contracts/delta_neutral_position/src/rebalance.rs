@@ -1,23 +1,31 @@
-use std::cmp::Ordering;
+use std::cmp::{min, Ordering};
 
-use aperture_common::delta_neutral_position::PositionState;
-use aperture_common::delta_neutral_position_manager::Context;
-use cosmwasm_std::{to_binary, Coin, CosmosMsg, Decimal, Deps, Env, StdResult, Uint128, WasmMsg};
+use aperture_common::delta_neutral_position::{LiquidityVenue, PositionState, SpecRewardPolicy};
+use aperture_common::delta_neutral_position_manager::{Context, EmergencyCloseConfig};
+use cosmwasm_std::{to_binary, CosmosMsg, Decimal, Deps, Env, StdResult, Uint128, WasmMsg};
+use terraswap::asset::{Asset, AssetInfo};
 
-use crate::dex_util::{simulate_terraswap_swap, swap_cw20_token_for_uusd};
+use crate::dex_util::{
+    create_uusd_swap_message, simulate_terraswap_swap, swap_cw20_token_for_uusd,
+};
+use crate::limit_order_util::{
+    create_cancel_limit_order_messages, create_submit_limit_order_message,
+    get_outstanding_limit_orders,
+};
 use crate::spectrum_util::{
-    get_spectrum_mirror_lp_balance, get_spectrum_mirror_pool_info,
-    simulate_spectrum_mirror_farm_unbond, unstake_lp_from_spectrum_and_withdraw_liquidity,
+    create_spectrum_gov_stake_message, get_spectrum_mirror_lp_balance,
+    get_spectrum_mirror_pool_info, simulate_spectrum_mirror_farm_unbond,
 };
 use crate::state::{CDP_IDX, MIRROR_ASSET_CW20_ADDR};
 use crate::util::{
     find_unclaimed_mir_amount, find_unclaimed_spec_amount, get_cdp_uusd_lock_info_result,
-    get_position_state, get_uusd_asset_from_amount,
+    get_position_state, get_position_state_at_pool_price, get_spec_reward_policy,
+    get_uusd_asset_from_amount, unstake_lp_and_withdraw_liquidity,
 };
 
 // Claim all available reward and redeem for uusd:
 // (1) MIR reward from Mirror short farm.
-// (2) SPEC reward from Spectrum Mirror long farm.
+// (2) SPEC reward from Spectrum Mirror long farm; under `SpecRewardPolicy::Stake`, SPEC is staked in Spectrum governance instead.
 // (3) Unlocked short sale proceeds, e.g. two weeks after position open or the previous reinvest event.
 pub fn claim_and_increase_uusd_balance(
     deps: Deps,
@@ -49,16 +57,25 @@ pub fn claim_and_increase_uusd_balance(
             funds: vec![],
         }));
 
-        // Swap SPEC reward for uusd.
-        let (spec_swap_msg, uusd_return_amount) = swap_cw20_token_for_uusd(
-            &deps.querier,
-            &context.terraswap_factory_addr,
-            &context.astroport_factory_addr,
-            &context.spectrum_cw20_addr,
-            spec_reward,
-        )?;
-        messages.push(spec_swap_msg);
-        uusd_increase_amount += uusd_return_amount;
+        match get_spec_reward_policy(deps.storage)? {
+            SpecRewardPolicy::Sell => {
+                // Swap SPEC reward for uusd.
+                let (spec_swap_msg, uusd_return_amount) = swap_cw20_token_for_uusd(
+                    &deps.querier,
+                    &context.terraswap_factory_addr,
+                    &context.astroport_factory_addr,
+                    &context.spectrum_cw20_addr,
+                    spec_reward,
+                    None,
+                )?;
+                messages.push(spec_swap_msg);
+                uusd_increase_amount += uusd_return_amount;
+            }
+            SpecRewardPolicy::Stake => {
+                // Stake SPEC reward in Spectrum governance.
+                messages.push(create_spectrum_gov_stake_message(context, spec_reward)?);
+            }
+        }
     }
     if mir_reward > Uint128::zero() {
         // Claim MIR reward.
@@ -75,6 +92,7 @@ pub fn claim_and_increase_uusd_balance(
             &context.astroport_factory_addr,
             &context.mirror_cw20_addr,
             mir_reward,
+            None,
         )?;
         messages.push(mir_swap_msg);
         uusd_increase_amount += uusd_return_amount;
@@ -97,41 +115,70 @@ pub fn claim_and_increase_uusd_balance(
     Ok((messages, uusd_increase_amount))
 }
 
+// If `emergency_close_config` is specified, the position state is obtained at the pool price instead of the oracle price, and swaps are bounded by `EmergencyCloseConfig.max_haircut`.
 pub fn achieve_delta_neutral(
     deps: Deps,
     env: &Env,
     context: &Context,
+    use_limit_order: bool,
+    emergency_close_config: Option<&EmergencyCloseConfig>,
 ) -> StdResult<Vec<CosmosMsg>> {
-    let mut state = get_position_state(deps, env, context)?;
+    let mut state = match emergency_close_config {
+        Some(_) => get_position_state_at_pool_price(deps, env, context)?,
+        None => get_position_state(deps, env, context)?,
+    };
 
     // We first claim all available reward and exchange them for uusd.
     let (mut messages, uusd_increase_amount) = claim_and_increase_uusd_balance(deps, env, context)?;
     state.uusd_balance += uusd_increase_amount;
 
-    messages.extend(achieve_delta_neutral_from_state(deps, context, &state)?);
+    // Cancel outstanding limit orders so that their unfilled offer amounts are refunded and available for rebalancing.
+    messages.extend(create_cancel_limit_order_messages(
+        context,
+        &get_outstanding_limit_orders(&deps.querier, env, context)?,
+    )?);
+    state.mirror_asset_balance += state.limit_order_mirror_asset_amount;
+    state.limit_order_mirror_asset_amount = Uint128::zero();
+    state.uusd_balance += state.limit_order_uusd_amount;
+    state.limit_order_uusd_amount = Uint128::zero();
+
+    messages.extend(achieve_delta_neutral_from_state(
+        deps,
+        context,
+        &state,
+        use_limit_order,
+        emergency_close_config.map(|config| config.max_haircut),
+    )?);
     Ok(messages)
 }
 
 // Brings the position back to delta-neutral.
 // Our goal here is to minimize the amount of LP tokens that have to be unstaked and liquidity withdrawn, as:
-// (1) Re-staking the liquidity is subject to a 0.1% Spectrum protocol fee.
-// (2) Performing swaps while holding LP tokens allows us to earn a portion of the 0.3% Terraswap fees.
+// (1) Re-staking the liquidity is subject to a 0.1% Spectrum protocol fee (Terraswap venue only).
+// (2) Performing swaps while holding LP tokens allows us to earn a portion of the 0.3% swap fees.
 //
 // An alternative, much simpler approach would be to unstake all LP tokens and withdraw liquidity, and then bring the position to delta-neutral without worrying about how Terraswap price movement affects our long position amount.
+//
+// If `use_limit_order` is true and the imbalance does not exceed `Context.limit_order_imbalance_threshold` (as a fraction of the short amount), we submit a Mirror limit order at the oracle price instead of swapping at the pool.
+// This avoids paying pool spread for small imbalances; the order is cancelled at the next rebalance, and any unfilled imbalance is handled again at that point.
+//
+// Pool swaps are bounded by `max_spread` against the pool price, if specified.
 pub fn achieve_delta_neutral_from_state(
     deps: Deps,
     context: &Context,
     state: &PositionState,
+    use_limit_order: bool,
+    max_spread: Option<Decimal>,
 ) -> StdResult<Vec<CosmosMsg>> {
     let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
-    let info = &state.terraswap_pool_info;
+    let info = &state.pool_info;
     let mut messages = vec![];
     let one = Uint128::from(1u128);
 
     // If there are LP tokens staked in Spectrum, obtain Spectrum Mirror Farm pool information which is used by simulate_spectrum_mirror_farm_unbond().
     let mut spectrum_pool_info = None;
     let mut spectrum_mirror_pool_lp_balance = Uint128::zero();
-    if !info.lp_token_amount.is_zero() {
+    if info.venue == LiquidityVenue::Terraswap && !info.lp_token_amount.is_zero() {
         spectrum_pool_info = Some(get_spectrum_mirror_pool_info(
             deps,
             &context.spectrum_mirror_farms_addr,
@@ -145,10 +192,151 @@ pub fn achieve_delta_neutral_from_state(
         )?;
     }
 
+    // Returns the amount of LP tokens remaining staked after unstaking `withdraw_lp_token_amount` from the long farm.
+    // Spectrum Mirror farm may leave less than the naive difference due to auto-compound share rounding; Mirror staking unbonds the exact amount.
+    let simulate_long_farm_unbond = |withdraw_lp_token_amount: Uint128| -> StdResult<Uint128> {
+        if withdraw_lp_token_amount.is_zero() {
+            return Ok(info.lp_token_amount);
+        }
+        match spectrum_pool_info.as_ref() {
+            Some(spectrum_pool_info) => simulate_spectrum_mirror_farm_unbond(
+                spectrum_mirror_pool_lp_balance,
+                spectrum_pool_info,
+                info.spectrum_auto_compound_share_amount,
+                withdraw_lp_token_amount,
+            ),
+            None => Ok(info.lp_token_amount.checked_sub(withdraw_lp_token_amount)?),
+        }
+    };
+
+    let imbalance_amount = if state.mirror_asset_long_amount > state.mirror_asset_short_amount {
+        state.mirror_asset_long_amount - state.mirror_asset_short_amount
+    } else {
+        state.mirror_asset_short_amount - state.mirror_asset_long_amount
+    };
+    let limit_order_addr = match context.mirror_limit_order_addr.as_ref() {
+        Some(limit_order_addr)
+            if use_limit_order
+                && imbalance_amount
+                    <= state.mirror_asset_short_amount
+                        * context.limit_order_imbalance_threshold =>
+        {
+            Some(limit_order_addr)
+        }
+        _ => None,
+    };
+
     match state
         .mirror_asset_long_amount
         .cmp(&state.mirror_asset_short_amount)
     {
+        Ordering::Greater if limit_order_addr.is_some() => {
+            // We are in a slightly net long position, so we offer the excess mAsset in a limit order at the oracle price.
+            // Binary search for the least amount of lp tokens to withdraw such that the mAsset remaining in the long farm does not exceed the short amount, i.e. the excess mAsset is liquid.
+            // Unlike a pool swap, a limit order does not move the pool price, so the long farm mAsset amount only changes due to the withdrawal itself.
+            // Nothing remains in the long farm if the withdrawal drains the pool.
+            let simulate_remaining_long_farm_mirror_asset_amount =
+                |withdraw_lp_token_amount: Uint128,
+                 withdrawn_mirror_asset_amount: Uint128|
+                 -> StdResult<Uint128> {
+                    let remaining_lp_token_total_supply = info
+                        .lp_token_total_supply
+                        .checked_sub(withdraw_lp_token_amount)?;
+                    if remaining_lp_token_total_supply.is_zero() {
+                        return Ok(Uint128::zero());
+                    }
+                    Ok(info
+                        .pool_mirror_asset_amount
+                        .checked_sub(withdrawn_mirror_asset_amount)?
+                        * Decimal::from_ratio(
+                            simulate_long_farm_unbond(withdraw_lp_token_amount)?,
+                            remaining_lp_token_total_supply,
+                        ))
+                };
+            let mut a = Uint128::zero();
+            let mut b = info.lp_token_amount;
+            while a < b {
+                let withdraw_lp_token_amount = (a + b) >> 1;
+                let withdrawn_mirror_asset_amount = info.pool_mirror_asset_amount
+                    * Decimal::from_ratio(withdraw_lp_token_amount, info.lp_token_total_supply);
+                let new_long_farm_mirror_asset_amount =
+                    simulate_remaining_long_farm_mirror_asset_amount(
+                        withdraw_lp_token_amount,
+                        withdrawn_mirror_asset_amount,
+                    )?;
+                if new_long_farm_mirror_asset_amount > state.mirror_asset_short_amount {
+                    a = withdraw_lp_token_amount + one;
+                } else {
+                    b = withdraw_lp_token_amount;
+                }
+            }
+            let withdraw_lp_token_amount = a;
+
+            let mut current_mirror_asset_balance = state.mirror_asset_balance;
+            let mut current_long_farm_mirror_asset_amount = state.mirror_asset_long_farm;
+            if withdraw_lp_token_amount > Uint128::zero() {
+                messages.extend(unstake_lp_and_withdraw_liquidity(
+                    context,
+                    &state.pool_info,
+                    &mirror_asset_cw20_addr,
+                    withdraw_lp_token_amount,
+                ));
+                let withdraw_lp_ratio =
+                    Decimal::from_ratio(withdraw_lp_token_amount, info.lp_token_total_supply);
+                let withdrawn_mirror_asset_amount =
+                    info.pool_mirror_asset_amount * withdraw_lp_ratio;
+                if withdrawn_mirror_asset_amount.is_zero()
+                    || (info.pool_uusd_amount * withdraw_lp_ratio).is_zero()
+                {
+                    // Strip the `Cw20HookMsg::WithdrawLiquidity` message since this would fail due to attempting to transfer a zero amount.
+                    messages.truncate(messages.len() - 1);
+                }
+                current_mirror_asset_balance += withdrawn_mirror_asset_amount;
+                current_long_farm_mirror_asset_amount =
+                    simulate_remaining_long_farm_mirror_asset_amount(
+                        withdraw_lp_token_amount,
+                        withdrawn_mirror_asset_amount,
+                    )?;
+            }
+
+            let offer_mirror_asset_amount = min(
+                (current_mirror_asset_balance + current_long_farm_mirror_asset_amount)
+                    .saturating_sub(state.mirror_asset_short_amount),
+                current_mirror_asset_balance,
+            );
+            let ask_uusd_amount = offer_mirror_asset_amount * state.mirror_asset_oracle_price;
+            if !offer_mirror_asset_amount.is_zero() && !ask_uusd_amount.is_zero() {
+                messages.push(create_submit_limit_order_message(
+                    limit_order_addr.unwrap(),
+                    Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: mirror_asset_cw20_addr.to_string(),
+                        },
+                        amount: offer_mirror_asset_amount,
+                    },
+                    get_uusd_asset_from_amount(ask_uusd_amount),
+                )?);
+            }
+        }
+        Ordering::Less
+            if limit_order_addr.is_some()
+                && state.uusd_balance >= imbalance_amount * state.mirror_asset_oracle_price =>
+        {
+            // We are in a slightly net short position and have enough uusd, so we bid for the missing mAsset in a limit order at the oracle price.
+            let offer_uusd_amount = imbalance_amount * state.mirror_asset_oracle_price;
+            if !offer_uusd_amount.is_zero() {
+                messages.push(create_submit_limit_order_message(
+                    limit_order_addr.unwrap(),
+                    get_uusd_asset_from_amount(offer_uusd_amount),
+                    Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: mirror_asset_cw20_addr.to_string(),
+                        },
+                        amount: imbalance_amount,
+                    },
+                )?);
+            }
+        }
         Ordering::Greater => {
             // We are in a net long position.
             // First, we binary search for the least amount of lp tokens to withdraw such that swapping (mAsset balance + withdrawn mAsset) for uusd is sufficient to bring us back to neutral.
@@ -162,17 +350,9 @@ pub fn achieve_delta_neutral_from_state(
             while a < b {
                 let withdraw_lp_token_amount = (a + b) >> 1;
                 let pool_mirror_asset_amount_after_swap =
-                    info.terraswap_pool_mirror_asset_amount + state.mirror_asset_balance;
-                let lp_token_amount_after_withdrawal = if withdraw_lp_token_amount.is_zero() {
-                    info.lp_token_amount
-                } else {
-                    simulate_spectrum_mirror_farm_unbond(
-                        spectrum_mirror_pool_lp_balance,
-                        spectrum_pool_info.as_ref().unwrap(),
-                        info.spectrum_auto_compound_share_amount,
-                        withdraw_lp_token_amount,
-                    )?
-                };
+                    info.pool_mirror_asset_amount + state.mirror_asset_balance;
+                let lp_token_amount_after_withdrawal =
+                    simulate_long_farm_unbond(withdraw_lp_token_amount)?;
                 let new_long_farm_mirror_asset_amount = pool_mirror_asset_amount_after_swap
                     * Decimal::from_ratio(
                         lp_token_amount_after_withdrawal,
@@ -189,34 +369,29 @@ pub fn achieve_delta_neutral_from_state(
             // We determined that we should redeem `withdraw_lp_token_amount` amount of LP tokens for liquid mAsset + UST.
             // If this amount is zero, then we do nothing; otherwise, we generate the necessary ExecuteMsg items and the state after liquidity withdrawal.
             let mut current_mirror_asset_balance = state.mirror_asset_balance;
-            let mut current_pool_mirror_asset_amount = info.terraswap_pool_mirror_asset_amount;
+            let mut current_pool_mirror_asset_amount = info.pool_mirror_asset_amount;
             let mut current_lp_token_amount = info.lp_token_amount;
             let mut current_lp_token_total_supply = info.lp_token_total_supply;
             if withdraw_lp_token_amount > Uint128::zero() {
-                messages.extend(unstake_lp_from_spectrum_and_withdraw_liquidity(
-                    &state.terraswap_pool_info,
-                    &context.spectrum_mirror_farms_addr,
+                messages.extend(unstake_lp_and_withdraw_liquidity(
+                    context,
+                    &state.pool_info,
                     &mirror_asset_cw20_addr,
                     withdraw_lp_token_amount,
                 ));
                 let withdraw_lp_ratio =
                     Decimal::from_ratio(withdraw_lp_token_amount, info.lp_token_total_supply);
                 let withdrawn_mirror_asset_amount =
-                    info.terraswap_pool_mirror_asset_amount * withdraw_lp_ratio;
-                let withdrawn_uusd_amount = info.terraswap_pool_uusd_amount * withdraw_lp_ratio;
+                    info.pool_mirror_asset_amount * withdraw_lp_ratio;
+                let withdrawn_uusd_amount = info.pool_uusd_amount * withdraw_lp_ratio;
                 if withdrawn_mirror_asset_amount.is_zero() || withdrawn_uusd_amount.is_zero() {
-                    // Strip the `terraswap::pair::Cw20HookMsg::WithdrawLiquidity` message since this would fail due to attempting to transfer a zero amount.
+                    // Strip the `Cw20HookMsg::WithdrawLiquidity` message since this would fail due to attempting to transfer a zero amount.
                     messages.truncate(messages.len() - 1);
                 }
 
                 current_mirror_asset_balance += withdrawn_mirror_asset_amount;
                 current_pool_mirror_asset_amount -= withdrawn_mirror_asset_amount;
-                current_lp_token_amount = simulate_spectrum_mirror_farm_unbond(
-                    spectrum_mirror_pool_lp_balance,
-                    spectrum_pool_info.as_ref().unwrap(),
-                    info.spectrum_auto_compound_share_amount,
-                    withdraw_lp_token_amount,
-                )?;
+                current_lp_token_amount = simulate_long_farm_unbond(withdraw_lp_token_amount)?;
                 current_lp_token_total_supply -= withdraw_lp_token_amount;
             }
 
@@ -252,6 +427,7 @@ pub fn achieve_delta_neutral_from_state(
                         &context.astroport_factory_addr,
                         &mirror_asset_cw20_addr,
                         mirror_asset_offer_amount,
+                        max_spread,
                     )?
                     .0,
                 );
@@ -271,23 +447,14 @@ pub fn achieve_delta_neutral_from_state(
                 let withdraw_lp_token_amount = (a + b) >> 1;
                 let fraction =
                     Decimal::from_ratio(withdraw_lp_token_amount, info.lp_token_total_supply);
-                let withdrawn_mirror_asset_amount =
-                    info.terraswap_pool_mirror_asset_amount * fraction;
-                let withdrawn_uusd_amount = info.terraswap_pool_uusd_amount * fraction;
+                let withdrawn_mirror_asset_amount = info.pool_mirror_asset_amount * fraction;
+                let withdrawn_uusd_amount = info.pool_uusd_amount * fraction;
                 let pool_mirror_asset_amount_after_withdrawal =
-                    info.terraswap_pool_mirror_asset_amount - withdrawn_mirror_asset_amount;
+                    info.pool_mirror_asset_amount - withdrawn_mirror_asset_amount;
                 let pool_uusd_amount_after_withdrawal =
-                    info.terraswap_pool_uusd_amount - withdrawn_uusd_amount;
-                let lp_token_amount_after_withdrawal = if withdraw_lp_token_amount.is_zero() {
-                    info.lp_token_amount
-                } else {
-                    simulate_spectrum_mirror_farm_unbond(
-                        spectrum_mirror_pool_lp_balance,
-                        spectrum_pool_info.as_ref().unwrap(),
-                        info.spectrum_auto_compound_share_amount,
-                        withdraw_lp_token_amount,
-                    )?
-                };
+                    info.pool_uusd_amount - withdrawn_uusd_amount;
+                let lp_token_amount_after_withdrawal =
+                    simulate_long_farm_unbond(withdraw_lp_token_amount)?;
                 let (_, pool_mirror_asset_amount_after_swap, return_mirror_asset_amount) =
                     simulate_terraswap_swap(
                         pool_uusd_amount_after_withdrawal,
@@ -315,32 +482,27 @@ pub fn achieve_delta_neutral_from_state(
             // If this amount is zero, then we do nothing; otherwise, we generate the necessary ExecuteMsg items and the state after liquidity withdrawal.
             let mut current_mirror_asset_balance = state.mirror_asset_balance;
             let mut current_uusd_balance = state.uusd_balance;
-            let mut current_pool_mirror_asset_amount = info.terraswap_pool_mirror_asset_amount;
-            let mut current_pool_uusd_amount = info.terraswap_pool_uusd_amount;
+            let mut current_pool_mirror_asset_amount = info.pool_mirror_asset_amount;
+            let mut current_pool_uusd_amount = info.pool_uusd_amount;
             let mut current_lp_token_amount = info.lp_token_amount;
             let mut current_lp_token_total_supply = info.lp_token_total_supply;
             if withdraw_lp_token_amount > Uint128::zero() {
-                messages.extend(unstake_lp_from_spectrum_and_withdraw_liquidity(
-                    &state.terraswap_pool_info,
-                    &context.spectrum_mirror_farms_addr,
+                messages.extend(unstake_lp_and_withdraw_liquidity(
+                    context,
+                    &state.pool_info,
                     &mirror_asset_cw20_addr,
                     withdraw_lp_token_amount,
                 ));
                 let withdraw_lp_ratio =
                     Decimal::from_ratio(withdraw_lp_token_amount, info.lp_token_total_supply);
                 let withdrawn_mirror_asset_amount =
-                    info.terraswap_pool_mirror_asset_amount * withdraw_lp_ratio;
-                let withdrawn_uusd_amount = info.terraswap_pool_uusd_amount * withdraw_lp_ratio;
+                    info.pool_mirror_asset_amount * withdraw_lp_ratio;
+                let withdrawn_uusd_amount = info.pool_uusd_amount * withdraw_lp_ratio;
                 current_mirror_asset_balance += withdrawn_mirror_asset_amount;
                 current_uusd_balance += withdrawn_uusd_amount;
                 current_pool_mirror_asset_amount -= withdrawn_mirror_asset_amount;
                 current_pool_uusd_amount -= withdrawn_uusd_amount;
-                current_lp_token_amount = simulate_spectrum_mirror_farm_unbond(
-                    spectrum_mirror_pool_lp_balance,
-                    spectrum_pool_info.as_ref().unwrap(),
-                    info.spectrum_auto_compound_share_amount,
-                    withdraw_lp_token_amount,
-                )?;
+                current_lp_token_amount = simulate_long_farm_unbond(withdraw_lp_token_amount)?;
                 current_lp_token_total_supply -= withdraw_lp_token_amount;
             }
 
@@ -371,19 +533,12 @@ pub fn achieve_delta_neutral_from_state(
                     b = offer_uusd_amount;
                 }
             }
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: info.terraswap_pair_addr.clone(),
-                msg: to_binary(&terraswap::pair::ExecuteMsg::Swap {
-                    offer_asset: get_uusd_asset_from_amount(a),
-                    max_spread: None,
-                    belief_price: None,
-                    to: None,
-                })?,
-                funds: vec![Coin {
-                    denom: String::from("uusd"),
-                    amount: a,
-                }],
-            }));
+            messages.push(create_uusd_swap_message(
+                info.venue,
+                &info.pair_addr,
+                a,
+                max_spread,
+            )?);
         }
         Ordering::Equal => {}
     }
@@ -392,31 +547,18 @@ pub fn achieve_delta_neutral_from_state(
 
 #[test]
 fn test_achieve_delta_neutral() {
+    use crate::mock_querier::{mock_context, mock_deps};
     use cosmwasm_std::testing::mock_env;
     use cosmwasm_std::{Addr, Timestamp};
 
-    let terraswap_factory_addr = Addr::unchecked("mock_terraswap_factory");
-    let astroport_factory_addr = Addr::unchecked("mock_astroport_factory");
     let cw20_token_addr = Addr::unchecked("mock_cw20_addr");
     let terraswap_pair_addr = Addr::unchecked("mock_terraswap_pair");
-    let astroport_pair_addr = Addr::unchecked("mock_astroport_pair");
-    let querier = crate::mock_querier::WasmMockQuerier::new(
-        terraswap_factory_addr.to_string(),
-        astroport_factory_addr.to_string(),
-        terraswap_pair_addr.to_string(),
-        astroport_pair_addr.to_string(),
-        Uint128::from(10u128),
-        Uint128::from(9u128),
-        cw20_token_addr.to_string(),
+    let mut deps = mock_deps(
+        cw20_token_addr.as_str(),
         Uint128::from(1000000u128),
         Uint128::from(9000000u128),
         Uint128::from(1u128),
     );
-    let mut deps = cosmwasm_std::OwnedDeps {
-        storage: cosmwasm_std::testing::MockStorage::default(),
-        api: cosmwasm_std::testing::MockApi::default(),
-        querier,
-    };
     MIRROR_ASSET_CW20_ADDR
         .save(deps.as_mut().storage, &cw20_token_addr)
         .unwrap();
@@ -427,28 +569,9 @@ fn test_achieve_delta_neutral() {
     let mut env = mock_env();
     env.contract.address = Addr::unchecked("this");
     env.block.time = Timestamp::from_seconds(12345);
-    let context = Context {
-        controller: Addr::unchecked("controller"),
-        anchor_ust_cw20_addr: Addr::unchecked("anchor_ust_cw20"),
-        mirror_cw20_addr: Addr::unchecked("mirror_cw20"),
-        spectrum_cw20_addr: Addr::unchecked("spectrum_cw20"),
-        anchor_market_addr: Addr::unchecked("anchor_market"),
-        mirror_collateral_oracle_addr: Addr::unchecked("mirror_collateral_oracle"),
-        mirror_lock_addr: Addr::unchecked("mirror_lock"),
-        mirror_mint_addr: Addr::unchecked("mirror_mint"),
-        mirror_oracle_addr: Addr::unchecked("mirror_oracle"),
-        mirror_staking_addr: Addr::unchecked("mirror_staking"),
-        spectrum_gov_addr: Addr::unchecked("spectrum_gov"),
-        spectrum_mirror_farms_addr: Addr::unchecked("spectrum_mirror_farms"),
-        spectrum_staker_addr: Addr::unchecked("spectrum_staker"),
-        terraswap_factory_addr,
-        astroport_factory_addr,
-        collateral_ratio_safety_margin: Decimal::from_ratio(3u128, 10u128),
-        min_open_uusd_amount: Uint128::from(500u128),
-        min_reinvest_uusd_amount: Uint128::from(10u128),
-    };
+    let context = mock_context();
 
-    let messages = achieve_delta_neutral(deps.as_ref(), &env, &context).unwrap();
+    let messages = achieve_delta_neutral(deps.as_ref(), &env, &context, false, None).unwrap();
     assert_eq!(
         messages[0],
         CosmosMsg::Wasm(WasmMsg::Execute {
@@ -544,37 +667,56 @@ fn test_achieve_delta_neutral() {
             funds: vec![],
         })
     );
+
+    // Under the stake policy, SPEC reward is staked in Spectrum governance instead of being swapped for uusd.
+    let (_, sell_policy_uusd_increase_amount) =
+        claim_and_increase_uusd_balance(deps.as_ref(), &env, &context).unwrap();
+    crate::state::SPEC_REWARD_POLICY
+        .save(deps.as_mut().storage, &SpecRewardPolicy::Stake)
+        .unwrap();
+    let (messages, uusd_increase_amount) =
+        claim_and_increase_uusd_balance(deps.as_ref(), &env, &context).unwrap();
+    assert_eq!(
+        messages[2],
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("spectrum_cw20"),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: String::from("spectrum_gov"),
+                amount: Uint128::from(5u128),
+                msg: to_binary(&spectrum_protocol::gov::Cw20HookMsg::stake_tokens {
+                    staker_addr: None,
+                    days: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert_eq!(messages.len(), 6);
+    // SPEC reward of 5 would have been swapped for 10 uusd at the mock Terraswap pair.
+    assert_eq!(
+        uusd_increase_amount + Uint128::from(10u128),
+        sell_policy_uusd_increase_amount
+    );
 }
 
 #[cfg(test)]
 fn run_achieve_delta_neutral_from_position_state_test(
     position_state: PositionState,
+    use_limit_order: bool,
 ) -> Vec<CosmosMsg> {
+    use crate::mock_querier::{mock_context, mock_deps};
     use cosmwasm_std::testing::mock_env;
     use cosmwasm_std::{Addr, Timestamp};
 
-    let terraswap_factory_addr = Addr::unchecked("mock_terraswap_factory");
-    let astroport_factory_addr = Addr::unchecked("mock_astroport_factory");
     let cw20_token_addr = Addr::unchecked("mock_cw20_addr");
-    let terraswap_pair_addr = Addr::unchecked("mock_terraswap_pair");
-    let astroport_pair_addr = Addr::unchecked("mock_astroport_pair");
-    let querier = crate::mock_querier::WasmMockQuerier::new(
-        terraswap_factory_addr.to_string(),
-        astroport_factory_addr.to_string(),
-        terraswap_pair_addr.to_string(),
-        astroport_pair_addr.to_string(),
-        Uint128::from(10u128),
-        Uint128::from(9u128),
-        cw20_token_addr.to_string(),
+    let mut deps = mock_deps(
+        cw20_token_addr.as_str(),
         Uint128::from(1000000u128),
         Uint128::from(9000000u128),
-        position_state.terraswap_pool_info.lp_token_amount,
+        position_state.pool_info.lp_token_amount,
     );
-    let mut deps = cosmwasm_std::OwnedDeps {
-        storage: cosmwasm_std::testing::MockStorage::default(),
-        api: cosmwasm_std::testing::MockApi::default(),
-        querier,
-    };
     MIRROR_ASSET_CW20_ADDR
         .save(deps.as_mut().storage, &cw20_token_addr)
         .unwrap();
@@ -586,55 +728,56 @@ fn run_achieve_delta_neutral_from_position_state_test(
     env.contract.address = Addr::unchecked("this");
     env.block.time = Timestamp::from_seconds(12345);
     let context = Context {
-        controller: Addr::unchecked("controller"),
-        anchor_ust_cw20_addr: Addr::unchecked("anchor_ust_cw20"),
-        mirror_cw20_addr: Addr::unchecked("mirror_cw20"),
-        spectrum_cw20_addr: Addr::unchecked("spectrum_cw20"),
-        anchor_market_addr: Addr::unchecked("anchor_market"),
-        mirror_collateral_oracle_addr: Addr::unchecked("mirror_collateral_oracle"),
-        mirror_lock_addr: Addr::unchecked("mirror_lock"),
-        mirror_mint_addr: Addr::unchecked("mirror_mint"),
-        mirror_oracle_addr: Addr::unchecked("mirror_oracle"),
-        mirror_staking_addr: Addr::unchecked("mirror_staking"),
-        spectrum_gov_addr: Addr::unchecked("spectrum_gov"),
-        spectrum_mirror_farms_addr: Addr::unchecked("spectrum_mirror_farms"),
-        spectrum_staker_addr: Addr::unchecked("spectrum_staker"),
-        terraswap_factory_addr,
-        astroport_factory_addr,
-        collateral_ratio_safety_margin: Decimal::from_ratio(3u128, 10u128),
-        min_open_uusd_amount: Uint128::from(500u128),
-        min_reinvest_uusd_amount: Uint128::from(10u128),
+        mirror_limit_order_addr: Some(Addr::unchecked("mirror_limit_order")),
+        limit_order_imbalance_threshold: Decimal::from_ratio(5u128, 100u128),
+        ..mock_context()
     };
-    achieve_delta_neutral_from_state(deps.as_ref(), &context, &position_state).unwrap()
+    achieve_delta_neutral_from_state(
+        deps.as_ref(),
+        &context,
+        &position_state,
+        use_limit_order,
+        None,
+    )
+    .unwrap()
 }
 
 #[test]
 fn test_achieve_delta_neutral_from_net_long() {
-    use aperture_common::delta_neutral_position::TerraswapPoolInfo;
+    use aperture_common::delta_neutral_position::LiquidityPoolInfo;
     use std::str::FromStr;
 
     assert_eq!(
-        run_achieve_delta_neutral_from_position_state_test(PositionState {
-            uusd_balance: Uint128::from(783745u128),
-            uusd_long_farm: Uint128::from(143776156u128),
-            mirror_asset_short_amount: Uint128::from(2873u128),
-            mirror_asset_balance: Uint128::from(31u128),
-            mirror_asset_long_farm: Uint128::from(2873u128),
-            mirror_asset_long_amount: Uint128::from(2904u128),
-            collateral_anchor_ust_amount: Uint128::from(293574270u128),
-            collateral_uusd_value: Uint128::from(359454826u128),
-            mirror_asset_oracle_price: Decimal::from_ratio(4627292u128, 100u128),
-            anchor_ust_oracle_price: Decimal::from_str("1.224408483533399161").unwrap(),
-            terraswap_pool_info: TerraswapPoolInfo {
-                lp_token_amount: Uint128::from(549523u128),
-                lp_token_cw20_addr: String::from("terra1d34edutzwcz6jgecgk26mpyynqh74j3emdsnq5"),
-                lp_token_total_supply: Uint128::from(5344082180u128),
-                terraswap_pair_addr: String::from("terra1prfcyujt9nsn5kfj5n925sfd737r2n8tk5lmpv"),
-                terraswap_pool_mirror_asset_amount: Uint128::from(27948214u128),
-                terraswap_pool_uusd_amount: Uint128::from(1398215539717u128),
-                spectrum_auto_compound_share_amount: Uint128::from(549523u128),
+        run_achieve_delta_neutral_from_position_state_test(
+            PositionState {
+                uusd_balance: Uint128::from(783745u128),
+                uusd_long_farm: Uint128::from(143776156u128),
+                mirror_asset_short_amount: Uint128::from(2873u128),
+                mirror_asset_balance: Uint128::from(31u128),
+                mirror_asset_long_farm: Uint128::from(2873u128),
+                limit_order_mirror_asset_amount: Uint128::zero(),
+                limit_order_uusd_amount: Uint128::zero(),
+                mirror_asset_long_amount: Uint128::from(2904u128),
+                collateral_amount: Uint128::from(293574270u128),
+                collateral_uusd_value: Uint128::from(359454826u128),
+                mirror_asset_oracle_price: Decimal::from_ratio(4627292u128, 100u128),
+                collateral_oracle_price: Decimal::from_str("1.224408483533399161").unwrap(),
+                collateral_multiplier: Decimal::one(),
+                pool_info: LiquidityPoolInfo {
+                    venue: LiquidityVenue::Terraswap,
+                    lp_token_amount: Uint128::from(549523u128),
+                    lp_token_cw20_addr: String::from(
+                        "terra1d34edutzwcz6jgecgk26mpyynqh74j3emdsnq5"
+                    ),
+                    lp_token_total_supply: Uint128::from(5344082180u128),
+                    pair_addr: String::from("terra1prfcyujt9nsn5kfj5n925sfd737r2n8tk5lmpv"),
+                    pool_mirror_asset_amount: Uint128::from(27948214u128),
+                    pool_uusd_amount: Uint128::from(1398215539717u128),
+                    spectrum_auto_compound_share_amount: Uint128::from(549523u128),
+                },
             },
-        }),
+            false
+        ),
         [CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: String::from("mock_cw20_addr"),
             msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
@@ -653,27 +796,34 @@ fn test_achieve_delta_neutral_from_net_long() {
     );
 
     assert_eq!(
-        run_achieve_delta_neutral_from_position_state_test(PositionState {
-            uusd_balance: Uint128::from(1u128),
-            uusd_long_farm: Uint128::from(18000u128),
-            mirror_asset_short_amount: Uint128::from(1000u128),
-            mirror_asset_balance: Uint128::from(10u128),
-            mirror_asset_long_farm: Uint128::from(2000u128),
-            mirror_asset_long_amount: Uint128::from(2010u128),
-            collateral_anchor_ust_amount: Uint128::from(9000u128),
-            collateral_uusd_value: Uint128::from(9900u128),
-            mirror_asset_oracle_price: Decimal::from_ratio(10u128, 1u128),
-            anchor_ust_oracle_price: Decimal::from_ratio(11u128, 10u128),
-            terraswap_pool_info: aperture_common::delta_neutral_position::TerraswapPoolInfo {
-                lp_token_amount: Uint128::from(6000u128),
-                lp_token_cw20_addr: String::from("lp_token"),
-                lp_token_total_supply: Uint128::from(3000000u128),
-                terraswap_pair_addr: String::from("mock_terraswap_pair"),
-                terraswap_pool_mirror_asset_amount: Uint128::from(1000000u128),
-                terraswap_pool_uusd_amount: Uint128::from(9000000u128),
-                spectrum_auto_compound_share_amount: Uint128::from(6000u128),
+        run_achieve_delta_neutral_from_position_state_test(
+            PositionState {
+                uusd_balance: Uint128::from(1u128),
+                uusd_long_farm: Uint128::from(18000u128),
+                mirror_asset_short_amount: Uint128::from(1000u128),
+                mirror_asset_balance: Uint128::from(10u128),
+                mirror_asset_long_farm: Uint128::from(2000u128),
+                limit_order_mirror_asset_amount: Uint128::zero(),
+                limit_order_uusd_amount: Uint128::zero(),
+                mirror_asset_long_amount: Uint128::from(2010u128),
+                collateral_amount: Uint128::from(9000u128),
+                collateral_uusd_value: Uint128::from(9900u128),
+                mirror_asset_oracle_price: Decimal::from_ratio(10u128, 1u128),
+                collateral_oracle_price: Decimal::from_ratio(11u128, 10u128),
+                collateral_multiplier: Decimal::one(),
+                pool_info: aperture_common::delta_neutral_position::LiquidityPoolInfo {
+                    venue: LiquidityVenue::Terraswap,
+                    lp_token_amount: Uint128::from(6000u128),
+                    lp_token_cw20_addr: String::from("lp_token"),
+                    lp_token_total_supply: Uint128::from(3000000u128),
+                    pair_addr: String::from("mock_terraswap_pair"),
+                    pool_mirror_asset_amount: Uint128::from(1000000u128),
+                    pool_uusd_amount: Uint128::from(9000000u128),
+                    spectrum_auto_compound_share_amount: Uint128::from(6000u128),
+                },
             },
-        }),
+            false
+        ),
         [
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: String::from("spectrum_mirror_farms"),
@@ -715,28 +865,37 @@ fn test_achieve_delta_neutral_from_net_long() {
 
 #[test]
 fn test_achieve_delta_neutral_from_net_short() {
+    use cosmwasm_std::Coin;
+
     assert_eq!(
-        run_achieve_delta_neutral_from_position_state_test(PositionState {
-            uusd_balance: Uint128::from(100000u128),
-            uusd_long_farm: Uint128::from(9000u128),
-            mirror_asset_short_amount: Uint128::from(2000u128),
-            mirror_asset_balance: Uint128::from(10u128),
-            mirror_asset_long_farm: Uint128::from(1000u128),
-            mirror_asset_long_amount: Uint128::from(1010u128),
-            collateral_anchor_ust_amount: Uint128::from(9000u128),
-            collateral_uusd_value: Uint128::from(9900u128),
-            mirror_asset_oracle_price: Decimal::from_ratio(10u128, 1u128),
-            anchor_ust_oracle_price: Decimal::from_ratio(11u128, 10u128),
-            terraswap_pool_info: aperture_common::delta_neutral_position::TerraswapPoolInfo {
-                lp_token_amount: Uint128::from(1u128),
-                lp_token_cw20_addr: String::from("lp_token"),
-                lp_token_total_supply: Uint128::from(1000u128),
-                terraswap_pair_addr: String::from("mock_terraswap_pair"),
-                terraswap_pool_mirror_asset_amount: Uint128::from(1000000u128),
-                terraswap_pool_uusd_amount: Uint128::from(9000000u128),
-                spectrum_auto_compound_share_amount: Uint128::from(1u128),
+        run_achieve_delta_neutral_from_position_state_test(
+            PositionState {
+                uusd_balance: Uint128::from(100000u128),
+                uusd_long_farm: Uint128::from(9000u128),
+                mirror_asset_short_amount: Uint128::from(2000u128),
+                mirror_asset_balance: Uint128::from(10u128),
+                mirror_asset_long_farm: Uint128::from(1000u128),
+                limit_order_mirror_asset_amount: Uint128::zero(),
+                limit_order_uusd_amount: Uint128::zero(),
+                mirror_asset_long_amount: Uint128::from(1010u128),
+                collateral_amount: Uint128::from(9000u128),
+                collateral_uusd_value: Uint128::from(9900u128),
+                mirror_asset_oracle_price: Decimal::from_ratio(10u128, 1u128),
+                collateral_oracle_price: Decimal::from_ratio(11u128, 10u128),
+                collateral_multiplier: Decimal::one(),
+                pool_info: aperture_common::delta_neutral_position::LiquidityPoolInfo {
+                    venue: LiquidityVenue::Terraswap,
+                    lp_token_amount: Uint128::from(1u128),
+                    lp_token_cw20_addr: String::from("lp_token"),
+                    lp_token_total_supply: Uint128::from(1000u128),
+                    pair_addr: String::from("mock_terraswap_pair"),
+                    pool_mirror_asset_amount: Uint128::from(1000000u128),
+                    pool_uusd_amount: Uint128::from(9000000u128),
+                    spectrum_auto_compound_share_amount: Uint128::from(1u128),
+                },
             },
-        }),
+            false
+        ),
         [CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: String::from("mock_terraswap_pair"),
             msg: to_binary(&terraswap::pair::ExecuteMsg::Swap {
@@ -759,27 +918,34 @@ fn test_achieve_delta_neutral_from_net_short() {
     );
 
     assert_eq!(
-        run_achieve_delta_neutral_from_position_state_test(PositionState {
-            uusd_balance: Uint128::from(1u128),
-            uusd_long_farm: Uint128::from(9000u128),
-            mirror_asset_short_amount: Uint128::from(2000u128),
-            mirror_asset_balance: Uint128::from(10u128),
-            mirror_asset_long_farm: Uint128::from(1000u128),
-            mirror_asset_long_amount: Uint128::from(1010u128),
-            collateral_anchor_ust_amount: Uint128::from(9000u128),
-            collateral_uusd_value: Uint128::from(9900u128),
-            mirror_asset_oracle_price: Decimal::from_ratio(10u128, 1u128),
-            anchor_ust_oracle_price: Decimal::from_ratio(11u128, 10u128),
-            terraswap_pool_info: aperture_common::delta_neutral_position::TerraswapPoolInfo {
-                lp_token_amount: Uint128::from(3000u128),
-                lp_token_cw20_addr: String::from("lp_token"),
-                lp_token_total_supply: Uint128::from(3000000u128),
-                terraswap_pair_addr: String::from("mock_terraswap_pair"),
-                terraswap_pool_mirror_asset_amount: Uint128::from(1000000u128),
-                terraswap_pool_uusd_amount: Uint128::from(9000000u128),
-                spectrum_auto_compound_share_amount: Uint128::from(3000u128),
+        run_achieve_delta_neutral_from_position_state_test(
+            PositionState {
+                uusd_balance: Uint128::from(1u128),
+                uusd_long_farm: Uint128::from(9000u128),
+                mirror_asset_short_amount: Uint128::from(2000u128),
+                mirror_asset_balance: Uint128::from(10u128),
+                mirror_asset_long_farm: Uint128::from(1000u128),
+                limit_order_mirror_asset_amount: Uint128::zero(),
+                limit_order_uusd_amount: Uint128::zero(),
+                mirror_asset_long_amount: Uint128::from(1010u128),
+                collateral_amount: Uint128::from(9000u128),
+                collateral_uusd_value: Uint128::from(9900u128),
+                mirror_asset_oracle_price: Decimal::from_ratio(10u128, 1u128),
+                collateral_oracle_price: Decimal::from_ratio(11u128, 10u128),
+                collateral_multiplier: Decimal::one(),
+                pool_info: aperture_common::delta_neutral_position::LiquidityPoolInfo {
+                    venue: LiquidityVenue::Terraswap,
+                    lp_token_amount: Uint128::from(3000u128),
+                    lp_token_cw20_addr: String::from("lp_token"),
+                    lp_token_total_supply: Uint128::from(3000000u128),
+                    pair_addr: String::from("mock_terraswap_pair"),
+                    pool_mirror_asset_amount: Uint128::from(1000000u128),
+                    pool_uusd_amount: Uint128::from(9000000u128),
+                    spectrum_auto_compound_share_amount: Uint128::from(3000u128),
+                },
             },
-        }),
+            false
+        ),
         [
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: String::from("spectrum_mirror_farms"),
@@ -825,28 +991,190 @@ fn test_achieve_delta_neutral_from_net_short() {
 
 #[test]
 fn test_achieve_delta_neutral_from_neutral() {
-    assert!(
-        run_achieve_delta_neutral_from_position_state_test(PositionState {
+    assert!(run_achieve_delta_neutral_from_position_state_test(
+        PositionState {
             uusd_balance: Uint128::from(100000u128),
             uusd_long_farm: Uint128::from(9000u128),
             mirror_asset_short_amount: Uint128::from(1010u128),
             mirror_asset_balance: Uint128::from(10u128),
             mirror_asset_long_farm: Uint128::from(1000u128),
+            limit_order_mirror_asset_amount: Uint128::zero(),
+            limit_order_uusd_amount: Uint128::zero(),
             mirror_asset_long_amount: Uint128::from(1010u128),
-            collateral_anchor_ust_amount: Uint128::from(9000u128),
+            collateral_amount: Uint128::from(9000u128),
             collateral_uusd_value: Uint128::from(9900u128),
             mirror_asset_oracle_price: Decimal::from_ratio(10u128, 1u128),
-            anchor_ust_oracle_price: Decimal::from_ratio(11u128, 10u128),
-            terraswap_pool_info: aperture_common::delta_neutral_position::TerraswapPoolInfo {
+            collateral_oracle_price: Decimal::from_ratio(11u128, 10u128),
+            collateral_multiplier: Decimal::one(),
+            pool_info: aperture_common::delta_neutral_position::LiquidityPoolInfo {
+                venue: LiquidityVenue::Terraswap,
                 lp_token_amount: Uint128::from(1u128),
                 lp_token_cw20_addr: String::from("lp_token"),
                 lp_token_total_supply: Uint128::from(1000u128),
-                terraswap_pair_addr: String::from("mock_terraswap_pair"),
-                terraswap_pool_mirror_asset_amount: Uint128::from(1000000u128),
-                terraswap_pool_uusd_amount: Uint128::from(9000000u128),
+                pair_addr: String::from("mock_terraswap_pair"),
+                pool_mirror_asset_amount: Uint128::from(1000000u128),
+                pool_uusd_amount: Uint128::from(9000000u128),
                 spectrum_auto_compound_share_amount: Uint128::from(1u128),
             },
+        },
+        false
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_achieve_delta_neutral_with_limit_order() {
+    let create_position_state = |uusd_balance: u128,
+                                 mirror_asset_balance: u128,
+                                 lp_token_amount: u128| {
+        let mirror_asset_long_farm = Uint128::from(lp_token_amount * 1000000u128 / 3000000u128);
+        PositionState {
+            uusd_balance: Uint128::from(uusd_balance),
+            uusd_long_farm: Uint128::from(lp_token_amount * 3u128),
+            mirror_asset_short_amount: Uint128::from(1000u128),
+            mirror_asset_balance: Uint128::from(mirror_asset_balance),
+            mirror_asset_long_farm,
+            limit_order_mirror_asset_amount: Uint128::zero(),
+            limit_order_uusd_amount: Uint128::zero(),
+            mirror_asset_long_amount: Uint128::from(mirror_asset_balance) + mirror_asset_long_farm,
+            collateral_amount: Uint128::from(20000u128),
+            collateral_uusd_value: Uint128::from(22000u128),
+            mirror_asset_oracle_price: Decimal::from_ratio(10u128, 1u128),
+            collateral_oracle_price: Decimal::from_ratio(11u128, 10u128),
+            collateral_multiplier: Decimal::one(),
+            pool_info: aperture_common::delta_neutral_position::LiquidityPoolInfo {
+                venue: LiquidityVenue::Terraswap,
+                lp_token_amount: Uint128::from(lp_token_amount),
+                lp_token_cw20_addr: String::from("lp_token"),
+                lp_token_total_supply: Uint128::from(3000000u128),
+                pair_addr: String::from("mock_terraswap_pair"),
+                pool_mirror_asset_amount: Uint128::from(1000000u128),
+                pool_uusd_amount: Uint128::from(9000000u128),
+                spectrum_auto_compound_share_amount: Uint128::from(lp_token_amount),
+            },
+        }
+    };
+    let mirror_asset_info = AssetInfo::Token {
+        contract_addr: String::from("mock_cw20_addr"),
+    };
+
+    // Slightly net long: excess mAsset is withdrawn from the long farm and offered at the oracle price.
+    // Spectrum auto-compound share rounding leaves slightly less mAsset than the naive amount.
+    assert_eq!(
+        run_achieve_delta_neutral_from_position_state_test(
+            create_position_state(0, 10, 3060),
+            true
+        ),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("spectrum_mirror_farms"),
+                msg: to_binary(&spectrum_protocol::mirror_farm::ExecuteMsg::unbond {
+                    asset_token: String::from("mock_cw20_addr"),
+                    amount: Uint128::from(57u128)
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("lp_token"),
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: String::from("mock_terraswap_pair"),
+                    amount: Uint128::from(57u128),
+                    msg: to_binary(&terraswap::pair::Cw20HookMsg::WithdrawLiquidity {}).unwrap()
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("mock_cw20_addr"),
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: String::from("mirror_limit_order"),
+                    amount: Uint128::from(29u128),
+                    msg: to_binary(&mirror_protocol::limit_order::Cw20HookMsg::SubmitOrder {
+                        ask_asset: get_uusd_asset_from_amount(Uint128::from(290u128)),
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        ]
+    );
+
+    // Slightly net short with sufficient uusd balance: missing mAsset is bid for at the oracle price.
+    assert_eq!(
+        run_achieve_delta_neutral_from_position_state_test(
+            create_position_state(500, 0, 2940),
+            true
+        ),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("mirror_limit_order"),
+            msg: to_binary(&mirror_protocol::limit_order::ExecuteMsg::SubmitOrder {
+                offer_asset: get_uusd_asset_from_amount(Uint128::from(200u128)),
+                ask_asset: Asset {
+                    info: mirror_asset_info,
+                    amount: Uint128::from(20u128),
+                },
+            })
+            .unwrap(),
+            funds: vec![cosmwasm_std::Coin {
+                denom: String::from("uusd"),
+                amount: Uint128::from(200u128),
+            }],
+        })]
+    );
+
+    // Slightly net short without sufficient uusd balance: fall back to a pool swap.
+    let messages = run_achieve_delta_neutral_from_position_state_test(
+        create_position_state(100, 0, 2940),
+        true,
+    );
+    assert_eq!(
+        messages,
+        run_achieve_delta_neutral_from_position_state_test(
+            create_position_state(100, 0, 2940),
+            false
+        )
+    );
+    assert!(matches!(
+        messages.last(),
+        Some(CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })) if contract_addr == "mock_terraswap_pair"
+    ));
+
+    // Imbalance over the threshold: fall back to pool swaps.
+    assert_eq!(
+        run_achieve_delta_neutral_from_position_state_test(
+            create_position_state(0, 10, 6000),
+            true
+        ),
+        run_achieve_delta_neutral_from_position_state_test(
+            create_position_state(0, 10, 6000),
+            false
+        )
+    );
+    // Slightly net long while holding the entire pool: all lp tokens are withdrawn, leaving nothing in the long farm.
+    let mut position_state = create_position_state(0, 0, 1);
+    position_state.mirror_asset_short_amount = Uint128::from(20u128);
+    position_state.mirror_asset_long_farm = Uint128::from(21u128);
+    position_state.mirror_asset_long_amount = Uint128::from(21u128);
+    position_state.pool_info.lp_token_total_supply = Uint128::from(1u128);
+    position_state.pool_info.pool_mirror_asset_amount = Uint128::from(21u128);
+    position_state.pool_info.pool_uusd_amount = Uint128::from(189u128);
+    let messages = run_achieve_delta_neutral_from_position_state_test(position_state, true);
+    assert_eq!(
+        messages.last().unwrap(),
+        &CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("mock_cw20_addr"),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: String::from("mirror_limit_order"),
+                amount: Uint128::from(1u128),
+                msg: to_binary(&mirror_protocol::limit_order::Cw20HookMsg::SubmitOrder {
+                    ask_asset: get_uusd_asset_from_amount(Uint128::from(10u128)),
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
         })
-        .is_empty()
     );
 }
@@ -0,0 +1,220 @@
+use aperture_common::delta_neutral_position::{
+    AnnualizedReturn, DetailedPositionInfo, PositionSnapshot, PositionSnapshotItem,
+    PositionSnapshotKind, PositionSnapshotsResponse,
+};
+use cosmwasm_std::{Decimal, Env, StdResult, Storage, Uint128};
+use cw_storage_plus::U64Key;
+
+use crate::state::{POSITION_SNAPSHOTS, POSITION_SNAPSHOT_COUNT};
+
+// Maximum number of snapshots retained; once full, a new snapshot overwrites the oldest one.
+const POSITION_SNAPSHOT_CAPACITY: u64 = 365;
+const NANOSECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60 * 1_000_000_000;
+// Minimum time between the latest snapshot and a new rebalance snapshot, so that retained snapshots span about a year.
+const REBALANCE_SNAPSHOT_INTERVAL_NANOSECONDS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+pub fn record_position_snapshot(
+    storage: &mut dyn Storage,
+    snapshot: &PositionSnapshot,
+) -> StdResult<()> {
+    let count = POSITION_SNAPSHOT_COUNT.may_load(storage)?.unwrap_or(0);
+    POSITION_SNAPSHOTS.save(
+        storage,
+        U64Key::from(count % POSITION_SNAPSHOT_CAPACITY),
+        snapshot,
+    )?;
+    POSITION_SNAPSHOT_COUNT.save(storage, &(count + 1))
+}
+
+// Returns whether a rebalance should record a snapshot, i.e. whether the latest snapshot, if any, is at least `REBALANCE_SNAPSHOT_INTERVAL_NANOSECONDS` old.
+// This lets rebalances skip the position valuation, which requires a number of cross-contract queries, most of the time.
+pub fn should_record_rebalance_snapshot(storage: &dyn Storage, env: &Env) -> StdResult<bool> {
+    let count = POSITION_SNAPSHOT_COUNT.may_load(storage)?.unwrap_or(0);
+    if count == 0 {
+        return Ok(true);
+    }
+    let latest = POSITION_SNAPSHOTS.load(
+        storage,
+        U64Key::from((count - 1) % POSITION_SNAPSHOT_CAPACITY),
+    )?;
+    Ok(env.block.time.nanos()
+        >= latest
+            .time_nanoseconds
+            .saturating_add(REBALANCE_SNAPSHOT_INTERVAL_NANOSECONDS))
+}
+
+// Creates a snapshot of an open position from its detailed info; rewards are reported as harvested since they are claimed during rebalance.
+pub fn create_position_snapshot_from_detailed_info(
+    env: &Env,
+    kind: PositionSnapshotKind,
+    detailed_info: &DetailedPositionInfo,
+) -> PositionSnapshot {
+    let (mirror_asset_long_amount, mirror_asset_short_amount) = match &detailed_info.state {
+        Some(state) => (
+            state.mirror_asset_long_amount,
+            state.mirror_asset_short_amount,
+        ),
+        None => (Uint128::zero(), Uint128::zero()),
+    };
+    PositionSnapshot {
+        kind,
+        height: env.block.height,
+        time_nanoseconds: env.block.time.nanos(),
+        uusd_value: detailed_info.uusd_value,
+        collateral_ratio: detailed_info.collateral_ratio,
+        mirror_asset_long_amount,
+        mirror_asset_short_amount,
+        rewards_harvested_uusd_value: detailed_info.claimable_mir_reward_uusd_value
+            + detailed_info.claimable_spec_reward_uusd_value,
+        fees_charged_uusd_amount: Uint128::zero(),
+    }
+}
+
+fn compute_annualized_return(
+    first: &PositionSnapshot,
+    last: &PositionSnapshot,
+) -> Option<AnnualizedReturn> {
+    if first.uusd_value.is_zero() || last.time_nanoseconds <= first.time_nanoseconds {
+        return None;
+    }
+    let elapsed_nanoseconds = (last.time_nanoseconds - first.time_nanoseconds) as u128;
+    let (value_change, negative) = if last.uusd_value >= first.uusd_value {
+        (last.uusd_value - first.uusd_value, false)
+    } else {
+        (first.uusd_value - last.uusd_value, true)
+    };
+    Some(AnnualizedReturn {
+        rate: Decimal::from_ratio(
+            value_change.multiply_ratio(NANOSECONDS_PER_YEAR, elapsed_nanoseconds),
+            first.uusd_value,
+        ),
+        negative,
+    })
+}
+
+pub fn query_position_snapshots(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PositionSnapshotsResponse> {
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+    let total_count = POSITION_SNAPSHOT_COUNT.may_load(storage)?.unwrap_or(0);
+    let oldest_retained = total_count.saturating_sub(POSITION_SNAPSHOT_CAPACITY);
+    let load_snapshot = |sequence_number: u64| {
+        POSITION_SNAPSHOTS.load(
+            storage,
+            U64Key::from(sequence_number % POSITION_SNAPSHOT_CAPACITY),
+        )
+    };
+
+    let start = match start_after {
+        Some(sequence_number) => (sequence_number + 1).max(oldest_retained),
+        None => oldest_retained,
+    };
+    let end = total_count.min(start + limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64);
+    let mut snapshots = vec![];
+    for sequence_number in start..end {
+        snapshots.push(PositionSnapshotItem {
+            sequence_number,
+            snapshot: load_snapshot(sequence_number)?,
+        });
+    }
+
+    let annualized_return = if total_count >= oldest_retained + 2 {
+        compute_annualized_return(
+            &load_snapshot(oldest_retained)?,
+            &load_snapshot(total_count - 1)?,
+        )
+    } else {
+        None
+    };
+    Ok(PositionSnapshotsResponse {
+        snapshots,
+        total_count,
+        annualized_return,
+    })
+}
+
+#[test]
+fn test_position_snapshots() {
+    let mut storage = cosmwasm_std::testing::MockStorage::new();
+    let create_snapshot = |day: u64, uusd_value: u128| PositionSnapshot {
+        kind: PositionSnapshotKind::Rebalance,
+        height: day,
+        time_nanoseconds: day * 24 * 60 * 60 * 1_000_000_000,
+        uusd_value: Uint128::from(uusd_value),
+        collateral_ratio: None,
+        mirror_asset_long_amount: Uint128::zero(),
+        mirror_asset_short_amount: Uint128::zero(),
+        rewards_harvested_uusd_value: Uint128::zero(),
+        fees_charged_uusd_amount: Uint128::zero(),
+    };
+
+    let response = query_position_snapshots(&storage, None, None).unwrap();
+    assert_eq!(response.total_count, 0);
+    assert!(response.snapshots.is_empty());
+    assert_eq!(response.annualized_return, None);
+
+    // Value grows by 1% every 73 days, i.e. 5% per year.
+    for i in 0..POSITION_SNAPSHOT_CAPACITY + 5 {
+        record_position_snapshot(
+            &mut storage,
+            &create_snapshot(i, 100000 + i as u128 * 1000 / 73),
+        )
+        .unwrap();
+    }
+    let response = query_position_snapshots(&storage, None, Some(3)).unwrap();
+    assert_eq!(response.total_count, POSITION_SNAPSHOT_CAPACITY + 5);
+    // The first five snapshots have been overwritten.
+    assert_eq!(
+        response
+            .snapshots
+            .iter()
+            .map(|item| item.sequence_number)
+            .collect::<Vec<u64>>(),
+        vec![5, 6, 7]
+    );
+    assert_eq!(response.snapshots[0].snapshot, create_snapshot(5, 100068));
+    let annualized_return = response.annualized_return.unwrap();
+    assert!(!annualized_return.negative);
+    assert!(
+        annualized_return.rate > Decimal::permille(49)
+            && annualized_return.rate < Decimal::permille(51)
+    );
+
+    let response =
+        query_position_snapshots(&storage, Some(POSITION_SNAPSHOT_CAPACITY + 2), None).unwrap();
+    assert_eq!(
+        response
+            .snapshots
+            .iter()
+            .map(|item| item.sequence_number)
+            .collect::<Vec<u64>>(),
+        vec![
+            POSITION_SNAPSHOT_CAPACITY + 3,
+            POSITION_SNAPSHOT_CAPACITY + 4
+        ]
+    );
+
+    // The latest snapshot was taken on day `POSITION_SNAPSHOT_CAPACITY + 4`.
+    let mut env = cosmwasm_std::testing::mock_env();
+    env.block.time = cosmwasm_std::Timestamp::from_nanos(
+        (POSITION_SNAPSHOT_CAPACITY + 5) * REBALANCE_SNAPSHOT_INTERVAL_NANOSECONDS - 1,
+    );
+    assert_eq!(should_record_rebalance_snapshot(&storage, &env), Ok(false));
+    env.block.time = env.block.time.plus_nanos(1);
+    assert_eq!(should_record_rebalance_snapshot(&storage, &env), Ok(true));
+    assert_eq!(
+        should_record_rebalance_snapshot(&cosmwasm_std::testing::MockStorage::new(), &env),
+        Ok(true)
+    );
+
+    assert_eq!(
+        compute_annualized_return(&create_snapshot(0, 1000), &create_snapshot(73, 990)),
+        Some(AnnualizedReturn {
+            rate: Decimal::percent(5),
+            negative: true,
+        })
+    );
+}
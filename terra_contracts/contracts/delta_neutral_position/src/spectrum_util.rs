@@ -1,13 +1,16 @@
 use aperture_common::{
-    delta_neutral_position::TerraswapPoolInfo, delta_neutral_position_manager::Context,
+    delta_neutral_position::LiquidityPoolInfo, delta_neutral_position_manager::Context,
 };
 use cosmwasm_std::{
-    to_binary, Addr, CanonicalAddr, CosmosMsg, Decimal, Deps, StdResult, Uint128, WasmMsg,
+    to_binary, Addr, CanonicalAddr, CosmosMsg, Decimal, Deps, Env, QuerierWrapper, StdResult,
+    Uint128, WasmMsg,
 };
 use cw_storage_plus::Map;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::dex_util::swap_cw20_token_for_uusd;
+
 // Spectrum Mirror Farm pool info.
 // Copied from https://github.com/spectrumprotocol/contracts/blob/c6d95b8e853b16c94f98db60695c299c0d308fce/contracts/farms/spectrum_mirror_farm/src/state.rs#L83
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -201,7 +204,7 @@ fn verify_length_prefix() {
 // Unstake `withdraw_lp_token_amount` amount of LP token from Spectrum Mirror farm at `spectrum_mirror_farms_addr`,
 // and then redeem the LP tokens at the Terraswap pool for mAsset (`mirror_asset_cw20_addr`) and UST.
 pub fn unstake_lp_from_spectrum_and_withdraw_liquidity(
-    terraswap_pool_info: &TerraswapPoolInfo,
+    pool_info: &LiquidityPoolInfo,
     spectrum_mirror_farms_addr: &Addr,
     mirror_asset_cw20_addr: &Addr,
     withdraw_lp_token_amount: Uint128,
@@ -217,10 +220,10 @@ pub fn unstake_lp_from_spectrum_and_withdraw_liquidity(
             .unwrap(),
         }),
         CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: terraswap_pool_info.lp_token_cw20_addr.to_string(),
+            contract_addr: pool_info.lp_token_cw20_addr.to_string(),
             funds: vec![],
             msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
-                contract: terraswap_pool_info.terraswap_pair_addr.to_string(),
+                contract: pool_info.pair_addr.to_string(),
                 amount: withdraw_lp_token_amount,
                 msg: to_binary(&terraswap::pair::Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
             })
@@ -234,13 +237,14 @@ pub fn test_unstake_lp_from_spectrum_and_withdraw_liquidity() {
     let withdraw_lp_token_amount = Uint128::from(10u128);
     assert_eq!(
         unstake_lp_from_spectrum_and_withdraw_liquidity(
-            &TerraswapPoolInfo {
+            &LiquidityPoolInfo {
+                venue: aperture_common::delta_neutral_position::LiquidityVenue::Terraswap,
                 lp_token_amount: Uint128::from(100u128),
                 lp_token_cw20_addr: String::from("lp_token_cw20"),
                 lp_token_total_supply: Uint128::from(1000u128),
-                terraswap_pair_addr: String::from("terraswap_pair"),
-                terraswap_pool_mirror_asset_amount: Uint128::from(300u128),
-                terraswap_pool_uusd_amount: Uint128::from(3000u128),
+                pair_addr: String::from("terraswap_pair"),
+                pool_mirror_asset_amount: Uint128::from(300u128),
+                pool_uusd_amount: Uint128::from(3000u128),
                 spectrum_auto_compound_share_amount: Uint128::from(335195917u128),
             },
             &Addr::unchecked("spectrum_mirror_farms"),
@@ -270,3 +274,141 @@ pub fn test_unstake_lp_from_spectrum_and_withdraw_liquidity() {
         ]
     )
 }
+
+// Returns the amount of SPEC staked by this position in Spectrum governance, including accrued staking yield.
+pub fn get_spectrum_gov_staked_spec_amount(
+    querier: &QuerierWrapper,
+    env: &Env,
+    context: &Context,
+) -> StdResult<Uint128> {
+    let balance_response: spectrum_protocol::gov::BalanceResponse = querier.query_wasm_smart(
+        &context.spectrum_gov_addr,
+        &spectrum_protocol::gov::QueryMsg::balance {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    Ok(balance_response.balance)
+}
+
+// Stake `spec_amount` amount of SPEC in Spectrum governance on behalf of this position.
+// SPEC is staked without a lock-up period so that it can be withdrawn at any time, e.g. when the position is closed.
+pub fn create_spectrum_gov_stake_message(
+    context: &Context,
+    spec_amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: context.spectrum_cw20_addr.to_string(),
+        msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+            contract: context.spectrum_gov_addr.to_string(),
+            amount: spec_amount,
+            msg: to_binary(&spectrum_protocol::gov::Cw20HookMsg::stake_tokens {
+                staker_addr: None,
+                days: None,
+            })?,
+        })?,
+        funds: vec![],
+    }))
+}
+
+// Withdraw all SPEC staked by this position from Spectrum governance and swap it for uusd.
+// Returns the messages and the expected amount of uusd received.
+pub fn unstake_spec_from_spectrum_gov_and_swap_for_uusd(
+    querier: &QuerierWrapper,
+    env: &Env,
+    context: &Context,
+) -> StdResult<(Vec<CosmosMsg>, Uint128)> {
+    let staked_spec_amount = get_spectrum_gov_staked_spec_amount(querier, env, context)?;
+    if staked_spec_amount.is_zero() {
+        return Ok((vec![], Uint128::zero()));
+    }
+    let (spec_swap_msg, uusd_return_amount) = swap_cw20_token_for_uusd(
+        querier,
+        &context.terraswap_factory_addr,
+        &context.astroport_factory_addr,
+        &context.spectrum_cw20_addr,
+        staked_spec_amount,
+        None,
+    )?;
+    Ok((
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: context.spectrum_gov_addr.to_string(),
+                msg: to_binary(&spectrum_protocol::gov::ExecuteMsg::withdraw {
+                    amount: None,
+                    days: None,
+                })?,
+                funds: vec![],
+            }),
+            spec_swap_msg,
+        ],
+        uusd_return_amount,
+    ))
+}
+
+#[test]
+fn test_unstake_spec_from_spectrum_gov_and_swap_for_uusd() {
+    use crate::mock_querier::{mock_context, mock_deps};
+    use cosmwasm_std::testing::mock_env;
+
+    let deps = mock_deps(
+        "spectrum_cw20",
+        Uint128::from(1000000u128),
+        Uint128::from(9000000u128),
+        Uint128::from(1u128),
+    );
+    let mut env = mock_env();
+    env.contract.address = Addr::unchecked("this");
+    let context = mock_context();
+
+    assert_eq!(
+        create_spectrum_gov_stake_message(&context, Uint128::from(5u128)).unwrap(),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("spectrum_cw20"),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: String::from("spectrum_gov"),
+                amount: Uint128::from(5u128),
+                msg: to_binary(&spectrum_protocol::gov::Cw20HookMsg::stake_tokens {
+                    staker_addr: None,
+                    days: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    let (messages, uusd_return_amount) =
+        unstake_spec_from_spectrum_gov_and_swap_for_uusd(&deps.as_ref().querier, &env, &context)
+            .unwrap();
+    assert_eq!(uusd_return_amount, Uint128::from(10u128));
+    assert_eq!(
+        messages,
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("spectrum_gov"),
+                msg: to_binary(&spectrum_protocol::gov::ExecuteMsg::withdraw {
+                    amount: None,
+                    days: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("spectrum_cw20"),
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: String::from("mock_terraswap_pair"),
+                    amount: Uint128::from(7u128),
+                    msg: to_binary(&terraswap::pair::Cw20HookMsg::Swap {
+                        belief_price: None,
+                        max_spread: None,
+                        to: None
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        ]
+    );
+}
@@ -1,7 +1,11 @@
-use aperture_common::delta_neutral_position::{PositionActionInfo, TargetCollateralRatioRange};
+use aperture_common::delta_neutral_position::{
+    LiquidityVenue, PositionActionInfo, PositionSnapshot, SpecRewardPolicy,
+    TargetCollateralRatioRange,
+};
 
 use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map, U64Key};
+use terraswap::asset::AssetInfo;
 
 pub const MANAGER: Item<Addr> = Item::new("manager");
 pub const POSITION_OPEN_INFO: Item<PositionActionInfo> = Item::new("position_open_info");
@@ -14,3 +18,16 @@ pub const TARGET_COLLATERAL_RATIO_RANGE: Item<TargetCollateralRatioRange> =
 pub const LAST_FEE_COLLECTION_POSITION_UUSD_VALUE: Item<Uint128> =
     Item::new("last_fee_collection_position_uusd_value");
 pub const CODE_ID: Item<u64> = Item::new("ci");
+// Positions opened before the introduction of this item use Terraswap; see `get_liquidity_venue()`.
+pub const LIQUIDITY_VENUE: Item<LiquidityVenue> = Item::new("lv");
+// Positions opened before the introduction of this item use aUST as collateral; see `get_collateral_asset_info()`.
+pub const COLLATERAL_ASSET_INFO: Item<AssetInfo> = Item::new("cai");
+// Positions opened before the introduction of this item sell SPEC rewards; see `get_spec_reward_policy()`.
+pub const SPEC_REWARD_POLICY: Item<SpecRewardPolicy> = Item::new("srp");
+// Amount of aUST obtained by parking uusd in Anchor Earn while short proceeds are pending unlock; see `get_parked_anchor_ust_amount()`.
+// This is tracked separately as the aUST balance may also include aUST collateral withdrawn from the CDP.
+pub const PARKED_ANCHOR_UST_AMOUNT: Item<Uint128> = Item::new("pau");
+// Ring buffer of the most recent position snapshots, keyed by sequence number modulo `POSITION_SNAPSHOT_CAPACITY`.
+pub const POSITION_SNAPSHOTS: Map<U64Key, PositionSnapshot> = Map::new("ps");
+// Total number of snapshots ever recorded; the next snapshot is assigned this sequence number.
+pub const POSITION_SNAPSHOT_COUNT: Item<u64> = Item::new("psc");
@@ -1,39 +1,48 @@
-use std::cmp::Ordering;
+use std::cmp::{min, Ordering};
 
 use aperture_common::{
-    anchor_util::get_anchor_ust_balance_with_uusd_value,
+    anchor_util::{
+        get_anchor_ust_balance_with_uusd_value, get_anchor_ust_exchange_rate,
+        get_anchor_ust_redemption_uusd_value,
+    },
     delta_neutral_position::{
-        DetailedPositionInfo, PositionInfoResponse, PositionState, TerraswapPoolInfo,
+        DetailedPositionInfo, LiquidityPoolInfo, LiquidityVenue, PositionInfoResponse,
+        PositionState, SpecRewardPolicy,
+    },
+    delta_neutral_position_manager::{
+        self, Context, EmergencyCloseConfig, FeeCollectionConfig, MirrorAssetAllowlistEntry,
+        MirrorAssetStrategyParams, PositionStatus,
     },
-    delta_neutral_position_manager::{Context, FeeCollectionConfig},
     mirror_util::{
         get_mirror_asset_config_response, get_mirror_asset_oracle_uusd_price_response,
         get_mirror_cdp_response, is_mirror_asset_delisted,
     },
 };
 use cosmwasm_std::{
-    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, Env, QuerierWrapper, StdResult, Uint128,
-    WasmMsg,
+    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, QuerierWrapper, StdResult,
+    Storage, Uint128, WasmMsg,
 };
 use cw_storage_plus::{Item, Map};
-use mirror_protocol::collateral_oracle::CollateralPriceResponse;
 use terraswap::asset::{Asset, AssetInfo};
 
 use crate::{
+    collateral_util::{get_collateral_asset_info, get_collateral_price_response},
     dex_util::{
-        compute_terraswap_offer_amount, create_terraswap_cw20_uusd_pair_asset_info,
-        get_terraswap_mirror_asset_uusd_liquidity_info,
+        compute_terraswap_offer_amount, create_withdraw_liquidity_message,
+        get_mirror_asset_uusd_liquidity_info, simulate_terraswap_swap, swap_cw20_token_for_uusd,
+    },
+    limit_order_util::{get_limit_order_unfilled_offer_amounts, get_outstanding_limit_orders},
+    spectrum_util::{
+        check_spectrum_mirror_farm_existence, get_spectrum_gov_staked_spec_amount,
+        unstake_lp_from_spectrum_and_withdraw_liquidity,
     },
-    spectrum_util::unstake_lp_from_spectrum_and_withdraw_liquidity,
     state::{
-        CDP_IDX, CDP_PREEMPTIVELY_CLOSED, MANAGER, MIRROR_ASSET_CW20_ADDR, POSITION_CLOSE_INFO,
-        POSITION_OPEN_INFO, TARGET_COLLATERAL_RATIO_RANGE,
+        CDP_IDX, CDP_PREEMPTIVELY_CLOSED, LIQUIDITY_VENUE, MANAGER, MIRROR_ASSET_CW20_ADDR,
+        PARKED_ANCHOR_UST_AMOUNT, POSITION_CLOSE_INFO, POSITION_OPEN_INFO, SPEC_REWARD_POLICY,
+        TARGET_COLLATERAL_RATIO_RANGE,
     },
 };
 
-// The minimum allowed width for the target collateral ratio range.
-pub const MIN_TARGET_CR_RANGE_WIDTH: &str = "0.4";
-
 pub fn get_uusd_asset_from_amount(amount: Uint128) -> Asset {
     Asset {
         info: AssetInfo::NativeToken {
@@ -66,74 +75,131 @@ pub fn get_cdp_uusd_lock_info_result(
     )
 }
 
-pub fn get_position_state(deps: Deps, env: &Env, context: &Context) -> StdResult<PositionState> {
-    let cdp_response = get_mirror_cdp_response(&deps.querier, context, CDP_IDX.load(deps.storage)?);
-    let collateral_price_response: CollateralPriceResponse = deps.querier.query_wasm_smart(
-        context.mirror_collateral_oracle_addr.clone(),
-        &mirror_protocol::collateral_oracle::QueryMsg::CollateralPrice {
-            asset: context.anchor_ust_cw20_addr.to_string(),
-            timeframe: None,
-        },
-    )?;
-    let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
-    let spectrum_info: spectrum_protocol::mirror_farm::RewardInfoResponse =
-        deps.querier.query_wasm_smart(
-            context.spectrum_mirror_farms_addr.to_string(),
-            &spectrum_protocol::mirror_farm::QueryMsg::reward_info {
-                staker_addr: env.contract.address.to_string(),
-                asset_token: Some(mirror_asset_cw20_addr.to_string()),
-            },
-        )?;
+pub fn get_liquidity_venue(storage: &dyn Storage) -> StdResult<LiquidityVenue> {
+    Ok(LIQUIDITY_VENUE
+        .may_load(storage)?
+        .unwrap_or(LiquidityVenue::Terraswap))
+}
 
+pub fn get_spec_reward_policy(storage: &dyn Storage) -> StdResult<SpecRewardPolicy> {
+    Ok(SPEC_REWARD_POLICY
+        .may_load(storage)?
+        .unwrap_or(SpecRewardPolicy::Sell))
+}
+
+// Returns the amount of mAsset-UST LP tokens staked by this position, and the number of Spectrum auto-compound shares backing them.
+// Terraswap LP tokens are staked in Spectrum Mirror farm, while Astroport LP tokens are staked directly in Mirror staking.
+fn get_staked_lp_token_amount(
+    deps: Deps,
+    env: &Env,
+    context: &Context,
+    venue: LiquidityVenue,
+    mirror_asset_cw20_addr: &Addr,
+) -> StdResult<(Uint128, Uint128)> {
     let mut lp_token_amount = Uint128::zero();
     let mut spectrum_auto_compound_share_amount = Uint128::zero();
-    for info in spectrum_info.reward_infos.iter() {
-        if info.asset_token == mirror_asset_cw20_addr {
-            lp_token_amount = info.bond_amount;
-            spectrum_auto_compound_share_amount = info.auto_bond_share;
+    match venue {
+        LiquidityVenue::Terraswap => {
+            let spectrum_info: spectrum_protocol::mirror_farm::RewardInfoResponse =
+                deps.querier.query_wasm_smart(
+                    context.spectrum_mirror_farms_addr.to_string(),
+                    &spectrum_protocol::mirror_farm::QueryMsg::reward_info {
+                        staker_addr: env.contract.address.to_string(),
+                        asset_token: Some(mirror_asset_cw20_addr.to_string()),
+                    },
+                )?;
+            for info in spectrum_info.reward_infos.iter() {
+                if info.asset_token == *mirror_asset_cw20_addr {
+                    lp_token_amount = info.bond_amount;
+                    spectrum_auto_compound_share_amount = info.auto_bond_share;
+                }
+            }
+        }
+        LiquidityVenue::Astroport => {
+            let mirror_reward_info: mirror_protocol::staking::RewardInfoResponse =
+                deps.querier.query_wasm_smart(
+                    &context.mirror_staking_addr,
+                    &mirror_protocol::staking::QueryMsg::RewardInfo {
+                        staker_addr: env.contract.address.to_string(),
+                        asset_token: Some(mirror_asset_cw20_addr.to_string()),
+                    },
+                )?;
+            for info in mirror_reward_info.reward_infos.iter() {
+                if info.asset_token == *mirror_asset_cw20_addr && !info.is_short {
+                    lp_token_amount = info.bond_amount;
+                }
+            }
         }
     }
-    let asset_infos = create_terraswap_cw20_uusd_pair_asset_info(&mirror_asset_cw20_addr);
-    let terraswap_pair_info = terraswap::querier::query_pair_info(
+    Ok((lp_token_amount, spectrum_auto_compound_share_amount))
+}
+
+pub fn get_position_state(deps: Deps, env: &Env, context: &Context) -> StdResult<PositionState> {
+    get_position_state_with_mirror_asset_price_source(deps, env, context, false)
+}
+
+// Same as `get_position_state()`, except that `mirror_asset_oracle_price` is set to the mAsset-UST pool price, so the state can be obtained while the Mirror oracle is unavailable.
+pub fn get_position_state_at_pool_price(
+    deps: Deps,
+    env: &Env,
+    context: &Context,
+) -> StdResult<PositionState> {
+    get_position_state_with_mirror_asset_price_source(deps, env, context, true)
+}
+
+fn get_position_state_with_mirror_asset_price_source(
+    deps: Deps,
+    env: &Env,
+    context: &Context,
+    use_pool_price: bool,
+) -> StdResult<PositionState> {
+    let cdp_response = get_mirror_cdp_response(&deps.querier, context, CDP_IDX.load(deps.storage)?);
+    let collateral_price_response = get_collateral_price_response(
         &deps.querier,
-        context.terraswap_factory_addr.clone(),
-        &asset_infos,
+        context,
+        &get_collateral_asset_info(deps.storage, context)?,
     )?;
-    let terraswap_pair_addr = terraswap_pair_info.contract_addr;
-    let validated_pair_addr = deps.api.addr_validate(&terraswap_pair_addr)?;
-    let lp_token_cw20_addr = terraswap_pair_info.liquidity_token;
+    let mirror_asset_cw20_addr = MIRROR_ASSET_CW20_ADDR.load(deps.storage)?;
+    let venue = get_liquidity_venue(deps.storage)?;
+    let (lp_token_amount, spectrum_auto_compound_share_amount) =
+        get_staked_lp_token_amount(deps, env, context, venue, &mirror_asset_cw20_addr)?;
+    let (pair_addr, lp_token_cw20_addr, pool_mirror_asset_amount, pool_uusd_amount) =
+        get_mirror_asset_uusd_liquidity_info(deps, context, venue, &mirror_asset_cw20_addr)?;
     let lp_token_total_supply = terraswap::querier::query_supply(
         &deps.querier,
         deps.api.addr_validate(&lp_token_cw20_addr)?,
     )?;
 
-    let terraswap_pool_mirror_asset_amount =
-        asset_infos[0].query_pool(&deps.querier, deps.api, validated_pair_addr.clone())?;
-    let terraswap_pool_uusd_amount =
-        asset_infos[1].query_pool(&deps.querier, deps.api, validated_pair_addr)?;
-
     let mirror_asset_balance = terraswap::querier::query_token_balance(
         &deps.querier,
         mirror_asset_cw20_addr.clone(),
         env.contract.address.clone(),
     )?;
     let mirror_asset_long_farm =
-        lp_token_amount.multiply_ratio(terraswap_pool_mirror_asset_amount, lp_token_total_supply);
+        lp_token_amount.multiply_ratio(pool_mirror_asset_amount, lp_token_total_supply);
+    let (limit_order_mirror_asset_amount, limit_order_uusd_amount) =
+        get_limit_order_unfilled_offer_amounts(
+            &get_outstanding_limit_orders(&deps.querier, env, context)?,
+            &mirror_asset_cw20_addr,
+        )?;
     let state = PositionState {
         uusd_balance: terraswap::querier::query_balance(
             &deps.querier,
             env.contract.address.clone(),
             "uusd".into(),
         )?,
-        uusd_long_farm: lp_token_amount
-            .multiply_ratio(terraswap_pool_uusd_amount, lp_token_total_supply),
+        uusd_long_farm: lp_token_amount.multiply_ratio(pool_uusd_amount, lp_token_total_supply),
         mirror_asset_short_amount: cdp_response
             .as_ref()
             .map_or(Uint128::zero(), |cdp_response| cdp_response.asset.amount),
         mirror_asset_balance,
         mirror_asset_long_farm,
-        mirror_asset_long_amount: mirror_asset_balance.checked_add(mirror_asset_long_farm)?,
-        collateral_anchor_ust_amount: cdp_response
+        limit_order_mirror_asset_amount,
+        limit_order_uusd_amount,
+        mirror_asset_long_amount: mirror_asset_balance
+            .checked_add(mirror_asset_long_farm)?
+            .checked_add(limit_order_mirror_asset_amount)?,
+        collateral_amount: cdp_response
             .as_ref()
             .map_or(Uint128::zero(), |cdp_response| {
                 cdp_response.collateral.amount
@@ -143,29 +209,136 @@ pub fn get_position_state(deps: Deps, env: &Env, context: &Context) -> StdResult
             .map_or(Uint128::zero(), |cdp_response| {
                 cdp_response.collateral.amount * collateral_price_response.rate
             }),
-        mirror_asset_oracle_price: get_mirror_asset_oracle_uusd_price_response(
-            &deps.querier,
-            context,
-            &mirror_asset_cw20_addr,
-        )?
-        .rate,
-        anchor_ust_oracle_price: collateral_price_response.rate,
-        terraswap_pool_info: TerraswapPoolInfo {
+        mirror_asset_oracle_price: if use_pool_price {
+            Decimal::from_ratio(pool_uusd_amount, pool_mirror_asset_amount)
+        } else {
+            get_mirror_asset_oracle_uusd_price_response(
+                &deps.querier,
+                context,
+                &mirror_asset_cw20_addr,
+            )?
+            .rate
+        },
+        collateral_oracle_price: collateral_price_response.rate,
+        collateral_multiplier: collateral_price_response.multiplier,
+        pool_info: LiquidityPoolInfo {
+            venue,
             lp_token_amount,
             lp_token_cw20_addr,
             lp_token_total_supply,
-            terraswap_pair_addr,
-            terraswap_pool_mirror_asset_amount,
-            terraswap_pool_uusd_amount,
+            pair_addr,
+            pool_mirror_asset_amount,
+            pool_uusd_amount,
             spectrum_auto_compound_share_amount,
         },
     };
     Ok(state)
 }
 
+// Unstake `withdraw_lp_token_amount` amount of LP token from the long farm, i.e. Spectrum Mirror farm for Terraswap LP tokens or Mirror staking for Astroport LP tokens,
+// and then redeem the LP tokens at the mAsset-UST pool for mAsset (`mirror_asset_cw20_addr`) and UST.
+pub fn unstake_lp_and_withdraw_liquidity(
+    context: &Context,
+    pool_info: &LiquidityPoolInfo,
+    mirror_asset_cw20_addr: &Addr,
+    withdraw_lp_token_amount: Uint128,
+) -> Vec<CosmosMsg> {
+    match pool_info.venue {
+        LiquidityVenue::Terraswap => unstake_lp_from_spectrum_and_withdraw_liquidity(
+            pool_info,
+            &context.spectrum_mirror_farms_addr,
+            mirror_asset_cw20_addr,
+            withdraw_lp_token_amount,
+        ),
+        LiquidityVenue::Astroport => vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: context.mirror_staking_addr.to_string(),
+                funds: vec![],
+                msg: to_binary(&mirror_protocol::staking::ExecuteMsg::Unbond {
+                    asset_token: mirror_asset_cw20_addr.to_string(),
+                    amount: withdraw_lp_token_amount,
+                })
+                .unwrap(),
+            }),
+            create_withdraw_liquidity_message(
+                LiquidityVenue::Astroport,
+                &pool_info.lp_token_cw20_addr,
+                &pool_info.pair_addr,
+                withdraw_lp_token_amount,
+            )
+            .unwrap(),
+        ],
+    }
+}
+
+// Returns a Wasm execute message that stakes `lp_token_amount` amount of LP tokens in the long farm matching `pool_info.venue`.
+pub fn stake_lp_in_long_farm(
+    context: &Context,
+    pool_info: &LiquidityPoolInfo,
+    mirror_asset_cw20_addr: &Addr,
+    lp_token_amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let (staking_addr, hook_msg) = match pool_info.venue {
+        // Note that Spectrum contract will round the deposit fee down; at 0.1% fee rate, an LP deposit amount of <= 999 will result in no fees being deducted as the fee amount rounds down to zero.
+        // Thus, as long as `lp_token_amount` is positive, the after-fee amount is never zero.
+        // Reference: https://github.com/spectrumprotocol/contracts/blob/ddf4a90794ccba45d1781b96b49787eed3d43ff4/contracts/farms/spectrum_mirror_farm/src/bond.rs#L67
+        LiquidityVenue::Terraswap => (
+            &context.spectrum_mirror_farms_addr,
+            to_binary(&spectrum_protocol::mirror_farm::Cw20HookMsg::bond {
+                asset_token: mirror_asset_cw20_addr.to_string(),
+                compound_rate: Some(Decimal::one()),
+                staker_addr: None,
+            })?,
+        ),
+        LiquidityVenue::Astroport => (
+            &context.mirror_staking_addr,
+            to_binary(&mirror_protocol::staking::Cw20HookMsg::Bond {
+                asset_token: mirror_asset_cw20_addr.to_string(),
+            })?,
+        ),
+    };
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pool_info.lp_token_cw20_addr.clone(),
+        msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+            contract: staking_addr.to_string(),
+            amount: lp_token_amount,
+            msg: hook_msg,
+        })?,
+        funds: vec![],
+    }))
+}
+
+// Check whether the long farm matching `pool_info.venue` accepts the position's LP tokens.
+// For Terraswap, this is the existence of the Spectrum Mirror farm for the mAsset; for Astroport, Mirror staking must have the Astroport LP token registered as the mAsset's staking token.
+pub fn check_long_farm_existence(
+    deps: Deps,
+    context: &Context,
+    pool_info: &LiquidityPoolInfo,
+    mirror_asset_cw20_addr: &Addr,
+) -> bool {
+    match pool_info.venue {
+        LiquidityVenue::Terraswap => {
+            check_spectrum_mirror_farm_existence(deps, context, mirror_asset_cw20_addr)
+        }
+        LiquidityVenue::Astroport => {
+            match deps
+                .querier
+                .query_wasm_smart::<mirror_protocol::staking::PoolInfoResponse>(
+                    &context.mirror_staking_addr,
+                    &mirror_protocol::staking::QueryMsg::PoolInfo {
+                        asset_token: mirror_asset_cw20_addr.to_string(),
+                    },
+                ) {
+                Ok(response) => response.staking_token == pool_info.lp_token_cw20_addr,
+                Err(_) => false,
+            }
+        }
+    }
+}
+
 pub fn increase_mirror_asset_balance_from_long_farm(
     state: &PositionState,
-    spectrum_mirror_farms_addr: &Addr,
+    context: &Context,
     mirror_asset_cw20_addr: &Addr,
     target_mirror_asset_balance: Uint128,
 ) -> Vec<CosmosMsg> {
@@ -174,7 +347,7 @@ pub fn increase_mirror_asset_balance_from_long_farm(
     }
     let withdraw_mirror_asset_amount = target_mirror_asset_balance - state.mirror_asset_balance;
     let mut withdraw_lp_token_amount = state
-        .terraswap_pool_info
+        .pool_info
         .lp_token_amount
         .multiply_ratio(withdraw_mirror_asset_amount, state.mirror_asset_long_farm);
 
@@ -190,19 +363,20 @@ pub fn increase_mirror_asset_balance_from_long_farm(
     // floor(pool_mAsset_amount * redeemed_lp_token_amount / lp_token_total_supply). Thus, this is not equivalent to the Terraswap implementation described above.
     //
     // Hence, we have to do `pool_mAsset_amount * Decimal::from_ratio(redeemed_lp_token_amount, lp_token_total_supply)` in order to match Terraswap implementation.
-    while state.terraswap_pool_info.terraswap_pool_mirror_asset_amount
+    // Astroport XYK pairs compute the released amounts in the same way, so this applies to both venues.
+    while state.pool_info.pool_mirror_asset_amount
         * Decimal::from_ratio(
             withdraw_lp_token_amount,
-            state.terraswap_pool_info.lp_token_total_supply,
+            state.pool_info.lp_token_total_supply,
         )
         < withdraw_mirror_asset_amount
     {
         withdraw_lp_token_amount += Uint128::from(1u128);
     }
 
-    unstake_lp_from_spectrum_and_withdraw_liquidity(
-        &state.terraswap_pool_info,
-        spectrum_mirror_farms_addr,
+    unstake_lp_and_withdraw_liquidity(
+        context,
+        &state.pool_info,
         mirror_asset_cw20_addr,
         withdraw_lp_token_amount,
     )
@@ -210,41 +384,47 @@ pub fn increase_mirror_asset_balance_from_long_farm(
 
 #[test]
 fn test_increase_mirror_asset_balance_from_long_farm() {
-    let state = PositionState {
+    use crate::mock_querier::mock_context;
+    let mut state = PositionState {
         uusd_balance: Uint128::zero(),
         uusd_long_farm: Uint128::from(151396812u128),
         mirror_asset_short_amount: Uint128::from(10219520u128),
         mirror_asset_balance: Uint128::from(640760u128),
         mirror_asset_long_farm: Uint128::from(8924723u128),
+        limit_order_mirror_asset_amount: Uint128::zero(),
+        limit_order_uusd_amount: Uint128::zero(),
         mirror_asset_long_amount: Uint128::from(640760u128 + 8924723u128),
-        collateral_anchor_ust_amount: Uint128::from(294335732u128),
+        collateral_amount: Uint128::from(294335732u128),
         collateral_uusd_value: Uint128::from(353202878u128),
         mirror_asset_oracle_price: Decimal::from_ratio(155149u128, 10000u128),
-        anchor_ust_oracle_price: Decimal::from_ratio(12u128, 10u128),
-        terraswap_pool_info: TerraswapPoolInfo {
+        collateral_oracle_price: Decimal::from_ratio(12u128, 10u128),
+        collateral_multiplier: Decimal::one(),
+        pool_info: LiquidityPoolInfo {
+            venue: LiquidityVenue::Terraswap,
             lp_token_amount: Uint128::from(35195917u128),
             lp_token_cw20_addr: String::from("lp_token_cw20"),
             lp_token_total_supply: Uint128::from(215043294146u128),
-            terraswap_pair_addr: String::from("terraswap_pair"),
-            terraswap_pool_mirror_asset_amount: Uint128::from(54529109845u128),
-            terraswap_pool_uusd_amount: Uint128::from(924941217839u128),
+            pair_addr: String::from("terraswap_pair"),
+            pool_mirror_asset_amount: Uint128::from(54529109845u128),
+            pool_uusd_amount: Uint128::from(924941217839u128),
             spectrum_auto_compound_share_amount: Uint128::from(335195917u128),
         },
     };
+    let context = mock_context();
     let target_mirror_asset_balance = Uint128::from(1684481u128);
 
     let withdraw_mirror_asset_amount = target_mirror_asset_balance - state.mirror_asset_balance;
     let estimated_withdraw_lp_token_amount = state
-        .terraswap_pool_info
+        .pool_info
         .lp_token_amount
         .multiply_ratio(withdraw_mirror_asset_amount, state.mirror_asset_long_farm);
     // Assert that due to rounding, the withdrawn mAsset amount plus the original balance is not enough to hit the target amount.
     assert_eq!(
         state.mirror_asset_balance
-            + state.terraswap_pool_info.terraswap_pool_mirror_asset_amount
+            + state.pool_info.pool_mirror_asset_amount
                 * Decimal::from_ratio(
                     estimated_withdraw_lp_token_amount,
-                    state.terraswap_pool_info.lp_token_total_supply,
+                    state.pool_info.lp_token_total_supply,
                 ),
         Uint128::from(1684480u128)
     );
@@ -258,7 +438,7 @@ fn test_increase_mirror_asset_balance_from_long_farm() {
     assert_eq!(
         increase_mirror_asset_balance_from_long_farm(
             &state,
-            &Addr::unchecked("spectrum_mirror_farms"),
+            &context,
             &Addr::unchecked("mirror_asset_cw20"),
             target_mirror_asset_balance
         ),
@@ -283,40 +463,41 @@ fn test_increase_mirror_asset_balance_from_long_farm() {
                 .unwrap(),
             })
         ]
-    )
-}
+    );
 
-pub fn increase_uusd_balance_from_aust_collateral(
-    context: &Context,
-    cdp_idx: Uint128,
-    anchor_ust_amount: Uint128,
-) -> Vec<CosmosMsg> {
-    vec![
-        CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: context.mirror_mint_addr.to_string(),
-            funds: vec![],
-            msg: to_binary(&mirror_protocol::mint::ExecuteMsg::Withdraw {
-                position_idx: cdp_idx,
-                collateral: Some(Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: context.anchor_ust_cw20_addr.to_string(),
-                    },
-                    amount: anchor_ust_amount,
-                }),
-            })
-            .unwrap(),
-        }),
-        CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: context.anchor_ust_cw20_addr.to_string(),
-            funds: vec![],
-            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
-                contract: context.anchor_market_addr.to_string(),
-                amount: anchor_ust_amount,
-                msg: to_binary(&moneymarket::market::Cw20HookMsg::RedeemStable {}).unwrap(),
+    // Astroport LP tokens are unstaked from Mirror staking instead.
+    state.pool_info.venue = LiquidityVenue::Astroport;
+    state.pool_info.pair_addr = String::from("astroport_pair");
+    state.pool_info.spectrum_auto_compound_share_amount = Uint128::zero();
+    assert_eq!(
+        increase_mirror_asset_balance_from_long_farm(
+            &state,
+            &context,
+            &Addr::unchecked("mirror_asset_cw20"),
+            target_mirror_asset_balance
+        ),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("mirror_staking"),
+                funds: vec![],
+                msg: to_binary(&mirror_protocol::staking::ExecuteMsg::Unbond {
+                    asset_token: String::from("mirror_asset_cw20"),
+                    amount: expected_withdraw_lp_token_amount,
+                })
+                .unwrap(),
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("lp_token_cw20"),
+                funds: vec![],
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: String::from("astroport_pair"),
+                    amount: expected_withdraw_lp_token_amount,
+                    msg: to_binary(&astroport::pair::Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+                })
+                .unwrap(),
             })
-            .unwrap(),
-        }),
-    ]
+        ]
+    )
 }
 
 pub fn find_unclaimed_spec_amount(deps: Deps, env: &Env, context: &Context) -> StdResult<Uint128> {
@@ -353,28 +534,133 @@ pub fn find_unclaimed_mir_amount(deps: Deps, env: &Env, context: &Context) -> St
     Ok(mir_reward)
 }
 
+// Returns the amount of uusd obtained by selling `amount` of the cw20 token through `swap_cw20_token_for_uusd`, i.e. on whichever of Terraswap and Astroport returns more uusd.
+// This is used to value MIR and SPEC rewards, which are sold the same way when claimed.
 pub fn find_cw20_token_uusd_value(
     querier: &QuerierWrapper,
-    terraswap_factory_addr: &Addr,
+    context: &Context,
     cw20_token_addr: &Addr,
     amount: Uint128,
 ) -> StdResult<Uint128> {
-    let terraswap_pair_info = terraswap::querier::query_pair_info(
-        querier,
-        terraswap_factory_addr.clone(),
-        &create_terraswap_cw20_uusd_pair_asset_info(cw20_token_addr),
-    )?;
-    Ok(terraswap::querier::simulate(
+    if amount.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    Ok(swap_cw20_token_for_uusd(
         querier,
-        Addr::unchecked(terraswap_pair_info.contract_addr),
-        &Asset {
-            info: AssetInfo::Token {
-                contract_addr: cw20_token_addr.to_string(),
-            },
-            amount,
-        },
+        &context.terraswap_factory_addr,
+        &context.astroport_factory_addr,
+        cw20_token_addr,
+        amount,
+        None,
     )?
-    .return_amount)
+    .1)
+}
+
+#[test]
+fn test_find_cw20_token_uusd_value() {
+    use crate::mock_querier::{mock_context, mock_deps};
+    let mut deps = mock_deps(
+        "mirror_cw20",
+        Uint128::from(1000u128),
+        Uint128::from(100u128),
+        Uint128::zero(),
+    );
+    let context = mock_context();
+    // Terraswap returns 10 uusd and Astroport returns 9 uusd.
+    assert_eq!(
+        find_cw20_token_uusd_value(
+            &QuerierWrapper::new(&deps.querier),
+            &context,
+            &context.mirror_cw20_addr,
+            Uint128::from(100u128)
+        ),
+        Ok(Uint128::from(10u128))
+    );
+    deps.querier.terraswap_return_amount = Uint128::from(8u128);
+    deps.querier.astroport_return_amount = Uint128::from(12u128);
+    assert_eq!(
+        find_cw20_token_uusd_value(
+            &QuerierWrapper::new(&deps.querier),
+            &context,
+            &context.mirror_cw20_addr,
+            Uint128::from(100u128)
+        ),
+        Ok(Uint128::from(12u128))
+    );
+    assert_eq!(
+        find_cw20_token_uusd_value(
+            &QuerierWrapper::new(&deps.querier),
+            &context,
+            &context.mirror_cw20_addr,
+            Uint128::zero()
+        ),
+        Ok(Uint128::zero())
+    );
+}
+
+pub fn get_parked_anchor_ust_amount(storage: &dyn Storage) -> StdResult<Uint128> {
+    Ok(PARKED_ANCHOR_UST_AMOUNT
+        .may_load(storage)?
+        .unwrap_or_default())
+}
+
+// Returns the amount of uusd that would be returned if the aUST obtained by parking uusd in Anchor Earn is redeemed.
+pub fn get_parked_anchor_ust_uusd_value(
+    deps: Deps,
+    env: &Env,
+    context: &Context,
+) -> StdResult<Uint128> {
+    let parked_anchor_ust_amount = get_parked_anchor_ust_amount(deps.storage)?;
+    if parked_anchor_ust_amount.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    Ok(get_anchor_ust_redemption_uusd_value(
+        parked_anchor_ust_amount,
+        get_anchor_ust_exchange_rate(deps, env, &context.anchor_market_addr)?,
+    ))
+}
+
+// Returns messages that redeem the aUST obtained by parking uusd in Anchor Earn for uusd, and resets the parked amount.
+// Any other aUST held by this position, e.g. aUST collateral withdrawn from the CDP, is left untouched.
+pub fn redeem_parked_anchor_ust(
+    deps: DepsMut,
+    env: &Env,
+    context: &Context,
+) -> StdResult<Vec<CosmosMsg>> {
+    let anchor_ust_amount = min(
+        get_parked_anchor_ust_amount(deps.storage)?,
+        terraswap::querier::query_token_balance(
+            &deps.querier,
+            context.anchor_ust_cw20_addr.clone(),
+            env.contract.address.clone(),
+        )?,
+    );
+    PARKED_ANCHOR_UST_AMOUNT.remove(deps.storage);
+    if anchor_ust_amount.is_zero() {
+        return Ok(vec![]);
+    }
+    Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: context.anchor_ust_cw20_addr.to_string(),
+        msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+            contract: context.anchor_market_addr.to_string(),
+            amount: anchor_ust_amount,
+            msg: to_binary(&moneymarket::market::Cw20HookMsg::RedeemStable {})?,
+        })?,
+        funds: vec![],
+    })])
+}
+
+// Returns the uusd value of SPEC staked in Spectrum governance under `SpecRewardPolicy::Stake`.
+fn get_staked_spec_uusd_value(deps: Deps, env: &Env, context: &Context) -> StdResult<Uint128> {
+    if get_spec_reward_policy(deps.storage)? != SpecRewardPolicy::Stake {
+        return Ok(Uint128::zero());
+    }
+    find_cw20_token_uusd_value(
+        &deps.querier,
+        context,
+        &context.spectrum_cw20_addr,
+        get_spectrum_gov_staked_spec_amount(&deps.querier, env, context)?,
+    )
 }
 
 pub fn query_position_info(
@@ -406,6 +692,7 @@ pub fn query_position_info(
             &context.anchor_market_addr,
             &context.anchor_ust_cw20_addr,
         )?;
+        let staked_spec_uusd_value = get_staked_spec_uusd_value(deps, env, context)?;
         response.detailed_info = Some(DetailedPositionInfo {
             cdp_preemptively_closed,
             state: None,
@@ -415,7 +702,9 @@ pub fn query_position_info(
             claimable_short_proceeds_uusd_amount: Uint128::zero(),
             claimable_mir_reward_uusd_value: Uint128::zero(),
             claimable_spec_reward_uusd_value: Uint128::zero(),
-            uusd_value: anchor_earn_uusd_value,
+            staked_spec_uusd_value,
+            anchor_earn_parked_uusd_value: Uint128::zero(),
+            uusd_value: anchor_earn_uusd_value.checked_add(staked_spec_uusd_value)?,
         });
         return Ok(response);
     }
@@ -431,54 +720,57 @@ pub fn query_position_info(
         }
     };
 
-    // Native UST.
+    // Native UST, including UST offered in outstanding limit orders.
     let mut value = state
         .uusd_balance
         .checked_add(state.uusd_long_farm)?
+        .checked_add(state.limit_order_uusd_amount)?
         .checked_add(unclaimed_short_proceeds_uusd_amount)?;
-    // Collateral aUST.
+    // CDP collateral.
     value = value.checked_add(state.collateral_uusd_value)?;
     // Unclaimed SPEC reward.
     let spec_uusd_value = find_cw20_token_uusd_value(
         &deps.querier,
-        &context.terraswap_factory_addr,
+        context,
         &context.spectrum_cw20_addr,
         find_unclaimed_spec_amount(deps, env, context)?,
     )?;
     value = value.checked_add(spec_uusd_value)?;
+    // uusd parked in Anchor Earn while short proceeds are pending unlock.
+    let anchor_earn_parked_uusd_value = get_parked_anchor_ust_uusd_value(deps, env, context)?;
+    value = value.checked_add(anchor_earn_parked_uusd_value)?;
+    // SPEC staked in Spectrum governance.
+    let staked_spec_uusd_value = get_staked_spec_uusd_value(deps, env, context)?;
+    value = value.checked_add(staked_spec_uusd_value)?;
     // Unclaimed MIR reward.
     let mir_uusd_value = find_cw20_token_uusd_value(
         &deps.querier,
-        &context.terraswap_factory_addr,
+        context,
         &context.mirror_cw20_addr,
         find_unclaimed_mir_amount(deps, env, context)?,
     )?;
     value = value.checked_add(mir_uusd_value)?;
-    // mAsset value.
+    // mAsset value, priced in the position's own mAsset-UST pool.
     match state
         .mirror_asset_long_amount
         .cmp(&state.mirror_asset_short_amount)
     {
         Ordering::Greater => {
             let net_long_amount = state.mirror_asset_long_amount - state.mirror_asset_short_amount;
-            value = value.checked_add(find_cw20_token_uusd_value(
-                &deps.querier,
-                &context.terraswap_factory_addr,
-                &MIRROR_ASSET_CW20_ADDR.load(deps.storage)?,
-                net_long_amount,
-            )?)?;
+            value = value.checked_add(
+                simulate_terraswap_swap(
+                    state.pool_info.pool_mirror_asset_amount,
+                    state.pool_info.pool_uusd_amount,
+                    net_long_amount,
+                )
+                .2,
+            )?;
         }
         Ordering::Less => {
             let net_short_amount = state.mirror_asset_short_amount - state.mirror_asset_long_amount;
-            let (_, pool_mirror_asset_amount, pool_uusd_amount) =
-                get_terraswap_mirror_asset_uusd_liquidity_info(
-                    deps,
-                    &context.terraswap_factory_addr,
-                    &MIRROR_ASSET_CW20_ADDR.load(deps.storage)?,
-                )?;
             value = value.checked_sub(compute_terraswap_offer_amount(
-                pool_mirror_asset_amount,
-                pool_uusd_amount,
+                state.pool_info.pool_mirror_asset_amount,
+                state.pool_info.pool_uusd_amount,
                 net_short_amount,
             )?)?;
         }
@@ -503,6 +795,8 @@ pub fn query_position_info(
         claimable_short_proceeds_uusd_amount,
         claimable_mir_reward_uusd_value: mir_uusd_value,
         claimable_spec_reward_uusd_value: spec_uusd_value,
+        staked_spec_uusd_value,
+        anchor_earn_parked_uusd_value,
         uusd_value: value,
     });
     Ok(response)
@@ -514,6 +808,49 @@ pub fn get_fee_collection_config_from_manager(deps: Deps) -> StdResult<FeeCollec
     FEE_COLLECTION_CONFIG.query(&deps.querier, manager_addr)
 }
 
+// Falls back to the default config if the manager admin has not set one.
+pub fn get_emergency_close_config_from_manager(deps: Deps) -> StdResult<EmergencyCloseConfig> {
+    let manager_addr = MANAGER.load(deps.storage)?;
+    const EMERGENCY_CLOSE_CONFIG: Item<EmergencyCloseConfig> = Item::new("ecc");
+    Ok(EMERGENCY_CLOSE_CONFIG
+        .query(&deps.querier, manager_addr)
+        .unwrap_or_default())
+}
+
+// Loads the strategy parameters in effect for `mirror_asset_cw20_addr`, i.e. the overrides on its allowlist entry in the manager applied to `context`.
+pub fn get_mirror_asset_strategy_params(
+    deps: Deps,
+    context: &Context,
+    mirror_asset_cw20_addr: &Addr,
+) -> StdResult<MirrorAssetStrategyParams> {
+    let manager_addr = MANAGER.load(deps.storage)?;
+    const POSITION_OPEN_ALLOWED_MIRROR_ASSETS: Map<String, MirrorAssetAllowlistEntry> =
+        Map::new("poamae");
+    POSITION_OPEN_ALLOWED_MIRROR_ASSETS
+        .query(
+            &deps.querier,
+            manager_addr,
+            mirror_asset_cw20_addr.to_string(),
+        )?
+        .unwrap_or_default()
+        .overrides
+        .apply(context)
+}
+
+// Reports a change in the lifecycle status of this position to the manager, which maintains an index of positions by status.
+pub fn create_position_status_update_message(
+    storage: &dyn Storage,
+    status: PositionStatus,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: MANAGER.load(storage)?.to_string(),
+        msg: to_binary(
+            &delta_neutral_position_manager::ExecuteMsg::UpdatePositionStatus { status },
+        )?,
+        funds: vec![],
+    }))
+}
+
 // Determines whether the CDP should be closed due to preemptive setting by the manager or because the mAsset is already delisted from Mirror.
 pub fn should_close_cdp(
     deps: Deps,
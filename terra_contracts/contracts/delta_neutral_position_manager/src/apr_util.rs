@@ -0,0 +1,441 @@
+use aperture_common::delta_neutral_position_manager::{Context, MirrorAssetAprRankingItem};
+use aperture_common::mirror_util::get_mirror_asset_oracle_uusd_price_response;
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{Addr, Decimal, Deps, Env, Order, StdError, StdResult, Uint128};
+use serde::{Deserialize, Serialize};
+use terraswap::asset::AssetInfo;
+
+use crate::state::POSITION_OPEN_ALLOWED_MIRROR_ASSETS;
+
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+// Anchor and Spectrum emit per block; this is the number of blocks per year Anchor assumes for its deposit APY.
+const BLOCKS_PER_YEAR: u64 = 4_656_810;
+
+// Anchor overseer epoch state; this response type is not included in the moneymarket package.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct AnchorOverseerEpochStateResponse {
+    deposit_rate: Decimal256,
+}
+
+// Protocol-wide states that are shared by all mAssets.
+struct ProtocolRewardInfo {
+    mirror_short_reward_addr: String,
+    // Annual MIR emission to all Mirror staking pools, and the total weight among which it is split.
+    mirror_annual_emission: Uint128,
+    mirror_distribution_weights: Vec<(String, u32)>,
+    mirror_uusd_price: Decimal256,
+    // Annual SPEC emission to the Spectrum Mirror farm, the farm's pools, and the fraction of MIR rewards kept by the farm as fees.
+    spectrum_farm_annual_emission: Uint128,
+    spectrum_farm_pools: Vec<spectrum_protocol::mirror_farm::PoolItem>,
+    spectrum_farm_fee_rate: Decimal256,
+    spectrum_uusd_price: Decimal256,
+    anchor_deposit_apr: Decimal256,
+}
+
+// Returns `(token amount, uusd amount, total LP share)` of the token-UST Terraswap pool.
+fn get_terraswap_token_uusd_pool(
+    deps: Deps,
+    terraswap_factory_addr: &Addr,
+    token_addr: &str,
+) -> StdResult<(Uint128, Uint128, Uint128)> {
+    let token_asset_info = AssetInfo::Token {
+        contract_addr: token_addr.to_string(),
+    };
+    let pair_info = terraswap::querier::query_pair_info(
+        &deps.querier,
+        terraswap_factory_addr.clone(),
+        &[
+            token_asset_info.clone(),
+            AssetInfo::NativeToken {
+                denom: String::from("uusd"),
+            },
+        ],
+    )?;
+    let pool_response: terraswap::pair::PoolResponse = deps
+        .querier
+        .query_wasm_smart(pair_info.contract_addr, &terraswap::pair::QueryMsg::Pool {})?;
+    let mut token_amount = Uint128::zero();
+    let mut uusd_amount = Uint128::zero();
+    for asset in pool_response.assets.iter() {
+        if asset.info == token_asset_info {
+            token_amount = asset.amount;
+        } else {
+            uusd_amount = asset.amount;
+        }
+    }
+    Ok((token_amount, uusd_amount, pool_response.total_share))
+}
+
+fn get_terraswap_token_uusd_price(
+    deps: Deps,
+    terraswap_factory_addr: &Addr,
+    token_addr: &str,
+) -> StdResult<Decimal256> {
+    let (token_amount, uusd_amount, _) =
+        get_terraswap_token_uusd_pool(deps, terraswap_factory_addr, token_addr)?;
+    if token_amount.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "empty Terraswap pool for token {}",
+            token_addr
+        )));
+    }
+    Ok(Decimal256::from(Decimal::from_ratio(
+        uusd_amount,
+        token_amount,
+    )))
+}
+
+// Returns `annual_reward_uusd / principal_uusd`, or zero if there is no principal.
+fn compute_apr(annual_reward_uusd: Decimal256, principal_uusd: Uint128) -> Decimal256 {
+    if principal_uusd.is_zero() {
+        Decimal256::zero()
+    } else {
+        annual_reward_uusd / Decimal256::from_uint256(principal_uusd)
+    }
+}
+
+// For every uusd worth of short mAsset, a delta-neutral position posts `collateral_ratio` uusd worth of collateral, and provides one uusd worth of purchased mAsset paired with one uusd as liquidity.
+// Therefore, a deposit is split among collateral, short and long legs with ratio `collateral_ratio : 1 : 2`, and the expected APR is the weighted average of each leg's APR.
+fn compute_expected_apr(
+    collateral_ratio: Decimal256,
+    anchor_deposit_apr: Decimal256,
+    short_apr: Decimal256,
+    long_apr: Decimal256,
+) -> Decimal256 {
+    let two = Decimal256::from_uint256(2u64);
+    (collateral_ratio * anchor_deposit_apr + short_apr + two * long_apr) / (collateral_ratio + two)
+}
+
+// Returns the MIR emission per year according to the segment of Mirror factory's distribution schedule that contains the current block time.
+fn get_mirror_annual_emission(
+    distribution_schedule: &[(u64, u64, Uint128)],
+    block_time: u64,
+) -> Uint128 {
+    distribution_schedule
+        .iter()
+        .find(|(start_time, end_time, _)| *start_time <= block_time && block_time < *end_time)
+        .map(|(start_time, end_time, amount)| {
+            amount.multiply_ratio(SECONDS_PER_YEAR, end_time - start_time)
+        })
+        .unwrap_or_default()
+}
+
+fn get_protocol_reward_info(
+    deps: Deps,
+    env: &Env,
+    context: &Context,
+) -> StdResult<ProtocolRewardInfo> {
+    // Mirror staking is owned by Mirror factory, which holds the MIR distribution schedule and per-asset weights.
+    let mirror_staking_config: mirror_protocol::staking::ConfigResponse =
+        deps.querier.query_wasm_smart(
+            &context.mirror_staking_addr,
+            &mirror_protocol::staking::QueryMsg::Config {},
+        )?;
+    let mirror_factory_config: mirror_protocol::factory::ConfigResponse =
+        deps.querier.query_wasm_smart(
+            &mirror_staking_config.owner,
+            &mirror_protocol::factory::QueryMsg::Config {},
+        )?;
+    let mirror_distribution_info: mirror_protocol::factory::DistributionInfoResponse =
+        deps.querier.query_wasm_smart(
+            &mirror_staking_config.owner,
+            &mirror_protocol::factory::QueryMsg::DistributionInfo {},
+        )?;
+
+    // Spectrum governance mints SPEC every block, sends `warchest_ratio` to the warchest, and splits the rest among vaults by weight.
+    let spectrum_gov_config: spectrum_protocol::gov::ConfigInfo = deps.querier.query_wasm_smart(
+        &context.spectrum_gov_addr,
+        &spectrum_protocol::gov::QueryMsg::config {},
+    )?;
+    let spectrum_gov_state: spectrum_protocol::gov::StateInfo = deps.querier.query_wasm_smart(
+        &context.spectrum_gov_addr,
+        &spectrum_protocol::gov::QueryMsg::state {},
+    )?;
+    let spectrum_gov_vaults: spectrum_protocol::gov::VaultsResponse =
+        deps.querier.query_wasm_smart(
+            &context.spectrum_gov_addr,
+            &spectrum_protocol::gov::QueryMsg::vaults {},
+        )?;
+    let spectrum_farm_weight = spectrum_gov_vaults
+        .vaults
+        .iter()
+        .find(|vault| vault.address == context.spectrum_mirror_farms_addr.as_str())
+        .map(|vault| vault.weight)
+        .unwrap_or(0);
+    let spectrum_farm_annual_emission = if spectrum_gov_state.total_weight == 0
+        || env.block.height < spectrum_gov_config.mint_start
+        || env.block.height >= spectrum_gov_config.mint_end
+    {
+        Uint128::zero()
+    } else {
+        let annual_mint_amount =
+            spectrum_gov_config.mint_per_block * Uint128::from(BLOCKS_PER_YEAR);
+        (annual_mint_amount - annual_mint_amount * spectrum_gov_config.warchest_ratio)
+            .multiply_ratio(spectrum_farm_weight, spectrum_gov_state.total_weight)
+    };
+    let spectrum_farm_config: spectrum_protocol::mirror_farm::ConfigInfo =
+        deps.querier.query_wasm_smart(
+            &context.spectrum_mirror_farms_addr,
+            &spectrum_protocol::mirror_farm::QueryMsg::config {},
+        )?;
+    let spectrum_farm_pools: spectrum_protocol::mirror_farm::PoolsResponse =
+        deps.querier.query_wasm_smart(
+            &context.spectrum_mirror_farms_addr,
+            &spectrum_protocol::mirror_farm::QueryMsg::pools {},
+        )?;
+
+    // Anchor overseer holds the current per-block deposit rate.
+    let anchor_market_config: moneymarket::market::ConfigResponse = deps.querier.query_wasm_smart(
+        &context.anchor_market_addr,
+        &moneymarket::market::QueryMsg::Config {},
+    )?;
+    let anchor_overseer_epoch_state: AnchorOverseerEpochStateResponse =
+        deps.querier.query_wasm_smart(
+            anchor_market_config.overseer_contract,
+            &moneymarket::overseer::QueryMsg::EpochState {},
+        )?;
+
+    Ok(ProtocolRewardInfo {
+        mirror_short_reward_addr: mirror_staking_config.short_reward_contract,
+        mirror_annual_emission: get_mirror_annual_emission(
+            &mirror_factory_config.distribution_schedule,
+            env.block.time.seconds(),
+        ),
+        mirror_distribution_weights: mirror_distribution_info.weights,
+        mirror_uusd_price: get_terraswap_token_uusd_price(
+            deps,
+            &context.terraswap_factory_addr,
+            context.mirror_cw20_addr.as_str(),
+        )?,
+        spectrum_farm_annual_emission,
+        spectrum_farm_pools: spectrum_farm_pools.pools,
+        spectrum_farm_fee_rate: Decimal256::from(
+            spectrum_farm_config.community_fee
+                + spectrum_farm_config.platform_fee
+                + spectrum_farm_config.controller_fee,
+        ),
+        spectrum_uusd_price: get_terraswap_token_uusd_price(
+            deps,
+            &context.terraswap_factory_addr,
+            context.spectrum_cw20_addr.as_str(),
+        )?,
+        anchor_deposit_apr: anchor_overseer_epoch_state.deposit_rate
+            * Decimal256::from_uint256(BLOCKS_PER_YEAR),
+    })
+}
+
+fn get_mirror_asset_apr_ranking_item(
+    deps: Deps,
+    context: &Context,
+    protocol_reward_info: &ProtocolRewardInfo,
+    collateral_ratio: Decimal256,
+    mirror_asset_cw20_addr: String,
+) -> StdResult<MirrorAssetAprRankingItem> {
+    let mirror_pool_info: mirror_protocol::staking::PoolInfoResponse =
+        deps.querier.query_wasm_smart(
+            &context.mirror_staking_addr,
+            &mirror_protocol::staking::QueryMsg::PoolInfo {
+                asset_token: mirror_asset_cw20_addr.clone(),
+            },
+        )?;
+    let short_reward_weight = deps
+        .querier
+        .query_wasm_smart::<mirror_protocol::short_reward::ShortRewardWeightResponse>(
+            &protocol_reward_info.mirror_short_reward_addr,
+            &mirror_protocol::short_reward::QueryMsg::ShortRewardWeight {
+                premium_rate: mirror_pool_info.premium_rate,
+            },
+        )?
+        .short_reward_weight;
+    let (_, pool_uusd_amount, pool_total_share) = get_terraswap_token_uusd_pool(
+        deps,
+        &context.terraswap_factory_addr,
+        &mirror_asset_cw20_addr,
+    )?;
+    let mirror_asset_oracle_price = Decimal256::from(
+        get_mirror_asset_oracle_uusd_price_response(
+            &deps.querier,
+            context,
+            &Addr::unchecked(&mirror_asset_cw20_addr),
+        )?
+        .rate,
+    );
+    // Each LP share is backed by an equal value of mAsset and uusd in the pool.
+    let get_lp_uusd_value = |lp_amount: Uint128| -> Uint128 {
+        if pool_total_share.is_zero() {
+            Uint128::zero()
+        } else {
+            pool_uusd_amount.multiply_ratio(lp_amount.u128() * 2, pool_total_share)
+        }
+    };
+
+    // MIR rewards allocated to this mAsset are split between LP stakers and short positions according to the short reward weight.
+    let total_weight: u64 = protocol_reward_info
+        .mirror_distribution_weights
+        .iter()
+        .map(|(_, weight)| *weight as u64)
+        .sum();
+    let asset_weight = protocol_reward_info
+        .mirror_distribution_weights
+        .iter()
+        .find(|(asset_token, _)| *asset_token == mirror_asset_cw20_addr)
+        .map(|(_, weight)| *weight)
+        .unwrap_or(0);
+    let asset_annual_mirror_reward = if total_weight == 0 {
+        Decimal256::zero()
+    } else {
+        Decimal256::from_uint256(
+            protocol_reward_info
+                .mirror_annual_emission
+                .multiply_ratio(asset_weight, total_weight),
+        ) * protocol_reward_info.mirror_uusd_price
+    };
+    let short_reward_weight_256 = Decimal256::from(short_reward_weight);
+    let short_annual_mirror_reward = asset_annual_mirror_reward * short_reward_weight_256;
+    let long_annual_mirror_reward = asset_annual_mirror_reward - short_annual_mirror_reward;
+
+    let short_mirror_reward_apr = compute_apr(
+        short_annual_mirror_reward,
+        Uint128::from(
+            mirror_asset_oracle_price
+                * cosmwasm_bignumber::Uint256::from(mirror_pool_info.total_short_amount),
+        ),
+    );
+    let long_mirror_reward_apr = compute_apr(
+        long_annual_mirror_reward
+            * (Decimal256::one() - protocol_reward_info.spectrum_farm_fee_rate),
+        get_lp_uusd_value(mirror_pool_info.total_bond_amount),
+    );
+
+    // SPEC rewards of the Spectrum Mirror farm are split among its pools by weight, and then among LP bonded in the pool.
+    let spectrum_farm_total_weight: u64 = protocol_reward_info
+        .spectrum_farm_pools
+        .iter()
+        .map(|pool| pool.weight as u64)
+        .sum();
+    let long_spectrum_reward_apr = match protocol_reward_info
+        .spectrum_farm_pools
+        .iter()
+        .find(|pool| pool.asset_token == mirror_asset_cw20_addr)
+    {
+        Some(spectrum_farm_pool) if spectrum_farm_total_weight > 0 => {
+            let spectrum_farm_reward_info: mirror_protocol::staking::RewardInfoResponse =
+                deps.querier.query_wasm_smart(
+                    &context.mirror_staking_addr,
+                    &mirror_protocol::staking::QueryMsg::RewardInfo {
+                        staker_addr: context.spectrum_mirror_farms_addr.to_string(),
+                        asset_token: Some(mirror_asset_cw20_addr.clone()),
+                    },
+                )?;
+            let spectrum_farm_bond_amount = spectrum_farm_reward_info
+                .reward_infos
+                .iter()
+                .filter(|reward_info| !reward_info.is_short)
+                .map(|reward_info| reward_info.bond_amount)
+                .sum();
+            compute_apr(
+                Decimal256::from_uint256(
+                    protocol_reward_info
+                        .spectrum_farm_annual_emission
+                        .multiply_ratio(spectrum_farm_pool.weight, spectrum_farm_total_weight),
+                ) * protocol_reward_info.spectrum_uusd_price,
+                get_lp_uusd_value(spectrum_farm_bond_amount),
+            )
+        }
+        _ => Decimal256::zero(),
+    };
+
+    Ok(MirrorAssetAprRankingItem {
+        mirror_asset_cw20_addr,
+        expected_apr: Decimal::from(compute_expected_apr(
+            collateral_ratio,
+            protocol_reward_info.anchor_deposit_apr,
+            short_mirror_reward_apr,
+            long_mirror_reward_apr + long_spectrum_reward_apr,
+        )),
+        long_mirror_reward_apr: Decimal::from(long_mirror_reward_apr),
+        long_spectrum_reward_apr: Decimal::from(long_spectrum_reward_apr),
+        short_mirror_reward_apr: Decimal::from(short_mirror_reward_apr),
+        anchor_deposit_apr: Decimal::from(protocol_reward_info.anchor_deposit_apr),
+        premium_rate: mirror_pool_info.premium_rate,
+        short_reward_weight,
+        pool_uusd_depth: pool_uusd_amount,
+    })
+}
+
+// Returns the APR estimate of every mAsset allowed for position open, sorted by expected APR in descending order.
+pub fn get_mirror_asset_apr_ranking(
+    deps: Deps,
+    env: &Env,
+    context: &Context,
+    collateral_ratio: Decimal,
+) -> StdResult<Vec<MirrorAssetAprRankingItem>> {
+    let protocol_reward_info = get_protocol_reward_info(deps, env, context)?;
+    let mut items = vec![];
+    for entry in
+        POSITION_OPEN_ALLOWED_MIRROR_ASSETS.range(deps.storage, None, None, Order::Ascending)
+    {
+        let (mirror_asset_key, allowlist_entry) = entry?;
+        if !allowlist_entry.allowed {
+            continue;
+        }
+        let mirror_asset_cw20_addr = String::from_utf8(mirror_asset_key)
+            .map_err(|_| StdError::generic_err("invalid mAsset key"))?;
+        items.push(get_mirror_asset_apr_ranking_item(
+            deps,
+            context,
+            &protocol_reward_info,
+            Decimal256::from(collateral_ratio),
+            mirror_asset_cw20_addr,
+        )?);
+    }
+    items.sort_by_key(|item| std::cmp::Reverse(item.expected_apr));
+    Ok(items)
+}
+
+#[test]
+fn test_get_mirror_annual_emission() {
+    let distribution_schedule = vec![
+        (0u64, SECONDS_PER_YEAR, Uint128::from(1000u128)),
+        (
+            SECONDS_PER_YEAR,
+            SECONDS_PER_YEAR * 3,
+            Uint128::from(1000u128),
+        ),
+    ];
+    assert_eq!(
+        get_mirror_annual_emission(&distribution_schedule, 100),
+        Uint128::from(1000u128)
+    );
+    assert_eq!(
+        get_mirror_annual_emission(&distribution_schedule, SECONDS_PER_YEAR),
+        Uint128::from(500u128)
+    );
+    assert_eq!(
+        get_mirror_annual_emission(&distribution_schedule, SECONDS_PER_YEAR * 3),
+        Uint128::zero()
+    );
+}
+
+#[test]
+fn test_compute_expected_apr() {
+    // Collateral ratio 2: a deposit of 400 uusd posts 200 uusd worth of collateral, shorts 100 uusd worth of mAsset, and provides 200 uusd worth of liquidity.
+    // Collateral earns 20%, short 10% and long 50%, i.e. (40 + 10 + 100) / 400 = 37.5%.
+    assert_eq!(
+        compute_expected_apr(
+            Decimal256::from_uint256(2u64),
+            Decimal256::percent(20),
+            Decimal256::percent(10),
+            Decimal256::percent(50),
+        ),
+        Decimal256::permille(375)
+    );
+    assert_eq!(
+        compute_apr(Decimal256::percent(10), Uint128::zero()),
+        Decimal256::zero()
+    );
+    assert_eq!(
+        compute_apr(Decimal256::from_uint256(30u64), Uint128::from(200u128)),
+        Decimal256::percent(15)
+    );
+}
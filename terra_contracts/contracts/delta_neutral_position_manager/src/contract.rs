@@ -1,37 +1,60 @@
 use std::collections::HashSet;
 
 use aperture_common::common::{
-    get_position_key, get_position_key_from_tuple, Action, Position, Recipient,
+    get_position_from_key, get_position_key, get_position_key_from_tuple, Action, Position,
 };
-use aperture_common::delta_neutral_position::PositionInfoResponse;
+use aperture_common::delta_neutral_position::{PositionActionInfo, PositionInfoResponse};
 use aperture_common::delta_neutral_position_manager::{
-    AdminConfig, BatchGetPositionInfoResponse, BatchGetPositionInfoResponseItem,
-    CheckMirrorAssetAllowlistResponse, Context, DeltaNeutralParams, ExecuteMsg,
-    FeeCollectionConfig, InstantiateMsg, InternalExecuteMsg, MigrateMsg, QueryMsg,
-    ShouldCallRebalanceAndReinvestResponse,
+    AdminConfig, AdminOperation, BatchGetPositionInfoResponse, BatchGetPositionInfoResponseItem,
+    CheckMirrorAssetAllowlistResponse, Context, ContextUpdate, DeltaNeutralParams,
+    DiversifiedDeltaNeutralParams, DiversifiedPositionAggregateInfo, DiversifiedPositionLeg,
+    EmergencyCloseConfig, ExecuteMsg, FeeCollectionConfig, InstantiateMsg, InternalExecuteMsg,
+    MigrateMsg, MirrorAssetAllowlistEntry, MirrorAssetAprRankingResponse,
+    MirrorAssetStrategyParamOverrides, MirrorAssetStrategyParams,
+    MirrorAssetStrategyParamsResponse, MirrorAssetTvlUtilization, PositionMigrationProgress,
+    PositionRecord, PositionRecordsResponse, PositionRecordsResponseItem, PositionStatus,
+    PreemptiveCloseProgress, QueryMsg, ShouldCallRebalanceAndReinvestResponse, TvlUtilization,
+    TvlUtilizationResponse,
 };
 use aperture_common::mirror_util::{
     get_mirror_asset_config_response, get_mirror_asset_fresh_oracle_uusd_rate,
     get_mirror_cdp_response, is_mirror_asset_delisted,
 };
 use aperture_common::terra_manager::TERRA_CHAIN_ID;
+use aperture_common::timelock::assert_valid_timelock_delay;
 use aperture_common::{delta_neutral_position, terra_manager};
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut,
-    Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128,
-    WasmMsg,
+    entry_point, from_binary, from_slice, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, Storage,
+    SubMsg, Uint128, WasmMsg,
 };
-use cw_storage_plus::Item;
+use cw_storage_plus::{Bound, Item, Prefix, PrimaryKey};
 use protobuf::Message;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
 use terraswap::asset::{Asset, AssetInfo};
 
+use crate::apr_util::get_mirror_asset_apr_ranking;
 use crate::msg_instantiate_contract_response::MsgInstantiateContractResponse;
 use crate::state::{
-    ADMIN_CONFIG, CONTEXT, FEE_COLLECTION_CONFIG, POSITION_OPEN_ALLOWED_MIRROR_ASSETS,
-    POSITION_TO_CONTRACT_ADDR, SHOULD_PREEMPTIVELY_CLOSE_CDP_MIRROR_ASSETS, TMP_POSITION,
+    get_position_status_index_key, position_records, ADMIN_CONFIG, CONTEXT,
+    DIVERSIFIED_POSITION_LEGS, EMERGENCY_CLOSE_CONFIG, FEE_COLLECTION_CONFIG, GLOBAL_TVL_CAP,
+    LEGACY_POSITION_OPEN_ALLOWED_MIRROR_ASSETS, MIRROR_ASSET_DEPLOYED_UUSD_AMOUNTS, PENDING_ADMIN,
+    POSITION_MIGRATION_PROGRESS, POSITION_OPEN_ALLOWED_MIRROR_ASSETS, POSITION_TO_CONTRACT_ADDR,
+    PREEMPTIVE_CLOSE_PROGRESS, SHOULD_PREEMPTIVELY_CLOSE_CDP_MIRROR_ASSETS, TIMELOCK,
+    TMP_DIVERSIFIED_POSITION_LEG_WEIGHTS, TMP_POSITION, TMP_POSITION_MIGRATION_BATCH,
+    TMP_PREEMPTIVE_CLOSE_BATCH, TOTAL_DEPLOYED_UUSD_AMOUNT,
 };
 
 const INSTANTIATE_REPLY_ID: u64 = 1;
+const DIVERSIFIED_POSITION_LEG_INSTANTIATE_REPLY_ID: u64 = 2;
+// Reply ids of preemptive close requests start here; the offset is the index of the position contract in TMP_PREEMPTIVE_CLOSE_BATCH.
+const PREEMPTIVE_CLOSE_REPLY_ID_BASE: u64 = 1000;
+// Reply ids of cursor-based position contract migrations start here; the offset is the index of the position contract in TMP_POSITION_MIGRATION_BATCH.
+const POSITION_MIGRATION_REPLY_ID_BASE: u64 = 2000;
+
+// Maximum number of legs in a diversified position; this bounds the gas cost of opening, closing and querying the position.
+const MAX_DIVERSIFIED_POSITION_LEGS: usize = 8;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -46,6 +69,7 @@ pub fn instantiate(
         delta_neutral_position_code_id: msg.delta_neutral_position_code_id,
     };
     ADMIN_CONFIG.save(deps.storage, &admin_config)?;
+    TIMELOCK.set_delay_seconds(deps.storage, msg.timelock_delay_seconds)?;
 
     let context = Context {
         controller: deps.api.addr_validate(&msg.controller)?,
@@ -56,6 +80,10 @@ pub fn instantiate(
         mirror_collateral_oracle_addr: deps
             .api
             .addr_validate(&msg.mirror_collateral_oracle_addr)?,
+        mirror_limit_order_addr: match msg.mirror_limit_order_addr {
+            Some(addr) => Some(deps.api.addr_validate(&addr)?),
+            None => None,
+        },
         mirror_lock_addr: deps.api.addr_validate(&msg.mirror_lock_addr)?,
         mirror_mint_addr: deps.api.addr_validate(&msg.mirror_mint_addr)?,
         mirror_oracle_addr: deps.api.addr_validate(&msg.mirror_oracle_addr)?,
@@ -68,6 +96,7 @@ pub fn instantiate(
         collateral_ratio_safety_margin: msg.collateral_ratio_safety_margin,
         min_open_uusd_amount: msg.min_open_uusd_amount,
         min_reinvest_uusd_amount: msg.min_reinvest_uusd_amount,
+        limit_order_imbalance_threshold: msg.limit_order_imbalance_threshold,
     };
     CONTEXT.save(deps.storage, &context)?;
 
@@ -75,6 +104,12 @@ pub fn instantiate(
     Ok(Response::default())
 }
 
+// Open position data is parsed as `DiversifiedDeltaNeutralParams` if it has a `legs` field, and as `DeltaNeutralParams` otherwise.
+#[derive(Deserialize)]
+struct OpenPositionDataShape {
+    legs: Option<IgnoredAny>,
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
@@ -89,56 +124,83 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             }
             match action {
                 Action::OpenPosition { data } => {
-                    let params: DeltaNeutralParams = from_binary(&data.unwrap())?;
-                    open_position(env, info, deps.storage, position, params, assets)
+                    let data = data.unwrap();
+                    if from_binary::<OpenPositionDataShape>(&data)?.legs.is_some() {
+                        open_diversified_position(
+                            env,
+                            info,
+                            deps.storage,
+                            position,
+                            from_binary::<DiversifiedDeltaNeutralParams>(&data)?,
+                            assets,
+                        )
+                    } else {
+                        open_position(
+                            env,
+                            info,
+                            deps.storage,
+                            position,
+                            from_binary::<DeltaNeutralParams>(&data)?,
+                            assets,
+                        )
+                    }
                 }
                 Action::IncreasePosition { .. } => Err(StdError::generic_err("not supported")),
                 Action::DecreasePosition { .. } => Err(StdError::generic_err("not supported")),
-                Action::ClosePosition { recipient } => close_position(deps, &position, recipient),
+                Action::ClosePosition { recipient } => close_position(
+                    deps,
+                    &position,
+                    delta_neutral_position::ExecuteMsg::ClosePosition { recipient },
+                ),
+                Action::EmergencyClosePosition { recipient } => close_position(
+                    deps,
+                    &position,
+                    delta_neutral_position::ExecuteMsg::EmergencyClosePosition { recipient },
+                ),
             }
         }
         ExecuteMsg::MigratePositionContracts {
             positions,
             position_contracts,
-        } => migrate_position_contracts(deps.as_ref(), positions, position_contracts),
-        ExecuteMsg::UpdateAdminConfig {
-            admin_addr,
-            terra_manager_addr,
-            delta_neutral_position_code_id,
-        } => update_admin_config(
-            deps,
-            info,
-            admin_addr,
-            terra_manager_addr,
-            delta_neutral_position_code_id,
-        ),
-        ExecuteMsg::UpdatePositionOpenMirrorAssetList {
-            mirror_assets,
-            allowed,
-        } => update_position_open_mirror_asset_list(deps, info, mirror_assets, allowed),
+        } => migrate_position_contracts(deps, positions, position_contracts),
+        ExecuteMsg::MigratePositionContractsFromCursor { limit } => {
+            if info.sender != ADMIN_CONFIG.load(deps.storage)?.admin {
+                return Err(StdError::generic_err("unauthorized"));
+            }
+            migrate_position_contracts_from_cursor(deps, limit)
+        }
+        // Sensitive admin operations can only be performed through `ProposeAdminOperation` and `ExecuteAdminOperation`.
+        ExecuteMsg::UpdateAdminConfig { .. }
+        | ExecuteMsg::UpdatePositionOpenMirrorAssetList { .. }
+        | ExecuteMsg::UpdateFeeCollectionConfig { .. }
+        | ExecuteMsg::UpdateEmergencyCloseConfig { .. }
+        | ExecuteMsg::UpdateGlobalTvlCap { .. }
+        | ExecuteMsg::UpdateMirrorAssetStrategyParamOverrides { .. }
+        | ExecuteMsg::UpdateContext(..) => Err(StdError::generic_err(
+            "operation is subject to timelock and must be proposed",
+        )),
+        ExecuteMsg::AcceptAdmin {} => accept_admin(deps, info),
         ExecuteMsg::AddShouldPreemptivelyCloseCdpMirrorAssetList { mirror_assets } => {
             add_should_preemptively_close_cdp_mirror_asset_list(deps, info, mirror_assets)
         }
-        ExecuteMsg::UpdateFeeCollectionConfig {
-            fee_collection_config,
-        } => update_fee_collection_config(deps, info, fee_collection_config),
-        ExecuteMsg::UpdateContext {
-            controller,
-            mirror_collateral_oracle_addr,
-            mirror_oracle_addr,
-            collateral_ratio_safety_margin,
-            min_open_uusd_amount,
-            min_reinvest_uusd_amount,
-        } => update_context(
-            deps,
-            info,
-            controller,
-            mirror_collateral_oracle_addr,
-            mirror_oracle_addr,
-            collateral_ratio_safety_margin,
-            min_open_uusd_amount,
-            min_reinvest_uusd_amount,
-        ),
+        ExecuteMsg::PreemptivelyCloseMirrorAssetCdps {
+            mirror_asset,
+            limit,
+        } => preemptively_close_mirror_asset_cdps(deps, info, mirror_asset, limit),
+        ExecuteMsg::ProposeAdminOperation { operation } => {
+            propose_admin_operation(deps, env, info, operation)
+        }
+        ExecuteMsg::ExecuteAdminOperation { proposal_id } => {
+            execute_admin_operation(deps, env, info, proposal_id)
+        }
+        ExecuteMsg::CancelAdminOperation { proposal_id } => {
+            if info.sender != ADMIN_CONFIG.load(deps.storage)?.admin {
+                return Err(StdError::generic_err("unauthorized"));
+            }
+            TIMELOCK.cancel_proposal(deps.storage, proposal_id)?;
+            Ok(Response::default())
+        }
+        ExecuteMsg::UpdatePositionStatus { status } => update_position_status(deps, info, status),
         ExecuteMsg::Internal(internal_msg) => {
             if info.sender != env.contract.address {
                 return Err(StdError::generic_err("unauthorized"));
@@ -148,11 +210,33 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
                     position,
                     params,
                     uusd_amount,
-                } => send_execute_message_to_position_contract(
-                    deps.as_ref(),
+                } => {
+                    let contract_addr = POSITION_TO_CONTRACT_ADDR
+                        .load(deps.storage, get_position_key(&position))?;
+                    save_position_record(
+                        deps.storage,
+                        &contract_addr,
+                        &position,
+                        None,
+                        &params.mirror_asset_cw20_addr,
+                        uusd_amount,
+                    )?;
+                    send_execute_message_to_position_contract(
+                        deps.as_ref(),
+                        &position,
+                        delta_neutral_position::ExecuteMsg::OpenPosition { params },
+                        Some(uusd_amount),
+                    )
+                }
+                InternalExecuteMsg::SendOpenPositionToDiversifiedPositionLegs {
+                    position,
+                    params,
+                    uusd_amounts,
+                } => send_open_position_to_diversified_position_legs(
+                    deps,
                     &position,
-                    delta_neutral_position::ExecuteMsg::OpenPosition { params },
-                    Some(uusd_amount),
+                    params,
+                    uusd_amounts,
                 ),
             }
         }
@@ -170,8 +254,11 @@ fn update_admin_config(
     if info.sender != config.admin {
         return Err(StdError::generic_err("unauthorized"));
     }
+    let mut response = Response::default();
     if let Some(admin_addr) = admin_addr {
-        config.admin = deps.api.addr_validate(&admin_addr)?;
+        let pending_admin = deps.api.addr_validate(&admin_addr)?;
+        PENDING_ADMIN.save(deps.storage, &pending_admin)?;
+        response = response.add_attribute("pending_admin", pending_admin);
     }
     if let Some(terra_manager_addr) = terra_manager_addr {
         config.terra_manager = deps.api.addr_validate(&terra_manager_addr)?;
@@ -180,7 +267,84 @@ fn update_admin_config(
         config.delta_neutral_position_code_id = delta_neutral_position_code_id;
     }
     ADMIN_CONFIG.save(deps.storage, &config)?;
-    Ok(Response::default())
+    Ok(response)
+}
+
+fn accept_admin(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    if PENDING_ADMIN.may_load(deps.storage)? != Some(info.sender.clone()) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    PENDING_ADMIN.remove(deps.storage);
+    ADMIN_CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.admin = info.sender.clone();
+        Ok(config)
+    })?;
+    Ok(Response::new().add_attribute("admin", info.sender))
+}
+
+fn propose_admin_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operation: AdminOperation,
+) -> StdResult<Response> {
+    if info.sender != ADMIN_CONFIG.load(deps.storage)?.admin {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    if let AdminOperation::UpdateTimelockDelay { delay_seconds } = operation {
+        assert_valid_timelock_delay(delay_seconds)?;
+    }
+    let proposal = TIMELOCK.propose(deps.storage, &env, operation)?;
+    Ok(Response::new()
+        .add_attribute("proposal_id", proposal.proposal_id.to_string())
+        .add_attribute("executable_after", proposal.executable_after.to_string()))
+}
+
+fn execute_admin_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> StdResult<Response> {
+    if info.sender != ADMIN_CONFIG.load(deps.storage)?.admin {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    let proposal = TIMELOCK.take_executable_proposal(deps.storage, &env, proposal_id)?;
+    match proposal.operation {
+        AdminOperation::UpdateAdminConfig {
+            admin_addr,
+            terra_manager_addr,
+            delta_neutral_position_code_id,
+        } => update_admin_config(
+            deps,
+            info,
+            admin_addr,
+            terra_manager_addr,
+            delta_neutral_position_code_id,
+        ),
+        AdminOperation::UpdateFeeCollectionConfig {
+            fee_collection_config,
+        } => update_fee_collection_config(deps, info, fee_collection_config),
+        AdminOperation::UpdateEmergencyCloseConfig {
+            emergency_close_config,
+        } => update_emergency_close_config(deps, info, emergency_close_config),
+        AdminOperation::UpdatePositionOpenMirrorAssetList {
+            mirror_assets,
+            allowed,
+        } => update_position_open_mirror_asset_list(deps, info, mirror_assets, allowed),
+        AdminOperation::UpdateGlobalTvlCap { uusd_cap } => {
+            update_global_tvl_cap(deps, info, uusd_cap)
+        }
+        AdminOperation::UpdateMirrorAssetStrategyParamOverrides {
+            mirror_assets,
+            overrides,
+        } => update_mirror_asset_strategy_param_overrides(deps, info, mirror_assets, overrides),
+        AdminOperation::UpdateContext(update) => update_context(deps, info, *update),
+        AdminOperation::UpdateTimelockDelay { delay_seconds } => {
+            TIMELOCK.set_delay_seconds(deps.storage, delay_seconds)?;
+            Ok(Response::default())
+        }
+    }
 }
 
 fn update_fee_collection_config(
@@ -196,6 +360,22 @@ fn update_fee_collection_config(
     Ok(Response::default())
 }
 
+fn update_emergency_close_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    emergency_close_config: EmergencyCloseConfig,
+) -> StdResult<Response> {
+    let config = ADMIN_CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    if emergency_close_config.max_haircut > Decimal::one() {
+        return Err(StdError::generic_err("max_haircut must not exceed 1"));
+    }
+    EMERGENCY_CLOSE_CONFIG.save(deps.storage, &emergency_close_config)?;
+    Ok(Response::default())
+}
+
 fn update_position_open_mirror_asset_list(
     deps: DepsMut,
     info: MessageInfo,
@@ -207,11 +387,137 @@ fn update_position_open_mirror_asset_list(
         return Err(StdError::generic_err("unauthorized"));
     }
     for mirror_asset in mirror_assets {
-        POSITION_OPEN_ALLOWED_MIRROR_ASSETS.save(deps.storage, mirror_asset, &allowed)?;
+        POSITION_OPEN_ALLOWED_MIRROR_ASSETS.update(
+            deps.storage,
+            mirror_asset,
+            |entry| -> StdResult<MirrorAssetAllowlistEntry> {
+                let mut entry = entry.unwrap_or_default();
+                entry.allowed = allowed;
+                Ok(entry)
+            },
+        )?;
+    }
+    Ok(Response::default())
+}
+
+fn update_mirror_asset_strategy_param_overrides(
+    deps: DepsMut,
+    info: MessageInfo,
+    mirror_assets: Vec<String>,
+    overrides: MirrorAssetStrategyParamOverrides,
+) -> StdResult<Response> {
+    let config = ADMIN_CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    for mirror_asset in mirror_assets {
+        POSITION_OPEN_ALLOWED_MIRROR_ASSETS.update(
+            deps.storage,
+            mirror_asset,
+            |entry| -> StdResult<MirrorAssetAllowlistEntry> {
+                let mut entry = entry.unwrap_or_default();
+                entry.overrides = overrides.clone();
+                Ok(entry)
+            },
+        )?;
+    }
+    Ok(Response::default())
+}
+
+// Returns the strategy parameters in effect for `mirror_asset`, and whether positions may be opened on it.
+fn get_mirror_asset_strategy_params(
+    storage: &dyn Storage,
+    context: &Context,
+    mirror_asset: &str,
+) -> StdResult<(bool, MirrorAssetStrategyParams)> {
+    let entry = POSITION_OPEN_ALLOWED_MIRROR_ASSETS
+        .may_load(storage, mirror_asset.to_string())?
+        .unwrap_or_default();
+    Ok((entry.allowed, entry.overrides.apply(context)?))
+}
+
+fn update_global_tvl_cap(
+    deps: DepsMut,
+    info: MessageInfo,
+    uusd_cap: Option<Uint128>,
+) -> StdResult<Response> {
+    let config = ADMIN_CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    match uusd_cap {
+        Some(uusd_cap) => GLOBAL_TVL_CAP.save(deps.storage, &uusd_cap)?,
+        None => GLOBAL_TVL_CAP.remove(deps.storage),
     }
     Ok(Response::default())
 }
 
+// Checks that opening positions with the specified (mAsset, uusd amount) pairs does not exceed any TVL cap.
+// Pairs may repeat an mAsset, e.g. across the legs of a diversified position.
+fn check_tvl_caps(storage: &dyn Storage, uusd_amounts: &[(String, Uint128)]) -> StdResult<()> {
+    let mut mirror_asset_uusd_amounts: Vec<(String, Uint128)> = vec![];
+    for (mirror_asset, uusd_amount) in uusd_amounts {
+        match mirror_asset_uusd_amounts
+            .iter_mut()
+            .find(|(existing, _)| existing == mirror_asset)
+        {
+            Some((_, total)) => *total += *uusd_amount,
+            None => mirror_asset_uusd_amounts.push((mirror_asset.clone(), *uusd_amount)),
+        }
+    }
+    for (mirror_asset, uusd_amount) in mirror_asset_uusd_amounts.iter() {
+        if let Some(uusd_cap) = POSITION_OPEN_ALLOWED_MIRROR_ASSETS
+            .may_load(storage, mirror_asset.clone())?
+            .and_then(|entry| entry.overrides.max_tvl_uusd_amount)
+        {
+            let deployed_uusd_amount = MIRROR_ASSET_DEPLOYED_UUSD_AMOUNTS
+                .may_load(storage, mirror_asset.clone())?
+                .unwrap_or_default();
+            if deployed_uusd_amount + *uusd_amount > uusd_cap {
+                return Err(StdError::generic_err("mAsset TVL cap exceeded"));
+            }
+        }
+    }
+    if let Some(uusd_cap) = GLOBAL_TVL_CAP.may_load(storage)? {
+        let total_uusd_amount: Uint128 = uusd_amounts.iter().map(|(_, amount)| *amount).sum();
+        let deployed_uusd_amount = TOTAL_DEPLOYED_UUSD_AMOUNT
+            .may_load(storage)?
+            .unwrap_or_default();
+        if deployed_uusd_amount + total_uusd_amount > uusd_cap {
+            return Err(StdError::generic_err("global TVL cap exceeded"));
+        }
+    }
+    Ok(())
+}
+
+// Adds `uusd_amount` to, or subtracts it from, the deployed uusd amounts that TVL caps are enforced against.
+fn update_deployed_uusd_amounts(
+    storage: &mut dyn Storage,
+    mirror_asset: &Addr,
+    uusd_amount: Uint128,
+    add: bool,
+) -> StdResult<()> {
+    let apply = |deployed_uusd_amount: Option<Uint128>| {
+        let deployed_uusd_amount = deployed_uusd_amount.unwrap_or_default();
+        if add {
+            deployed_uusd_amount + uusd_amount
+        } else {
+            deployed_uusd_amount.saturating_sub(uusd_amount)
+        }
+    };
+    let mirror_asset = mirror_asset.to_string();
+    let mirror_asset_deployed_uusd_amount =
+        apply(MIRROR_ASSET_DEPLOYED_UUSD_AMOUNTS.may_load(storage, mirror_asset.clone())?);
+    MIRROR_ASSET_DEPLOYED_UUSD_AMOUNTS.save(
+        storage,
+        mirror_asset,
+        &mirror_asset_deployed_uusd_amount,
+    )?;
+    let total_deployed_uusd_amount = apply(TOTAL_DEPLOYED_UUSD_AMOUNT.may_load(storage)?);
+    TOTAL_DEPLOYED_UUSD_AMOUNT.save(storage, &total_deployed_uusd_amount)?;
+    Ok(())
+}
+
 fn add_should_preemptively_close_cdp_mirror_asset_list(
     deps: DepsMut,
     info: MessageInfo,
@@ -231,49 +537,395 @@ fn add_should_preemptively_close_cdp_mirror_asset_list(
     Ok(Response::default())
 }
 
-#[allow(clippy::too_many_arguments)]
-fn update_context(
+fn preemptively_close_mirror_asset_cdps(
     deps: DepsMut,
     info: MessageInfo,
-    controller: Option<String>,
-    mirror_collateral_oracle_addr: Option<String>,
-    mirror_oracle_addr: Option<String>,
-    collateral_ratio_safety_margin: Option<Decimal>,
-    min_open_uusd_amount: Option<Uint128>,
-    min_reinvest_uusd_amount: Option<Uint128>,
+    mirror_asset: String,
+    limit: Option<u32>,
 ) -> StdResult<Response> {
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+    let config = ADMIN_CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // Positions not reached by this batch, as well as those not indexed in `position_records()`, close their CDPs on their next rebalance.
+    SHOULD_PREEMPTIVELY_CLOSE_CDP_MIRROR_ASSETS.save(
+        deps.storage,
+        deps.api.addr_validate(&mirror_asset)?,
+        &true,
+    )?;
+
+    let mut progress = PREEMPTIVE_CLOSE_PROGRESS
+        .may_load(deps.storage, mirror_asset.clone())?
+        .unwrap_or_default();
+    let mut response = Response::new();
+    let mut batch = vec![];
+    if !progress.done {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let min = progress
+            .cursor
+            .as_ref()
+            .map(|contract| Bound::exclusive(contract.as_bytes()));
+        let entries = position_records()
+            .idx
+            .mirror_asset
+            .prefix(mirror_asset.clone().into_bytes())
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit + 1)
+            .collect::<StdResult<Vec<(Vec<u8>, PositionRecord)>>>()?;
+        progress.done = entries.len() <= limit;
+
+        let msg = to_binary(&delta_neutral_position::ExecuteMsg::PreemptivelyCloseCdp {})?;
+        for (contract, record) in entries.into_iter().take(limit) {
+            let contract = Addr::unchecked(String::from_utf8(contract)?);
+            progress.cursor = Some(contract.clone());
+            match record.status {
+                PositionStatus::Closed | PositionStatus::CdpPreemptivelyClosed => {
+                    progress.skipped_count += 1;
+                }
+                PositionStatus::PendingDeltaNeutralSetup | PositionStatus::Active => {
+                    // A failed request is recorded in the reply so that it does not block the rest of the batch.
+                    response = response.add_submessage(SubMsg::reply_on_error(
+                        WasmMsg::Execute {
+                            contract_addr: contract.to_string(),
+                            msg: msg.clone(),
+                            funds: vec![],
+                        },
+                        PREEMPTIVE_CLOSE_REPLY_ID_BASE + batch.len() as u64,
+                    ));
+                    progress.requested_count += 1;
+                    batch.push(contract);
+                }
+            }
+        }
+        PREEMPTIVE_CLOSE_PROGRESS.save(deps.storage, mirror_asset.clone(), &progress)?;
+    }
+    let batch_size = batch.len();
+    TMP_PREEMPTIVE_CLOSE_BATCH.save(deps.storage, &(mirror_asset, batch))?;
+    Ok(response
+        .add_attribute("requested", batch_size.to_string())
+        .add_attribute("requested_total", progress.requested_count.to_string())
+        .add_attribute("skipped_total", progress.skipped_count.to_string())
+        .add_attribute("done", progress.done.to_string()))
+}
+
+// Validates `addr` and checks that it is a contract answering `query_msg` with an `R` response.
+// A mistyped address in `CONTEXT` would break every position contract, so this is checked before the address is saved.
+fn validate_context_contract<R: DeserializeOwned, Q: Serialize>(
+    deps: Deps,
+    field: &str,
+    addr: &str,
+    query_msg: &Q,
+) -> StdResult<Addr> {
+    let addr = deps.api.addr_validate(addr)?;
+    deps.querier
+        .query_wasm_smart::<R>(addr.to_string(), query_msg)
+        .map_err(|err| StdError::generic_err(format!("invalid {}: {}", field, err)))?;
+    Ok(addr)
+}
+
+fn validate_cw20_contract(deps: Deps, field: &str, addr: &str) -> StdResult<Addr> {
+    validate_context_contract::<cw20::TokenInfoResponse, _>(
+        deps,
+        field,
+        addr,
+        &cw20::Cw20QueryMsg::TokenInfo {},
+    )
+}
+
+fn update_context(deps: DepsMut, info: MessageInfo, update: ContextUpdate) -> StdResult<Response> {
     let config = ADMIN_CONFIG.load(deps.storage)?;
     if info.sender != config.admin {
         return Err(StdError::generic_err("unauthorized"));
     }
 
     let mut context = CONTEXT.load(deps.storage)?;
-    if let Some(controller) = controller {
+    if let Some(controller) = update.controller {
         context.controller = deps.api.addr_validate(&controller)?;
     }
-    if let Some(mirror_collateral_oracle_addr) = mirror_collateral_oracle_addr {
+    if let Some(addr) = update.anchor_ust_cw20_addr {
+        context.anchor_ust_cw20_addr =
+            validate_cw20_contract(deps.as_ref(), "anchor_ust_cw20_addr", &addr)?;
+    }
+    if let Some(addr) = update.mirror_cw20_addr {
+        context.mirror_cw20_addr =
+            validate_cw20_contract(deps.as_ref(), "mirror_cw20_addr", &addr)?;
+    }
+    if let Some(addr) = update.spectrum_cw20_addr {
+        context.spectrum_cw20_addr =
+            validate_cw20_contract(deps.as_ref(), "spectrum_cw20_addr", &addr)?;
+    }
+    if let Some(addr) = update.anchor_market_addr {
+        context.anchor_market_addr =
+            validate_context_contract::<moneymarket::market::ConfigResponse, _>(
+                deps.as_ref(),
+                "anchor_market_addr",
+                &addr,
+                &moneymarket::market::QueryMsg::Config {},
+            )?;
+    }
+    if let Some(addr) = update.mirror_collateral_oracle_addr {
         context.mirror_collateral_oracle_addr =
-            deps.api.addr_validate(&mirror_collateral_oracle_addr)?;
+            validate_context_contract::<mirror_protocol::collateral_oracle::ConfigResponse, _>(
+                deps.as_ref(),
+                "mirror_collateral_oracle_addr",
+                &addr,
+                &mirror_protocol::collateral_oracle::QueryMsg::Config {},
+            )?;
+    }
+    if let Some(addr) = update.mirror_limit_order_addr {
+        // The limit order contract has no config query.
+        context.mirror_limit_order_addr = Some(validate_context_contract::<
+            mirror_protocol::limit_order::LastOrderIdResponse,
+            _,
+        >(
+            deps.as_ref(),
+            "mirror_limit_order_addr",
+            &addr,
+            &mirror_protocol::limit_order::QueryMsg::LastOrderId {},
+        )?);
+    }
+    if let Some(addr) = update.mirror_lock_addr {
+        context.mirror_lock_addr =
+            validate_context_contract::<mirror_protocol::lock::ConfigResponse, _>(
+                deps.as_ref(),
+                "mirror_lock_addr",
+                &addr,
+                &mirror_protocol::lock::QueryMsg::Config {},
+            )?;
+    }
+    if let Some(addr) = update.mirror_mint_addr {
+        context.mirror_mint_addr =
+            validate_context_contract::<mirror_protocol::mint::ConfigResponse, _>(
+                deps.as_ref(),
+                "mirror_mint_addr",
+                &addr,
+                &mirror_protocol::mint::QueryMsg::Config {},
+            )?;
+    }
+    if let Some(addr) = update.mirror_oracle_addr {
+        context.mirror_oracle_addr =
+            validate_context_contract::<mirror_protocol::oracle::ConfigResponse, _>(
+                deps.as_ref(),
+                "mirror_oracle_addr",
+                &addr,
+                &mirror_protocol::oracle::QueryMsg::Config {},
+            )?;
+    }
+    if let Some(addr) = update.mirror_staking_addr {
+        context.mirror_staking_addr =
+            validate_context_contract::<mirror_protocol::staking::ConfigResponse, _>(
+                deps.as_ref(),
+                "mirror_staking_addr",
+                &addr,
+                &mirror_protocol::staking::QueryMsg::Config {},
+            )?;
+    }
+    if let Some(addr) = update.spectrum_gov_addr {
+        context.spectrum_gov_addr =
+            validate_context_contract::<spectrum_protocol::gov::ConfigInfo, _>(
+                deps.as_ref(),
+                "spectrum_gov_addr",
+                &addr,
+                &spectrum_protocol::gov::QueryMsg::config {},
+            )?;
+    }
+    if let Some(addr) = update.spectrum_mirror_farms_addr {
+        context.spectrum_mirror_farms_addr =
+            validate_context_contract::<spectrum_protocol::mirror_farm::ConfigInfo, _>(
+                deps.as_ref(),
+                "spectrum_mirror_farms_addr",
+                &addr,
+                &spectrum_protocol::mirror_farm::QueryMsg::config {},
+            )?;
+    }
+    if let Some(addr) = update.spectrum_staker_addr {
+        context.spectrum_staker_addr =
+            validate_context_contract::<spectrum_protocol::staker::ConfigInfo, _>(
+                deps.as_ref(),
+                "spectrum_staker_addr",
+                &addr,
+                &spectrum_protocol::staker::QueryMsg::config {},
+            )?;
     }
-    if let Some(mirror_oracle_addr) = mirror_oracle_addr {
-        context.mirror_oracle_addr = deps.api.addr_validate(&mirror_oracle_addr)?;
+    if let Some(addr) = update.terraswap_factory_addr {
+        context.terraswap_factory_addr =
+            validate_context_contract::<terraswap::factory::ConfigResponse, _>(
+                deps.as_ref(),
+                "terraswap_factory_addr",
+                &addr,
+                &terraswap::factory::QueryMsg::Config {},
+            )?;
     }
-    if let Some(collateral_ratio_safety_margin) = collateral_ratio_safety_margin {
+    if let Some(addr) = update.astroport_factory_addr {
+        context.astroport_factory_addr =
+            validate_context_contract::<astroport::factory::ConfigResponse, _>(
+                deps.as_ref(),
+                "astroport_factory_addr",
+                &addr,
+                &astroport::factory::QueryMsg::Config {},
+            )?;
+    }
+    if let Some(collateral_ratio_safety_margin) = update.collateral_ratio_safety_margin {
         context.collateral_ratio_safety_margin = collateral_ratio_safety_margin;
     }
-    if let Some(min_open_uusd_amount) = min_open_uusd_amount {
+    if let Some(min_open_uusd_amount) = update.min_open_uusd_amount {
         context.min_open_uusd_amount = min_open_uusd_amount;
     }
-    if let Some(min_reinvest_uusd_amount) = min_reinvest_uusd_amount {
+    if let Some(min_reinvest_uusd_amount) = update.min_reinvest_uusd_amount {
         context.min_reinvest_uusd_amount = min_reinvest_uusd_amount;
     }
+    if let Some(limit_order_imbalance_threshold) = update.limit_order_imbalance_threshold {
+        context.limit_order_imbalance_threshold = limit_order_imbalance_threshold;
+    }
     CONTEXT.save(deps.storage, &context)?;
 
     Ok(Response::default())
 }
 
-fn migrate_position_contracts(
+// Adds a newly opened position contract to `position_records()`; the position contract reports subsequent status changes through `ExecuteMsg::UpdatePositionStatus`.
+fn save_position_record(
+    storage: &mut dyn Storage,
+    contract_addr: &Addr,
+    position: &Position,
+    diversified_leg_index: Option<u32>,
+    mirror_asset_cw20_addr: &str,
+    open_uusd_amount: Uint128,
+) -> StdResult<()> {
+    let mirror_asset_cw20_addr = Addr::unchecked(mirror_asset_cw20_addr);
+    update_deployed_uusd_amounts(storage, &mirror_asset_cw20_addr, open_uusd_amount, true)?;
+    position_records().save(
+        storage,
+        contract_addr,
+        &PositionRecord {
+            position: position.clone(),
+            diversified_leg_index,
+            mirror_asset_cw20_addr,
+            status: PositionStatus::PendingDeltaNeutralSetup,
+            open_uusd_amount,
+        },
+    )
+}
+
+// Indexes a position contract opened before the introduction of `position_records()`.
+// Its mAsset and status are read directly from its storage so that this works regardless of the contract's code version.
+// Contracts whose mAsset cannot be read are skipped.
+fn backfill_position_record(
+    deps: DepsMut,
+    contract_addr: &Addr,
+    position: &Position,
+    diversified_leg_index: Option<u32>,
+) -> StdResult<()> {
+    let records = position_records();
+    if records.may_load(deps.storage, contract_addr)?.is_some() {
+        return Ok(());
+    }
+    const MIRROR_ASSET_CW20_ADDR: Item<Addr> = Item::new("mirror_asset_cw20_addr");
+    const POSITION_CLOSE_INFO: Item<PositionActionInfo> = Item::new("position_close_info");
+    const CDP_IDX: Item<Uint128> = Item::new("cdp_idx");
+    const CDP_PREEMPTIVELY_CLOSED: Item<bool> = Item::new("cpc");
+    const POSITION_OPEN_INFO: Item<PositionActionInfo> = Item::new("position_open_info");
+    let mirror_asset_cw20_addr =
+        match MIRROR_ASSET_CW20_ADDR.query(&deps.querier, contract_addr.clone()) {
+            Ok(addr) => addr,
+            Err(_) => return Ok(()),
+        };
+    let status = if POSITION_CLOSE_INFO
+        .query(&deps.querier, contract_addr.clone())
+        .is_ok()
+    {
+        PositionStatus::Closed
+    } else if CDP_PREEMPTIVELY_CLOSED
+        .query(&deps.querier, contract_addr.clone())
+        .unwrap_or(false)
+    {
+        PositionStatus::CdpPreemptivelyClosed
+    } else if CDP_IDX.query(&deps.querier, contract_addr.clone()).is_ok() {
+        PositionStatus::Active
+    } else {
+        PositionStatus::PendingDeltaNeutralSetup
+    };
+    let open_uusd_amount = POSITION_OPEN_INFO
+        .query(&deps.querier, contract_addr.clone())
+        .map(|open_info| open_info.uusd_amount)
+        .unwrap_or_default();
+    if status != PositionStatus::Closed {
+        update_deployed_uusd_amounts(
+            deps.storage,
+            &mirror_asset_cw20_addr,
+            open_uusd_amount,
+            true,
+        )?;
+    }
+    records.save(
+        deps.storage,
+        contract_addr,
+        &PositionRecord {
+            position: position.clone(),
+            diversified_leg_index,
+            mirror_asset_cw20_addr,
+            status,
+            open_uusd_amount,
+        },
+    )
+}
+
+fn update_position_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    status: PositionStatus,
+) -> StdResult<Response> {
+    // Records are keyed by position contract address, so a position contract can only update its own record.
+    // Position contracts opened before the introduction of `position_records()` are not indexed until `MigratePositionContracts` is called on them.
+    let records = position_records();
+    if let Some(mut record) = records.may_load(deps.storage, &info.sender)? {
+        // A closed position no longer counts towards TVL caps.
+        if status == PositionStatus::Closed && record.status != PositionStatus::Closed {
+            update_deployed_uusd_amounts(
+                deps.storage,
+                &record.mirror_asset_cw20_addr,
+                record.open_uusd_amount,
+                false,
+            )?;
+        }
+        record.status = status;
+        records.save(deps.storage, &info.sender, &record)?;
+    }
+    Ok(Response::default())
+}
+
+fn query_position_records(
     deps: Deps,
+    prefix: Prefix<PositionRecord>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PositionRecordsResponse> {
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+    let min = match start_after {
+        Some(contract) => Some(Bound::exclusive(
+            deps.api.addr_validate(&contract)?.as_bytes(),
+        )),
+        None => None,
+    };
+    let mut items = vec![];
+    for entry in prefix
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize)
+    {
+        let (contract, record) = entry?;
+        items.push(PositionRecordsResponseItem {
+            contract: Addr::unchecked(String::from_utf8(contract)?),
+            record,
+        });
+    }
+    Ok(PositionRecordsResponse { items })
+}
+
+fn migrate_position_contracts(
+    mut deps: DepsMut,
     positions: Vec<Position>,
     mut position_contracts: Vec<String>,
 ) -> StdResult<Response> {
@@ -281,26 +933,38 @@ fn migrate_position_contracts(
         .load(deps.storage)?
         .delta_neutral_position_code_id;
 
-    // This code-id item is used to query code ids stored under position contracts in a type-safe way.
-    const CODE_ID: Item<u64> = Item::new("ci");
-
-    // Position contracts being requested to migrate.
-    position_contracts.extend(positions.iter().map(|position| {
-        POSITION_TO_CONTRACT_ADDR
-            .load(deps.storage, get_position_key(position))
-            .unwrap()
-            .to_string()
-    }));
+    // Position contracts being requested to migrate; those not yet in `position_records()` are indexed.
+    for position in positions.iter() {
+        if let Some(legs) =
+            DIVERSIFIED_POSITION_LEGS.may_load(deps.storage, get_position_key(position))?
+        {
+            for (leg_index, leg) in legs.into_iter().enumerate() {
+                backfill_position_record(
+                    deps.branch(),
+                    &leg.contract,
+                    position,
+                    Some(leg_index as u32),
+                )?;
+                position_contracts.push(leg.contract.to_string());
+            }
+        } else {
+            let contract_addr =
+                POSITION_TO_CONTRACT_ADDR.load(deps.storage, get_position_key(position))?;
+            backfill_position_record(deps.branch(), &contract_addr, position, None)?;
+            position_contracts.push(contract_addr.to_string());
+        }
+    }
 
     // Generate messages for positions that need to be migrated.
     let msg = to_binary(&delta_neutral_position::MigrateMsg { new_code_id })?;
     let mut response = Response::new();
     for contract in position_contracts {
         // `Addr::unchecked` is used here to avoid the gas cost of address validation.
-        let needs_migration = CODE_ID
-            .query(&deps.querier, Addr::unchecked(&contract))
-            .map_or(true, |current_code_id| current_code_id != new_code_id);
-        if needs_migration {
+        if position_contract_needs_migration(
+            deps.as_ref(),
+            &Addr::unchecked(&contract),
+            new_code_id,
+        )? {
             response = response.add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
                 contract_addr: contract.to_string(),
                 new_code_id,
@@ -311,6 +975,117 @@ fn migrate_position_contracts(
     Ok(response)
 }
 
+// Position contracts store their code id upon migration; those that have never been migrated have no code id stored.
+fn position_contract_needs_migration(
+    deps: Deps,
+    contract: &Addr,
+    new_code_id: u64,
+) -> StdResult<bool> {
+    // Storage key of the code-id item of position contracts.
+    const CODE_ID_KEY: &[u8] = b"ci";
+    match deps.querier.query_wasm_raw(contract, CODE_ID_KEY)? {
+        Some(value) => Ok(from_slice::<u64>(&value)? != new_code_id),
+        None => Ok(true),
+    }
+}
+
+fn migrate_position_contracts_from_cursor(
+    mut deps: DepsMut,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+    let new_code_id = ADMIN_CONFIG
+        .load(deps.storage)?
+        .delta_neutral_position_code_id;
+    let mut progress = match POSITION_MIGRATION_PROGRESS.may_load(deps.storage)? {
+        Some(progress) if progress.new_code_id == new_code_id => progress,
+        _ => PositionMigrationProgress {
+            new_code_id,
+            ..Default::default()
+        },
+    };
+
+    let msg = to_binary(&delta_neutral_position::MigrateMsg { new_code_id })?;
+    let mut response = Response::new();
+    let mut batch = vec![];
+    let mut remaining = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    while !progress.done && remaining > 0 {
+        let min = progress
+            .cursor
+            .as_ref()
+            .map(|position| Bound::exclusive(get_position_key(position).joined_key()));
+        // Each entry is a raw position key with its position contracts and their leg indices.
+        let entries = if progress.processing_diversified_positions {
+            DIVERSIFIED_POSITION_LEGS
+                .range(deps.storage, min, None, Order::Ascending)
+                .take(remaining)
+                .map(|entry| {
+                    entry.map(|(key, legs)| {
+                        let contracts = legs
+                            .into_iter()
+                            .enumerate()
+                            .map(|(leg_index, leg)| (leg.contract, Some(leg_index as u32)))
+                            .collect::<Vec<_>>();
+                        (key, contracts)
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?
+        } else {
+            POSITION_TO_CONTRACT_ADDR
+                .range(deps.storage, min, None, Order::Ascending)
+                .take(remaining)
+                .map(|entry| entry.map(|(key, contract)| (key, vec![(contract, None)])))
+                .collect::<StdResult<Vec<_>>>()?
+        };
+        let exhausted = entries.len() < remaining;
+        remaining -= entries.len();
+
+        for (key, contracts) in entries {
+            let position = get_position_from_key(&key)?;
+            for (contract, leg_index) in contracts {
+                backfill_position_record(deps.branch(), &contract, &position, leg_index)?;
+                match position_contract_needs_migration(deps.as_ref(), &contract, new_code_id) {
+                    Ok(true) => {
+                        // A failed migration is recorded in the reply so that it does not block the rest of the batch.
+                        response = response.add_submessage(SubMsg::reply_on_error(
+                            WasmMsg::Migrate {
+                                contract_addr: contract.to_string(),
+                                new_code_id,
+                                msg: msg.clone(),
+                            },
+                            POSITION_MIGRATION_REPLY_ID_BASE + batch.len() as u64,
+                        ));
+                        progress.requested_count += 1;
+                        batch.push(contract);
+                    }
+                    Ok(false) => progress.skipped_count += 1,
+                    // Likewise, a contract whose code id cannot be queried is recorded without aborting the batch.
+                    Err(_) => progress.failed_contracts.push(contract),
+                }
+            }
+            progress.cursor = Some(position);
+        }
+
+        if exhausted {
+            progress.cursor = None;
+            if progress.processing_diversified_positions {
+                progress.done = true;
+            } else {
+                progress.processing_diversified_positions = true;
+            }
+        }
+    }
+    POSITION_MIGRATION_PROGRESS.save(deps.storage, &progress)?;
+    let batch_size = batch.len();
+    TMP_POSITION_MIGRATION_BATCH.save(deps.storage, &batch)?;
+    Ok(response
+        .add_attribute("requested", batch_size.to_string())
+        .add_attribute("requested_total", progress.requested_count.to_string())
+        .add_attribute("skipped_total", progress.skipped_count.to_string())
+        .add_attribute("done", progress.done.to_string()))
+}
+
 fn send_execute_message_to_position_contract(
     deps: Deps,
     position: &Position,
@@ -334,38 +1109,89 @@ fn send_execute_message_to_position_contract(
     )
 }
 
-pub fn open_position(
-    env: Env,
-    info: MessageInfo,
-    storage: &mut dyn Storage,
-    position: Position,
-    params: DeltaNeutralParams,
-    assets: Vec<Asset>,
+fn send_open_position_to_diversified_position_legs(
+    deps: DepsMut,
+    position: &Position,
+    params: DiversifiedDeltaNeutralParams,
+    uusd_amounts: Vec<Uint128>,
 ) -> StdResult<Response> {
-    let context = CONTEXT.load(storage)?;
-    let uusd_amount = validate_assets(&info, &context, &assets)?;
-
-    // Check that the specified mirror asset is on the allowlist.
-    if !POSITION_OPEN_ALLOWED_MIRROR_ASSETS.load(storage, params.mirror_asset_cw20_addr.clone())? {
-        return Err(StdError::generic_err("mAsset not allowed"));
+    let legs = DIVERSIFIED_POSITION_LEGS.load(deps.storage, get_position_key(position))?;
+    let mut response = Response::new();
+    for (leg_index, ((leg, leg_params), uusd_amount)) in
+        legs.iter().zip(params.legs).zip(uusd_amounts).enumerate()
+    {
+        save_position_record(
+            deps.storage,
+            &leg.contract,
+            position,
+            Some(leg_index as u32),
+            &leg_params.params.mirror_asset_cw20_addr,
+            uusd_amount,
+        )?;
+        response = response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: leg.contract.to_string(),
+            msg: to_binary(&delta_neutral_position::ExecuteMsg::OpenPosition {
+                params: leg_params.params,
+            })?,
+            funds: vec![Coin {
+                denom: String::from("uusd"),
+                amount: uusd_amount,
+            }],
+        }));
     }
+    Ok(response)
+}
 
-    // Instantiate a new contract for the position.
-    TMP_POSITION.save(storage, &position)?;
-    let mut response = Response::new();
-    response = response.add_submessage(SubMsg {
+fn create_position_contract_instantiate_submessage(
+    env: &Env,
+    code_id: u64,
+    reply_id: u64,
+) -> StdResult<SubMsg> {
+    Ok(SubMsg {
         msg: WasmMsg::Instantiate {
             admin: Some(env.contract.address.to_string()),
-            code_id: ADMIN_CONFIG.load(storage)?.delta_neutral_position_code_id,
+            code_id,
             msg: to_binary(&aperture_common::delta_neutral_position::InstantiateMsg {})?,
             funds: vec![],
             label: String::new(),
         }
         .into(),
         gas_limit: None,
-        id: INSTANTIATE_REPLY_ID,
+        id: reply_id,
         reply_on: ReplyOn::Success,
-    });
+    })
+}
+
+pub fn open_position(
+    env: Env,
+    info: MessageInfo,
+    storage: &mut dyn Storage,
+    position: Position,
+    params: DeltaNeutralParams,
+    assets: Vec<Asset>,
+) -> StdResult<Response> {
+    let context = CONTEXT.load(storage)?;
+    let (allowed, strategy_params) =
+        get_mirror_asset_strategy_params(storage, &context, &params.mirror_asset_cw20_addr)?;
+    let uusd_amount = validate_assets(&info, strategy_params.min_open_uusd_amount, &assets)?;
+
+    // Check that the specified mirror asset is on the allowlist.
+    if !allowed {
+        return Err(StdError::generic_err("mAsset not allowed"));
+    }
+    check_tvl_caps(
+        storage,
+        &[(params.mirror_asset_cw20_addr.clone(), uusd_amount)],
+    )?;
+
+    // Instantiate a new contract for the position.
+    TMP_POSITION.save(storage, &position)?;
+    let mut response = Response::new();
+    response = response.add_submessage(create_position_contract_instantiate_submessage(
+        &env,
+        ADMIN_CONFIG.load(storage)?.delta_neutral_position_code_id,
+        INSTANTIATE_REPLY_ID,
+    )?);
 
     // Call position contract to open this position.
     response = response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -382,22 +1208,144 @@ pub fn open_position(
     Ok(response)
 }
 
+// Opens a diversified position: one delta-neutral position contract is instantiated for each leg, and the deposit is split across legs in proportion to their weights.
+pub fn open_diversified_position(
+    env: Env,
+    info: MessageInfo,
+    storage: &mut dyn Storage,
+    position: Position,
+    params: DiversifiedDeltaNeutralParams,
+    assets: Vec<Asset>,
+) -> StdResult<Response> {
+    let context = CONTEXT.load(storage)?;
+    // The minimum open amount is enforced for each leg below.
+    let uusd_amount = validate_assets(&info, Uint128::zero(), &assets)?;
+    if params.legs.is_empty() || params.legs.len() > MAX_DIVERSIFIED_POSITION_LEGS {
+        return Err(StdError::generic_err("invalid number of legs"));
+    }
+
+    // Check that each leg has a positive weight and an mAsset on the allowlist.
+    let mut total_weight = 0u64;
+    let mut leg_min_open_uusd_amounts = vec![];
+    for leg in params.legs.iter() {
+        if leg.weight == 0 {
+            return Err(StdError::generic_err("leg weight must be positive"));
+        }
+        let (allowed, strategy_params) = get_mirror_asset_strategy_params(
+            storage,
+            &context,
+            &leg.params.mirror_asset_cw20_addr,
+        )?;
+        if !allowed {
+            return Err(StdError::generic_err("mAsset not allowed"));
+        }
+        leg_min_open_uusd_amounts.push(strategy_params.min_open_uusd_amount);
+        total_weight += leg.weight as u64;
+    }
+
+    // Split `uusd_amount` across legs in proportion to their weights; the last leg receives the rounding remainder.
+    let mut uusd_amounts = vec![];
+    let mut remaining_uusd_amount = uusd_amount;
+    for (index, leg) in params.legs.iter().enumerate() {
+        let leg_uusd_amount = if index + 1 == params.legs.len() {
+            remaining_uusd_amount
+        } else {
+            uusd_amount.multiply_ratio(leg.weight as u64, total_weight)
+        };
+        if leg_uusd_amount < leg_min_open_uusd_amounts[index] {
+            return Err(StdError::generic_err(
+                "UST amount too small to open a delta-neutral position leg",
+            ));
+        }
+        remaining_uusd_amount = remaining_uusd_amount.checked_sub(leg_uusd_amount)?;
+        uusd_amounts.push(leg_uusd_amount);
+    }
+    check_tvl_caps(
+        storage,
+        &params
+            .legs
+            .iter()
+            .map(|leg| leg.params.mirror_asset_cw20_addr.clone())
+            .zip(uusd_amounts.iter().cloned())
+            .collect::<Vec<(String, Uint128)>>(),
+    )?;
+
+    // Instantiate a new contract for each leg.
+    TMP_POSITION.save(storage, &position)?;
+    TMP_DIVERSIFIED_POSITION_LEG_WEIGHTS
+        .save(storage, &params.legs.iter().map(|leg| leg.weight).collect())?;
+    let code_id = ADMIN_CONFIG.load(storage)?.delta_neutral_position_code_id;
+    let mut response = Response::new();
+    for _ in params.legs.iter() {
+        response = response.add_submessage(create_position_contract_instantiate_submessage(
+            &env,
+            code_id,
+            DIVERSIFIED_POSITION_LEG_INSTANTIATE_REPLY_ID,
+        )?);
+    }
+
+    // Call leg contracts to open their positions.
+    response = response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::Internal(
+            InternalExecuteMsg::SendOpenPositionToDiversifiedPositionLegs {
+                position,
+                params,
+                uusd_amounts,
+            },
+        ))?,
+        funds: vec![],
+    }));
+    Ok(response)
+}
+
+// Sends `close_msg`, either `ClosePosition` or `EmergencyClosePosition`, to the position contract, or to every leg of a diversified position.
 pub fn close_position(
     deps: DepsMut,
     position: &Position,
-    recipient: Recipient,
+    close_msg: delta_neutral_position::ExecuteMsg,
 ) -> StdResult<Response> {
-    send_execute_message_to_position_contract(
-        deps.as_ref(),
-        position,
-        delta_neutral_position::ExecuteMsg::ClosePosition { recipient },
-        None,
-    )
+    if let Some(legs) =
+        DIVERSIFIED_POSITION_LEGS.may_load(deps.storage, get_position_key(position))?
+    {
+        let msg = to_binary(&close_msg)?;
+        return Ok(Response::new().add_messages(legs.into_iter().map(|leg| {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: leg.contract.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            })
+        })));
+    }
+    send_execute_message_to_position_contract(deps.as_ref(), position, close_msg, None)
 }
 
 // To store instantiated contract address into state and initiate investment.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    if msg.id >= POSITION_MIGRATION_REPLY_ID_BASE {
+        let batch = TMP_POSITION_MIGRATION_BATCH.load(deps.storage)?;
+        let contract = batch[(msg.id - POSITION_MIGRATION_REPLY_ID_BASE) as usize].clone();
+        let mut progress = POSITION_MIGRATION_PROGRESS.load(deps.storage)?;
+        progress.failed_contracts.push(contract);
+        POSITION_MIGRATION_PROGRESS.save(deps.storage, &progress)?;
+        return Ok(Response::default());
+    }
+    if msg.id >= PREEMPTIVE_CLOSE_REPLY_ID_BASE {
+        let (mirror_asset, batch) = TMP_PREEMPTIVE_CLOSE_BATCH.load(deps.storage)?;
+        let contract = batch[(msg.id - PREEMPTIVE_CLOSE_REPLY_ID_BASE) as usize].clone();
+        PREEMPTIVE_CLOSE_PROGRESS.update(
+            deps.storage,
+            mirror_asset,
+            |progress| -> StdResult<PreemptiveCloseProgress> {
+                let mut progress = progress.unwrap_or_default();
+                progress.failed_contracts.push(contract);
+                Ok(progress)
+            },
+        )?;
+        return Ok(Response::default());
+    }
+
     let data = msg.result.unwrap().data.unwrap();
     let res: MsgInstantiateContractResponse =
         Message::parse_from_bytes(data.as_slice()).map_err(|_| {
@@ -408,18 +1356,48 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
         })?;
     let contract_addr = deps.api.addr_validate(res.get_contract_address())?;
     let position = TMP_POSITION.load(deps.storage)?;
-    POSITION_TO_CONTRACT_ADDR.save(deps.storage, get_position_key(&position), &contract_addr)?;
+    if msg.id == DIVERSIFIED_POSITION_LEG_INSTANTIATE_REPLY_ID {
+        // Leg contracts are instantiated in order, so the number of recorded legs is the index of this leg.
+        let mut legs = DIVERSIFIED_POSITION_LEGS
+            .may_load(deps.storage, get_position_key(&position))?
+            .unwrap_or_default();
+        let weight = TMP_DIVERSIFIED_POSITION_LEG_WEIGHTS.load(deps.storage)?[legs.len()];
+        legs.push(DiversifiedPositionLeg {
+            contract: contract_addr,
+            weight,
+        });
+        DIVERSIFIED_POSITION_LEGS.save(deps.storage, get_position_key(&position), &legs)?;
+    } else {
+        POSITION_TO_CONTRACT_ADDR.save(
+            deps.storage,
+            get_position_key(&position),
+            &contract_addr,
+        )?;
+    }
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetPositionContractAddr { position } => {
             to_binary(&(POSITION_TO_CONTRACT_ADDR.load(deps.storage, get_position_key(&position))?))
         }
+        QueryMsg::GetDiversifiedPositionLegs { position } => {
+            to_binary(&(DIVERSIFIED_POSITION_LEGS.load(deps.storage, get_position_key(&position))?))
+        }
+        QueryMsg::GetTimelockDelay {} => to_binary(&TIMELOCK.get_delay_seconds(deps.storage)?),
+        QueryMsg::GetAdminOperationProposals { start_after, limit } => {
+            to_binary(&TIMELOCK.query_proposals(deps.storage, start_after, limit)?)
+        }
         QueryMsg::GetContext {} => to_binary(&CONTEXT.load(deps.storage)?),
+        QueryMsg::GetEmergencyCloseConfig {} => to_binary(
+            &EMERGENCY_CLOSE_CONFIG
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
         QueryMsg::GetAdminConfig {} => to_binary(&(ADMIN_CONFIG.load(deps.storage)?)),
+        QueryMsg::GetPendingAdmin {} => to_binary(&PENDING_ADMIN.may_load(deps.storage)?),
         QueryMsg::CheckMirrorAssetAllowlist { mirror_assets } => {
             to_binary(&CheckMirrorAssetAllowlistResponse {
                 allowed: mirror_assets
@@ -428,11 +1406,67 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
                         POSITION_OPEN_ALLOWED_MIRROR_ASSETS
                             .may_load(deps.storage, mirror_asset)
                             .unwrap()
+                            .map(|entry| entry.allowed)
                             .unwrap_or(false)
                     })
                     .collect(),
             })
         }
+        QueryMsg::GetMirrorAssetStrategyParams {
+            mirror_asset_cw20_addr,
+        } => {
+            let entry = POSITION_OPEN_ALLOWED_MIRROR_ASSETS
+                .may_load(deps.storage, mirror_asset_cw20_addr)?;
+            let params = entry
+                .clone()
+                .unwrap_or_default()
+                .overrides
+                .apply(&CONTEXT.load(deps.storage)?)?;
+            to_binary(&MirrorAssetStrategyParamsResponse { entry, params })
+        }
+        QueryMsg::GetMirrorAssetAprRanking { collateral_ratio } => {
+            to_binary(&MirrorAssetAprRankingResponse {
+                items: get_mirror_asset_apr_ranking(
+                    deps,
+                    &env,
+                    &CONTEXT.load(deps.storage)?,
+                    collateral_ratio,
+                )?,
+            })
+        }
+        QueryMsg::GetPositionsByMirrorAsset {
+            mirror_asset_cw20_addr,
+            start_after,
+            limit,
+        } => to_binary(&query_position_records(
+            deps,
+            position_records()
+                .idx
+                .mirror_asset
+                .prefix(mirror_asset_cw20_addr.into_bytes()),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GetPositionsByStatus {
+            status,
+            start_after,
+            limit,
+        } => to_binary(&query_position_records(
+            deps,
+            position_records()
+                .idx
+                .status
+                .prefix(get_position_status_index_key(status)),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GetTvlUtilization {} => to_binary(&query_tvl_utilization(deps)?),
+        QueryMsg::GetPreemptiveCloseProgress { mirror_asset } => {
+            to_binary(&PREEMPTIVE_CLOSE_PROGRESS.may_load(deps.storage, mirror_asset)?)
+        }
+        QueryMsg::GetPositionMigrationProgress {} => {
+            to_binary(&POSITION_MIGRATION_PROGRESS.may_load(deps.storage)?)
+        }
         QueryMsg::BatchGetPositionInfo { positions, ranges } => {
             let mut position_set = HashSet::new();
             if let Some(positions) = positions {
@@ -449,10 +1483,11 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             }
             let aperture_terra_manager = ADMIN_CONFIG.load(deps.storage)?.terra_manager;
             let position_info_query_msg = &delta_neutral_position::QueryMsg::GetPositionInfo {};
-            let mut response = BatchGetPositionInfoResponse { items: vec![] };
+            let mut response = BatchGetPositionInfoResponse {
+                items: vec![],
+                diversified_positions: vec![],
+            };
             for position in position_set {
-                let contract_addr = POSITION_TO_CONTRACT_ADDR
-                    .load(deps.storage, get_position_key_from_tuple(&position))?;
                 let holder = if position.0 == TERRA_CHAIN_ID {
                     let terra_position_info: terra_manager::PositionInfoResponse =
                         deps.querier.query_wasm_smart(
@@ -465,16 +1500,66 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 } else {
                     None
                 };
+                let position_key = get_position_key_from_tuple(&position);
+                let position = Position {
+                    chain_id: position.0,
+                    position_id: Uint128::from(position.1),
+                };
+                if let Some(legs) =
+                    DIVERSIFIED_POSITION_LEGS.may_load(deps.storage, position_key)?
+                {
+                    let mut aggregate_info = DiversifiedPositionAggregateInfo {
+                        position: position.clone(),
+                        legs: legs.clone(),
+                        open_uusd_amount: Uint128::zero(),
+                        uusd_value: Uint128::zero(),
+                        leg_value_ratios: vec![],
+                        closed: true,
+                    };
+                    let mut leg_uusd_values = vec![];
+                    for (leg_index, leg) in legs.into_iter().enumerate() {
+                        let info: PositionInfoResponse = deps
+                            .querier
+                            .query_wasm_smart(&leg.contract, position_info_query_msg)?;
+                        let leg_uusd_value = info
+                            .detailed_info
+                            .as_ref()
+                            .map_or(Uint128::zero(), |detailed_info| detailed_info.uusd_value);
+                        aggregate_info.open_uusd_amount += info.position_open_info.uusd_amount;
+                        aggregate_info.uusd_value += leg_uusd_value;
+                        aggregate_info.closed &= info.position_close_info.is_some();
+                        leg_uusd_values.push(leg_uusd_value);
+                        response.items.push(BatchGetPositionInfoResponseItem {
+                            position: position.clone(),
+                            contract: leg.contract,
+                            holder: holder.clone(),
+                            info,
+                            diversified_leg_index: Some(leg_index as u32),
+                        });
+                    }
+                    aggregate_info.leg_value_ratios = leg_uusd_values
+                        .into_iter()
+                        .map(|leg_uusd_value| {
+                            if aggregate_info.uusd_value.is_zero() {
+                                Decimal::zero()
+                            } else {
+                                Decimal::from_ratio(leg_uusd_value, aggregate_info.uusd_value)
+                            }
+                        })
+                        .collect();
+                    response.diversified_positions.push(aggregate_info);
+                    continue;
+                }
+                let contract_addr =
+                    POSITION_TO_CONTRACT_ADDR.load(deps.storage, get_position_key(&position))?;
                 response.items.push(BatchGetPositionInfoResponseItem {
-                    position: Position {
-                        chain_id: position.0,
-                        position_id: Uint128::from(position.1),
-                    },
+                    position,
                     contract: contract_addr.clone(),
                     holder,
                     info: deps
                         .querier
                         .query_wasm_smart(contract_addr, position_info_query_msg)?,
+                    diversified_leg_index: None,
                 });
             }
             to_binary(&response)
@@ -483,23 +1568,61 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             position,
             mirror_asset_net_amount_tolerance_ratio,
             liquid_uusd_threshold_ratio,
+            leg_index,
         } => query_should_call_rebalance_and_reinvest(
             deps,
             position,
             mirror_asset_net_amount_tolerance_ratio,
             liquid_uusd_threshold_ratio,
+            leg_index,
         ),
     }
 }
 
+fn query_tvl_utilization(deps: Deps) -> StdResult<TvlUtilizationResponse> {
+    let mut mirror_assets = vec![];
+    for entry in
+        POSITION_OPEN_ALLOWED_MIRROR_ASSETS.range(deps.storage, None, None, Order::Ascending)
+    {
+        let (key, allowlist_entry) = entry?;
+        let mirror_asset_cw20_addr = String::from_utf8(key)?;
+        mirror_assets.push(MirrorAssetTvlUtilization {
+            utilization: TvlUtilization {
+                deployed_uusd_amount: MIRROR_ASSET_DEPLOYED_UUSD_AMOUNTS
+                    .may_load(deps.storage, mirror_asset_cw20_addr.clone())?
+                    .unwrap_or_default(),
+                uusd_cap: allowlist_entry.overrides.max_tvl_uusd_amount,
+            },
+            mirror_asset_cw20_addr,
+        });
+    }
+    Ok(TvlUtilizationResponse {
+        global: TvlUtilization {
+            deployed_uusd_amount: TOTAL_DEPLOYED_UUSD_AMOUNT
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+            uusd_cap: GLOBAL_TVL_CAP.may_load(deps.storage)?,
+        },
+        mirror_assets,
+    })
+}
+
 fn query_should_call_rebalance_and_reinvest(
     deps: Deps,
     position: Position,
     mirror_asset_net_amount_tolerance_ratio: Decimal,
     liquid_uusd_threshold_ratio: Decimal,
+    leg_index: Option<u32>,
 ) -> StdResult<Binary> {
-    let position_contract =
-        POSITION_TO_CONTRACT_ADDR.load(deps.storage, get_position_key(&position))?;
+    let position_contract = match leg_index {
+        None => POSITION_TO_CONTRACT_ADDR.load(deps.storage, get_position_key(&position))?,
+        Some(leg_index) => DIVERSIFIED_POSITION_LEGS
+            .load(deps.storage, get_position_key(&position))?
+            .get(leg_index as usize)
+            .ok_or_else(|| StdError::generic_err("invalid leg index"))?
+            .contract
+            .clone(),
+    };
     let position_info: PositionInfoResponse = deps.querier.query_wasm_smart(
         &position_contract,
         &delta_neutral_position::QueryMsg::GetPositionInfo {},
@@ -621,13 +1744,15 @@ fn query_should_call_rebalance_and_reinvest(
         });
     }
 
-    let liquid_uusd_amount = info.state.as_ref().unwrap().uusd_balance
+    // While short proceeds are pending unlock, liquid uusd is parked in Anchor Earn; the parked uusd is reinvested after unlock.
+    let mut liquid_uusd_amount = info.state.as_ref().unwrap().uusd_balance
         + info.claimable_mir_reward_uusd_value
         + info.claimable_spec_reward_uusd_value
         + info.claimable_short_proceeds_uusd_amount;
-    if liquid_uusd_amount > info.uusd_value * liquid_uusd_threshold_ratio
-        && !uusd_short_proceeds_pending_unlock
-    {
+    if !uusd_short_proceeds_pending_unlock {
+        liquid_uusd_amount += info.anchor_earn_parked_uusd_value;
+    }
+    if liquid_uusd_amount > info.uusd_value * liquid_uusd_threshold_ratio {
         return to_binary(&ShouldCallRebalanceAndReinvestResponse {
             position_contract,
             should_call: true,
@@ -645,19 +1770,54 @@ fn query_should_call_rebalance_and_reinvest(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
     FEE_COLLECTION_CONFIG.save(deps.storage, &msg.fee_collection_config)?;
+    TIMELOCK.set_delay_seconds(deps.storage, msg.timelock_delay_seconds)?;
+
+    // Rewrite the context so that fields added since it was stored are persisted with their default values.
+    let context = CONTEXT.load(deps.storage)?;
+    CONTEXT.save(deps.storage, &context)?;
+
+    // Convert the legacy allowlist into entries without strategy parameter overrides.
+    let legacy_allowlist = LEGACY_POSITION_OPEN_ALLOWED_MIRROR_ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Vec<u8>, bool)>>>()?;
+    for (key, allowed) in legacy_allowlist {
+        let mirror_asset = String::from_utf8(key)?;
+        LEGACY_POSITION_OPEN_ALLOWED_MIRROR_ASSETS.remove(deps.storage, mirror_asset.clone());
+        POSITION_OPEN_ALLOWED_MIRROR_ASSETS.save(
+            deps.storage,
+            mirror_asset,
+            &MirrorAssetAllowlistEntry {
+                allowed,
+                overrides: MirrorAssetStrategyParamOverrides::default(),
+            },
+        )?;
+    }
+
     for mirror_asset in msg.position_open_allowed_mirror_assets {
-        POSITION_OPEN_ALLOWED_MIRROR_ASSETS.save(deps.storage, mirror_asset, &true)?;
+        POSITION_OPEN_ALLOWED_MIRROR_ASSETS.update(
+            deps.storage,
+            mirror_asset,
+            |entry| -> StdResult<MirrorAssetAllowlistEntry> {
+                let mut entry = entry.unwrap_or_default();
+                entry.allowed = true;
+                Ok(entry)
+            },
+        )?;
     }
     Ok(Response::default())
 }
 
 // Check that `assets` comprise exactly one native-uusd asset of amount >= min_uusd_amount.
-fn validate_assets(info: &MessageInfo, context: &Context, assets: &[Asset]) -> StdResult<Uint128> {
+fn validate_assets(
+    info: &MessageInfo,
+    min_uusd_amount: Uint128,
+    assets: &[Asset],
+) -> StdResult<Uint128> {
     if assets.len() == 1 {
         let asset = &assets[0];
         if let AssetInfo::NativeToken { denom } = &asset.info {
             if denom == "uusd"
-                && asset.amount >= context.min_open_uusd_amount
+                && asset.amount >= min_uusd_amount
                 && asset.assert_sent_native_token_balance(info).is_ok()
             {
                 return Ok(asset.amount);
@@ -667,14 +1827,56 @@ fn validate_assets(info: &MessageInfo, context: &Context, assets: &[Asset]) -> S
     Err(StdError::generic_err("invalid assets"))
 }
 
+// Proposes `operation` on behalf of `sender`, and executes it once the timelock delay has elapsed.
+#[cfg(test)]
+fn propose_and_execute_admin_operation(
+    mut deps: DepsMut,
+    env: Env,
+    sender: &str,
+    operation: AdminOperation,
+) -> StdResult<Response> {
+    use cosmwasm_std::testing::mock_info;
+
+    let response = execute(
+        deps.branch(),
+        env.clone(),
+        mock_info(sender, &[]),
+        ExecuteMsg::ProposeAdminOperation { operation },
+    )?;
+    let proposal_id = response.attributes[0].value.parse::<u64>().unwrap();
+    let mut later_env = env;
+    later_env.block.time = later_env
+        .block
+        .time
+        .plus_seconds(TIMELOCK.get_delay_seconds(deps.storage)?);
+    execute(
+        deps,
+        later_env,
+        mock_info(sender, &[]),
+        ExecuteMsg::ExecuteAdminOperation { proposal_id },
+    )
+}
+
 #[test]
 fn test_contract() {
+    use crate::mock_querier::{mock_deps, WasmMockQuerier};
+    use aperture_common::common::Recipient;
     use aperture_common::delta_neutral_position_manager::FeeCollectionConfig;
+    use aperture_common::timelock::{AdminOperationProposal, MIN_TIMELOCK_DELAY_SECONDS};
     use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{
+        from_slice, ContractResult, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest,
+        SystemResult, WasmQuery,
+    };
     use cosmwasm_std::{Addr, Decimal};
 
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_deps(&[
+        ("terra1pos345", None),
+        ("terra1pos456", Some(164)),
+        ("terra1pos567", Some(165)),
+        ("position_contract", None),
+    ]);
     let env = mock_env();
     let msg = InstantiateMsg {
         admin_addr: String::from("admin"),
@@ -686,6 +1888,7 @@ fn test_contract() {
         mirror_cw20_addr: String::from("mirror_cw20"),
         spectrum_cw20_addr: String::from("spectrum_cw20"),
         mirror_collateral_oracle_addr: String::from("mirror_collateral_oracle"),
+        mirror_limit_order_addr: None,
         mirror_lock_addr: String::from("mirror_lock"),
         mirror_mint_addr: String::from("mirror_mint"),
         mirror_oracle_addr: String::from("mirror_oracle"),
@@ -698,11 +1901,13 @@ fn test_contract() {
         collateral_ratio_safety_margin: Decimal::from_ratio(3u128, 10u128),
         min_open_uusd_amount: Uint128::from(500u128),
         min_reinvest_uusd_amount: Uint128::from(10u128),
+        limit_order_imbalance_threshold: Decimal::zero(),
         fee_collection_config: FeeCollectionConfig {
             performance_rate: Decimal::from_ratio(1u128, 10u128),
             off_market_position_open_service_fee_uusd: Uint128::zero(),
             collector_addr: String::from("collector"),
         },
+        timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
     };
 
     // Check state after instantiate().
@@ -734,6 +1939,7 @@ fn test_contract() {
             spectrum_cw20_addr: Addr::unchecked("spectrum_cw20"),
             anchor_market_addr: Addr::unchecked("anchor_market"),
             mirror_collateral_oracle_addr: Addr::unchecked("mirror_collateral_oracle"),
+            mirror_limit_order_addr: None,
             mirror_lock_addr: Addr::unchecked("mirror_lock"),
             mirror_mint_addr: Addr::unchecked("mirror_mint"),
             mirror_oracle_addr: Addr::unchecked("mirror_oracle"),
@@ -746,6 +1952,7 @@ fn test_contract() {
             collateral_ratio_safety_margin: Decimal::from_ratio(3u128, 10u128),
             min_open_uusd_amount: Uint128::from(500u128),
             min_reinvest_uusd_amount: Uint128::from(10u128),
+            limit_order_imbalance_threshold: Decimal::zero(),
         }
     );
     assert_eq!(
@@ -782,11 +1989,11 @@ fn test_contract() {
         Err(StdError::generic_err("unauthorized"))
     );
     assert_eq!(
-        execute(
+        propose_and_execute_admin_operation(
             deps.as_mut(),
             env.clone(),
-            mock_info("non-admin", &[]),
-            ExecuteMsg::UpdateAdminConfig {
+            "non-admin",
+            AdminOperation::UpdateAdminConfig {
                 admin_addr: Some(String::from("new-admin")),
                 terra_manager_addr: None,
                 delta_neutral_position_code_id: Some(159),
@@ -795,11 +2002,11 @@ fn test_contract() {
         Err(StdError::generic_err("unauthorized"))
     );
     assert_eq!(
-        execute(
+        propose_and_execute_admin_operation(
             deps.as_mut(),
             env.clone(),
-            mock_info("non-admin", &[]),
-            ExecuteMsg::UpdateFeeCollectionConfig {
+            "non-admin",
+            AdminOperation::UpdateFeeCollectionConfig {
                 fee_collection_config: FeeCollectionConfig {
                     performance_rate: Decimal::from_ratio(1u128, 10u128),
                     off_market_position_open_service_fee_uusd: Uint128::from(10u128),
@@ -810,11 +2017,11 @@ fn test_contract() {
         Err(StdError::generic_err("unauthorized"))
     );
     assert_eq!(
-        execute(
+        propose_and_execute_admin_operation(
             deps.as_mut(),
             env.clone(),
-            mock_info("non-admin", &[]),
-            ExecuteMsg::UpdatePositionOpenMirrorAssetList {
+            "non-admin",
+            AdminOperation::UpdatePositionOpenMirrorAssetList {
                 mirror_assets: vec![],
                 allowed: true
             },
@@ -827,6 +2034,9 @@ fn test_contract() {
         target_max_collateral_ratio: Decimal::from_ratio(27u128, 10u128),
         mirror_asset_cw20_addr: String::from("terra1ys4dwwzaenjg2gy02mslmc96f267xvpsjat7gx"),
         allow_off_market_position_open: None,
+        liquidity_venue: None,
+        collateral_asset_info: None,
+        spec_reward_policy: None,
     };
     let data = Some(to_binary(&delta_neutral_params).unwrap());
 
@@ -902,11 +2112,11 @@ fn test_contract() {
 
     // Allow mAsset and open position.
     assert_eq!(
-        execute(
+        propose_and_execute_admin_operation(
             deps.as_mut(),
             env.clone(),
-            mock_info("admin", &[]),
-            ExecuteMsg::UpdatePositionOpenMirrorAssetList {
+            "admin",
+            AdminOperation::UpdatePositionOpenMirrorAssetList {
                 mirror_assets: vec![delta_neutral_params.mirror_asset_cw20_addr.clone()],
                 allowed: true
             },
@@ -1053,36 +2263,133 @@ fn test_contract() {
         })
     );
 
-    // Admin config update.
+    // Emergency close is forwarded to the position contract, which checks oracle staleness.
+    let recipient = Recipient::TerraChain {
+        recipient: String::from("terra1recipient"),
+    };
+    let response = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("manager", &[]),
+        ExecuteMsg::PerformAction {
+            position: position.clone(),
+            action: Action::EmergencyClosePosition {
+                recipient: recipient.clone(),
+            },
+            assets: vec![],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        response.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("position_contract"),
+            funds: vec![],
+            msg: to_binary(
+                &delta_neutral_position::ExecuteMsg::EmergencyClosePosition { recipient }
+            )
+            .unwrap(),
+        }))]
+    );
+
+    // Emergency close config update.
+    let query_emergency_close_config = |deps: Deps| -> EmergencyCloseConfig {
+        from_binary(&query(deps, env.clone(), QueryMsg::GetEmergencyCloseConfig {}).unwrap())
+            .unwrap()
+    };
     assert_eq!(
-        execute(
+        query_emergency_close_config(deps.as_ref()),
+        EmergencyCloseConfig::default()
+    );
+    let emergency_close_config = EmergencyCloseConfig {
+        min_oracle_stale_seconds: 86400,
+        max_haircut: Decimal::percent(10),
+    };
+    assert_eq!(
+        propose_and_execute_admin_operation(
             deps.as_mut(),
             env.clone(),
-            mock_info("admin", &[]),
-            ExecuteMsg::UpdateAdminConfig {
+            "non-admin",
+            AdminOperation::UpdateEmergencyCloseConfig {
+                emergency_close_config: emergency_close_config.clone(),
+            },
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+    assert_eq!(
+        propose_and_execute_admin_operation(
+            deps.as_mut(),
+            env.clone(),
+            "admin",
+            AdminOperation::UpdateEmergencyCloseConfig {
+                emergency_close_config: emergency_close_config.clone(),
+            },
+        ),
+        Ok(Response::default())
+    );
+    assert_eq!(
+        query_emergency_close_config(deps.as_ref()),
+        emergency_close_config
+    );
+
+    // Admin config update; the nominated admin takes over once they accept.
+    assert_eq!(
+        propose_and_execute_admin_operation(
+            deps.as_mut(),
+            env.clone(),
+            "admin",
+            AdminOperation::UpdateAdminConfig {
                 admin_addr: Some(String::from("new-admin")),
                 terra_manager_addr: None,
                 delta_neutral_position_code_id: Some(165),
             },
         ),
-        Ok(Response::default())
+        Ok(Response::new().add_attribute("pending_admin", "new-admin"))
     );
     assert_eq!(
         ADMIN_CONFIG.load(deps.as_ref().storage).unwrap(),
         AdminConfig {
-            admin: Addr::unchecked("new-admin"),
+            admin: Addr::unchecked("admin"),
             terra_manager: Addr::unchecked("manager"),
             delta_neutral_position_code_id: 165,
         }
     );
+    assert_eq!(
+        from_binary::<Option<Addr>>(
+            &query(deps.as_ref(), env.clone(), QueryMsg::GetPendingAdmin {}).unwrap()
+        )
+        .unwrap(),
+        Some(Addr::unchecked("new-admin"))
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("non-admin", &[]),
+            ExecuteMsg::AcceptAdmin {},
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("new-admin", &[]),
+        ExecuteMsg::AcceptAdmin {},
+    )
+    .unwrap();
+    assert_eq!(
+        ADMIN_CONFIG.load(deps.as_ref().storage).unwrap().admin,
+        Addr::unchecked("new-admin")
+    );
+    assert_eq!(PENDING_ADMIN.may_load(deps.as_ref().storage).unwrap(), None);
 
     // Fee collection config update.
     assert_eq!(
-        execute(
+        propose_and_execute_admin_operation(
             deps.as_mut(),
             env.clone(),
-            mock_info("new-admin", &[]),
-            ExecuteMsg::UpdateFeeCollectionConfig {
+            "new-admin",
+            AdminOperation::UpdateFeeCollectionConfig {
                 fee_collection_config: FeeCollectionConfig {
                     performance_rate: Decimal::from_ratio(2u128, 10u128),
                     off_market_position_open_service_fee_uusd: Uint128::from(100u128),
@@ -1101,15 +2408,24 @@ fn test_contract() {
         }
     );
 
-    // Migrate position contract.
-    let response = execute(
+    // Migrate position contracts; "terra1pos345" and "position_contract" have never been migrated, and "terra1pos567" is already on the current code id.
+    let migrate_position_contracts = |deps: DepsMut, position_contracts: &[&str]| {
+        execute(
+            deps,
+            env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::MigratePositionContracts {
+                positions: vec![position.clone()],
+                position_contracts: position_contracts
+                    .iter()
+                    .map(|contract| contract.to_string())
+                    .collect(),
+            },
+        )
+    };
+    let response = migrate_position_contracts(
         deps.as_mut(),
-        env.clone(),
-        mock_info("anyone", &[]),
-        ExecuteMsg::MigratePositionContracts {
-            positions: vec![position.clone()],
-            position_contracts: vec![String::from("terra1pos345"), String::from("terra1pos456")],
-        },
+        &["terra1pos345", "terra1pos456", "terra1pos567"],
     )
     .unwrap();
     assert_eq!(response.messages.len(), 3);
@@ -1137,4 +2453,1163 @@ fn test_contract() {
             msg: to_binary(&delta_neutral_position::MigrateMsg { new_code_id: 165 }).unwrap(),
         })
     );
+    // A contract whose code id cannot be queried aborts the migration.
+    assert!(migrate_position_contracts(deps.as_mut(), &["terra1unknown"]).is_err());
+
+    // Context update; addresses that do not answer the expected config query are rejected.
+    // Mock querier that additionally answers the Mirror oracle config query at "new_mirror_oracle".
+    struct ContextQuerier(WasmMockQuerier);
+    impl Querier for ContextQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_slice(bin_request) {
+                Ok(QueryRequest::<Empty>::Wasm(WasmQuery::Smart { contract_addr, .. }))
+                    if contract_addr == "new_mirror_oracle" =>
+                {
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&mirror_protocol::oracle::ConfigResponse {
+                            owner: String::from("owner"),
+                            base_asset: String::from("uusd"),
+                        })
+                        .unwrap(),
+                    ))
+                }
+                _ => self.0.raw_query(bin_request),
+            }
+        }
+    }
+    let mut deps = OwnedDeps {
+        storage: deps.storage,
+        api: deps.api,
+        querier: ContextQuerier(deps.querier),
+    };
+    let update_context = |deps: DepsMut, update: ContextUpdate| {
+        propose_and_execute_admin_operation(
+            deps,
+            env.clone(),
+            "new-admin",
+            AdminOperation::UpdateContext(Box::new(update)),
+        )
+    };
+    assert!(update_context(
+        deps.as_mut(),
+        ContextUpdate {
+            mirror_oracle_addr: Some(String::from("new_mirror_oracle")),
+            // Typo of the new Mirror oracle address.
+            mirror_mint_addr: Some(String::from("new_mirror_orcale")),
+            ..Default::default()
+        }
+    )
+    .is_err());
+    assert!(update_context(
+        deps.as_mut(),
+        ContextUpdate {
+            // A contract of the wrong kind.
+            mirror_staking_addr: Some(String::from("new_mirror_oracle")),
+            ..Default::default()
+        }
+    )
+    .is_err());
+    assert_eq!(
+        update_context(
+            deps.as_mut(),
+            ContextUpdate {
+                mirror_oracle_addr: Some(String::from("new_mirror_oracle")),
+                min_reinvest_uusd_amount: Some(Uint128::from(20u128)),
+                ..Default::default()
+            }
+        ),
+        Ok(Response::default())
+    );
+    let context = CONTEXT.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        context.mirror_oracle_addr,
+        Addr::unchecked("new_mirror_oracle")
+    );
+    assert_eq!(context.mirror_mint_addr, Addr::unchecked("mirror_mint"));
+    assert_eq!(context.min_reinvest_uusd_amount, Uint128::from(20u128));
+
+    // Sensitive operations cannot be performed directly.
+    let direct_msgs = vec![
+        ExecuteMsg::UpdateAdminConfig {
+            admin_addr: Some(String::from("newer-admin")),
+            terra_manager_addr: None,
+            delta_neutral_position_code_id: None,
+        },
+        ExecuteMsg::UpdatePositionOpenMirrorAssetList {
+            mirror_assets: vec![String::from("terra1ys4dwwzaenjg2gy02mslmc96f267xvpsjat7gx")],
+            allowed: false,
+        },
+        ExecuteMsg::UpdateFeeCollectionConfig {
+            fee_collection_config: FeeCollectionConfig {
+                performance_rate: Decimal::one(),
+                off_market_position_open_service_fee_uusd: Uint128::zero(),
+                collector_addr: String::from("non-admin"),
+            },
+        },
+        ExecuteMsg::UpdateEmergencyCloseConfig {
+            emergency_close_config: EmergencyCloseConfig {
+                min_oracle_stale_seconds: 86400,
+                max_haircut: Decimal::one(),
+            },
+        },
+        ExecuteMsg::UpdateGlobalTvlCap { uusd_cap: None },
+        ExecuteMsg::UpdateMirrorAssetStrategyParamOverrides {
+            mirror_assets: vec![],
+            overrides: MirrorAssetStrategyParamOverrides::default(),
+        },
+        ExecuteMsg::UpdateContext(ContextUpdate::default()),
+    ];
+    for msg in direct_msgs {
+        assert_eq!(
+            execute(deps.as_mut(), env.clone(), mock_info("new-admin", &[]), msg),
+            Err(StdError::generic_err(
+                "operation is subject to timelock and must be proposed"
+            ))
+        );
+    }
+
+    // A change to the timelock delay only takes effect once the current delay has elapsed.
+    let propose = |deps: DepsMut, sender: &str, operation: AdminOperation| {
+        execute(
+            deps,
+            env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::ProposeAdminOperation { operation },
+        )
+        .map(|response| response.attributes[0].value.parse::<u64>().unwrap())
+    };
+    let execute_proposal = |deps: DepsMut, env: Env, sender: &str, proposal_id: u64| {
+        execute(
+            deps,
+            env,
+            mock_info(sender, &[]),
+            ExecuteMsg::ExecuteAdminOperation { proposal_id },
+        )
+    };
+    let query_timelock_delay = |deps: Deps| {
+        from_binary::<u64>(&query(deps, env.clone(), QueryMsg::GetTimelockDelay {}).unwrap())
+            .unwrap()
+    };
+    assert_eq!(
+        query_timelock_delay(deps.as_ref()),
+        MIN_TIMELOCK_DELAY_SECONDS
+    );
+    let update_timelock_delay = AdminOperation::UpdateTimelockDelay {
+        delay_seconds: 2 * MIN_TIMELOCK_DELAY_SECONDS,
+    };
+    assert_eq!(
+        propose(deps.as_mut(), "non-admin", update_timelock_delay.clone()),
+        Err(StdError::generic_err("unauthorized"))
+    );
+    assert_eq!(
+        propose(
+            deps.as_mut(),
+            "new-admin",
+            AdminOperation::UpdateTimelockDelay {
+                delay_seconds: MIN_TIMELOCK_DELAY_SECONDS - 1,
+            },
+        ),
+        Err(StdError::generic_err(
+            "timelock delay must be at least 86400 seconds"
+        ))
+    );
+    let update_timelock_delay_id =
+        propose(deps.as_mut(), "new-admin", update_timelock_delay).unwrap();
+    assert_eq!(
+        execute_proposal(
+            deps.as_mut(),
+            env.clone(),
+            "new-admin",
+            update_timelock_delay_id
+        ),
+        Err(StdError::generic_err("timelock has not elapsed"))
+    );
+    let mut later_env = env.clone();
+    later_env.block.time = later_env
+        .block
+        .time
+        .plus_seconds(MIN_TIMELOCK_DELAY_SECONDS);
+    execute_proposal(
+        deps.as_mut(),
+        later_env,
+        "new-admin",
+        update_timelock_delay_id,
+    )
+    .unwrap();
+    assert_eq!(
+        query_timelock_delay(deps.as_ref()),
+        2 * MIN_TIMELOCK_DELAY_SECONDS
+    );
+
+    // Admin handover through the timelock: executing the proposal nominates the new admin, who must then accept.
+    let update_admin_config_id = propose(
+        deps.as_mut(),
+        "new-admin",
+        AdminOperation::UpdateAdminConfig {
+            admin_addr: Some(String::from("newer-admin")),
+            terra_manager_addr: None,
+            delta_neutral_position_code_id: Some(166),
+        },
+    )
+    .unwrap();
+    let update_context_id = propose(
+        deps.as_mut(),
+        "new-admin",
+        AdminOperation::UpdateContext(Box::new(ContextUpdate {
+            min_reinvest_uusd_amount: Some(Uint128::from(30u128)),
+            ..Default::default()
+        })),
+    )
+    .unwrap();
+    let proposals: Vec<AdminOperationProposal<AdminOperation>> = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetAdminOperationProposals {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        proposals
+            .iter()
+            .map(|proposal| proposal.proposal_id)
+            .collect::<Vec<u64>>(),
+        vec![update_admin_config_id, update_context_id]
+    );
+    assert_eq!(
+        proposals[0].executable_after,
+        env.block.time.plus_seconds(2 * MIN_TIMELOCK_DELAY_SECONDS)
+    );
+    assert_eq!(
+        execute_proposal(
+            deps.as_mut(),
+            env.clone(),
+            "newer-admin",
+            update_admin_config_id
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+    assert_eq!(
+        execute_proposal(
+            deps.as_mut(),
+            env.clone(),
+            "new-admin",
+            update_admin_config_id
+        ),
+        Err(StdError::generic_err("timelock has not elapsed"))
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("non-admin", &[]),
+            ExecuteMsg::CancelAdminOperation {
+                proposal_id: update_context_id
+            },
+        ),
+        Err(StdError::generic_err("unauthorized"))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("new-admin", &[]),
+        ExecuteMsg::CancelAdminOperation {
+            proposal_id: update_context_id,
+        },
+    )
+    .unwrap();
+    let mut later_env = env.clone();
+    later_env.block.time = later_env
+        .block
+        .time
+        .plus_seconds(2 * MIN_TIMELOCK_DELAY_SECONDS);
+    execute_proposal(
+        deps.as_mut(),
+        later_env.clone(),
+        "new-admin",
+        update_admin_config_id,
+    )
+    .unwrap();
+    assert_eq!(
+        ADMIN_CONFIG.load(deps.as_ref().storage).unwrap(),
+        AdminConfig {
+            admin: Addr::unchecked("new-admin"),
+            terra_manager: Addr::unchecked("manager"),
+            delta_neutral_position_code_id: 166,
+        }
+    );
+    assert_eq!(
+        execute_proposal(deps.as_mut(), later_env, "new-admin", update_context_id),
+        Err(StdError::generic_err("proposal not found"))
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("newer-admin", &[]),
+        ExecuteMsg::AcceptAdmin {},
+    )
+    .unwrap();
+    assert_eq!(
+        ADMIN_CONFIG.load(deps.as_ref().storage).unwrap().admin,
+        Addr::unchecked("newer-admin")
+    );
+}
+
+#[test]
+fn test_migrate() {
+    use aperture_common::timelock::MIN_TIMELOCK_DELAY_SECONDS;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    let mut deps = mock_dependencies(&[]);
+    // Context as stored before `mirror_limit_order_addr` and `limit_order_imbalance_threshold` were introduced.
+    deps.as_mut().storage.set(
+        b"context",
+        br#"{"controller":"controller","anchor_ust_cw20_addr":"anchor_ust_cw20","mirror_cw20_addr":"mirror_cw20","spectrum_cw20_addr":"spectrum_cw20","anchor_market_addr":"anchor_market","mirror_collateral_oracle_addr":"mirror_collateral_oracle","mirror_lock_addr":"mirror_lock","mirror_mint_addr":"mirror_mint","mirror_oracle_addr":"mirror_oracle","mirror_staking_addr":"mirror_staking","spectrum_gov_addr":"spectrum_gov","spectrum_mirror_farms_addr":"spectrum_mirror_farms","spectrum_staker_addr":"spectrum_staker","terraswap_factory_addr":"terraswap_factory","astroport_factory_addr":"astroport_factory","collateral_ratio_safety_margin":"0.3","min_open_uusd_amount":"500","min_reinvest_uusd_amount":"10"}"#,
+    );
+    LEGACY_POSITION_OPEN_ALLOWED_MIRROR_ASSETS
+        .save(deps.as_mut().storage, String::from("mAAPL"), &true)
+        .unwrap();
+    let expected_context = Context {
+        controller: Addr::unchecked("controller"),
+        anchor_ust_cw20_addr: Addr::unchecked("anchor_ust_cw20"),
+        mirror_cw20_addr: Addr::unchecked("mirror_cw20"),
+        spectrum_cw20_addr: Addr::unchecked("spectrum_cw20"),
+        anchor_market_addr: Addr::unchecked("anchor_market"),
+        mirror_collateral_oracle_addr: Addr::unchecked("mirror_collateral_oracle"),
+        mirror_limit_order_addr: None,
+        mirror_lock_addr: Addr::unchecked("mirror_lock"),
+        mirror_mint_addr: Addr::unchecked("mirror_mint"),
+        mirror_oracle_addr: Addr::unchecked("mirror_oracle"),
+        mirror_staking_addr: Addr::unchecked("mirror_staking"),
+        spectrum_gov_addr: Addr::unchecked("spectrum_gov"),
+        spectrum_mirror_farms_addr: Addr::unchecked("spectrum_mirror_farms"),
+        spectrum_staker_addr: Addr::unchecked("spectrum_staker"),
+        terraswap_factory_addr: Addr::unchecked("terraswap_factory"),
+        astroport_factory_addr: Addr::unchecked("astroport_factory"),
+        collateral_ratio_safety_margin: Decimal::from_ratio(3u128, 10u128),
+        min_open_uusd_amount: Uint128::from(500u128),
+        min_reinvest_uusd_amount: Uint128::from(10u128),
+        limit_order_imbalance_threshold: Decimal::zero(),
+    };
+    // The legacy context is readable, e.g. by position contracts, even before the manager is migrated.
+    assert_eq!(
+        CONTEXT.load(deps.as_ref().storage),
+        Ok(expected_context.clone())
+    );
+
+    let fee_collection_config = FeeCollectionConfig {
+        performance_rate: Decimal::from_ratio(1u128, 10u128),
+        off_market_position_open_service_fee_uusd: Uint128::from(100u128),
+        collector_addr: String::from("collector"),
+    };
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            fee_collection_config: fee_collection_config.clone(),
+            position_open_allowed_mirror_assets: vec![String::from("mTSLA")],
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    assert_eq!(CONTEXT.load(deps.as_ref().storage), Ok(expected_context));
+    assert_eq!(
+        TIMELOCK.get_delay_seconds(deps.as_ref().storage),
+        Ok(MIN_TIMELOCK_DELAY_SECONDS)
+    );
+    assert_eq!(
+        from_binary::<Context>(&Binary::from(
+            deps.as_ref().storage.get(b"context").unwrap()
+        )),
+        CONTEXT.load(deps.as_ref().storage)
+    );
+    assert!(
+        String::from_utf8(deps.as_ref().storage.get(b"context").unwrap())
+            .unwrap()
+            .contains(r#""limit_order_imbalance_threshold":"0""#)
+    );
+    assert_eq!(
+        FEE_COLLECTION_CONFIG.load(deps.as_ref().storage),
+        Ok(fee_collection_config)
+    );
+    for mirror_asset in &["mAAPL", "mTSLA"] {
+        assert_eq!(
+            POSITION_OPEN_ALLOWED_MIRROR_ASSETS
+                .load(deps.as_ref().storage, String::from(*mirror_asset))
+                .map(|entry| entry.allowed),
+            Ok(true)
+        );
+    }
+    assert_eq!(
+        LEGACY_POSITION_OPEN_ALLOWED_MIRROR_ASSETS
+            .may_load(deps.as_ref().storage, String::from("mAAPL")),
+        Ok(None)
+    );
+}
+
+#[test]
+fn test_diversified_position() {
+    use crate::mock_querier::mock_deps;
+    use aperture_common::common::Recipient;
+    use aperture_common::delta_neutral_position_manager::DiversifiedDeltaNeutralLegParams;
+    use aperture_common::timelock::MIN_TIMELOCK_DELAY_SECONDS;
+    use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{ContractResult, SubMsgExecutionResponse};
+
+    let mut deps = mock_deps(&[("terra1leg0", None), ("terra1leg1", None)]);
+    let env = mock_env();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("instantiate_sender", &[]),
+        InstantiateMsg {
+            admin_addr: String::from("admin"),
+            terra_manager_addr: String::from("manager"),
+            anchor_ust_cw20_addr: String::from("anchor_ust_cw20"),
+            anchor_market_addr: String::from("anchor_market"),
+            delta_neutral_position_code_id: 123,
+            controller: String::from("controller"),
+            mirror_cw20_addr: String::from("mirror_cw20"),
+            spectrum_cw20_addr: String::from("spectrum_cw20"),
+            mirror_collateral_oracle_addr: String::from("mirror_collateral_oracle"),
+            mirror_limit_order_addr: None,
+            mirror_lock_addr: String::from("mirror_lock"),
+            mirror_mint_addr: String::from("mirror_mint"),
+            mirror_oracle_addr: String::from("mirror_oracle"),
+            mirror_staking_addr: String::from("mirror_staking"),
+            spectrum_gov_addr: String::from("spectrum_gov"),
+            spectrum_mirror_farms_addr: String::from("spectrum_mirror_farms"),
+            spectrum_staker_addr: String::from("spectrum_staker"),
+            terraswap_factory_addr: String::from("terraswap_factory"),
+            astroport_factory_addr: String::from("astroport_factory"),
+            collateral_ratio_safety_margin: Decimal::from_ratio(3u128, 10u128),
+            min_open_uusd_amount: Uint128::from(500u128),
+            min_reinvest_uusd_amount: Uint128::from(10u128),
+            limit_order_imbalance_threshold: Decimal::zero(),
+            fee_collection_config: FeeCollectionConfig {
+                performance_rate: Decimal::from_ratio(1u128, 10u128),
+                off_market_position_open_service_fee_uusd: Uint128::zero(),
+                collector_addr: String::from("collector"),
+            },
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        env.clone(),
+        "admin",
+        AdminOperation::UpdatePositionOpenMirrorAssetList {
+            mirror_assets: vec![String::from("mAAPL"), String::from("mTSLA")],
+            allowed: true,
+        },
+    )
+    .unwrap();
+
+    let position = Position {
+        chain_id: TERRA_CHAIN_ID,
+        position_id: Uint128::from(7u128),
+    };
+    let leg_params = |weight: u32, mirror_asset: &str| -> DiversifiedDeltaNeutralLegParams {
+        DiversifiedDeltaNeutralLegParams {
+            weight,
+            params: DeltaNeutralParams {
+                target_min_collateral_ratio: Decimal::from_ratio(23u128, 10u128),
+                target_max_collateral_ratio: Decimal::from_ratio(27u128, 10u128),
+                mirror_asset_cw20_addr: String::from(mirror_asset),
+                allow_off_market_position_open: None,
+                liquidity_venue: None,
+                collateral_asset_info: None,
+                spec_reward_policy: None,
+            },
+        }
+    };
+    let open = |deps: DepsMut, legs: Vec<DiversifiedDeltaNeutralLegParams>, amount: u128| {
+        let uusd_coin = Coin {
+            denom: String::from("uusd"),
+            amount: Uint128::from(amount),
+        };
+        execute(
+            deps,
+            mock_env(),
+            mock_info("manager", std::slice::from_ref(&uusd_coin)),
+            ExecuteMsg::PerformAction {
+                position: position.clone(),
+                action: Action::OpenPosition {
+                    data: Some(to_binary(&DiversifiedDeltaNeutralParams { legs }).unwrap()),
+                },
+                assets: vec![Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: uusd_coin.denom,
+                    },
+                    amount: uusd_coin.amount,
+                }],
+            },
+        )
+    };
+
+    // Validate legs.
+    assert_eq!(
+        open(deps.as_mut(), vec![], 2000u128).unwrap_err(),
+        StdError::generic_err("invalid number of legs")
+    );
+    assert_eq!(
+        open(
+            deps.as_mut(),
+            vec![leg_params(0, "mAAPL"), leg_params(3, "mTSLA")],
+            2000u128
+        )
+        .unwrap_err(),
+        StdError::generic_err("leg weight must be positive")
+    );
+    assert_eq!(
+        open(
+            deps.as_mut(),
+            vec![leg_params(1, "mAAPL"), leg_params(3, "mTSLA")],
+            1000u128
+        )
+        .unwrap_err(),
+        StdError::generic_err("UST amount too small to open a delta-neutral position leg")
+    );
+
+    // Malformed data is rejected with the parse error of the params type selected by the presence of `legs`.
+    let open_with_data = |deps: DepsMut, data: &[u8]| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("manager", &[]),
+            ExecuteMsg::PerformAction {
+                position: position.clone(),
+                action: Action::OpenPosition {
+                    data: Some(Binary::from(data)),
+                },
+                assets: vec![],
+            },
+        )
+    };
+    assert!(matches!(
+        open_with_data(deps.as_mut(), br#"{"target_min_collateral_ratio":"2.3"}"#).unwrap_err(),
+        StdError::ParseErr { target_type, .. } if target_type.ends_with("::DeltaNeutralParams")
+    ));
+    assert!(matches!(
+        open_with_data(deps.as_mut(), br#"{"legs":[{"weight":1}]}"#).unwrap_err(),
+        StdError::ParseErr { target_type, .. } if target_type.ends_with("::DiversifiedDeltaNeutralParams")
+    ));
+
+    // Enforce TVL caps across legs.
+    assert_eq!(
+        propose_and_execute_admin_operation(
+            deps.as_mut(),
+            env.clone(),
+            "anyone",
+            AdminOperation::UpdateGlobalTvlCap {
+                uusd_cap: Some(Uint128::from(2000u128)),
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+    let update_overrides = |deps: DepsMut, overrides: MirrorAssetStrategyParamOverrides| {
+        propose_and_execute_admin_operation(
+            deps,
+            mock_env(),
+            "admin",
+            AdminOperation::UpdateMirrorAssetStrategyParamOverrides {
+                mirror_assets: vec![String::from("mTSLA")],
+                overrides,
+            },
+        )
+        .unwrap()
+    };
+    update_overrides(
+        deps.as_mut(),
+        MirrorAssetStrategyParamOverrides {
+            min_open_uusd_amount: Some(Uint128::from(1600u128)),
+            ..MirrorAssetStrategyParamOverrides::default()
+        },
+    );
+    assert_eq!(
+        open(
+            deps.as_mut(),
+            vec![leg_params(1, "mAAPL"), leg_params(3, "mTSLA")],
+            2001u128
+        )
+        .unwrap_err(),
+        StdError::generic_err("UST amount too small to open a delta-neutral position leg")
+    );
+    update_overrides(
+        deps.as_mut(),
+        MirrorAssetStrategyParamOverrides {
+            max_tvl_uusd_amount: Some(Uint128::from(1500u128)),
+            ..MirrorAssetStrategyParamOverrides::default()
+        },
+    );
+    assert_eq!(
+        open(
+            deps.as_mut(),
+            vec![leg_params(1, "mAAPL"), leg_params(3, "mTSLA")],
+            2001u128
+        )
+        .unwrap_err(),
+        StdError::generic_err("mAsset TVL cap exceeded")
+    );
+    let overrides = MirrorAssetStrategyParamOverrides {
+        collateral_ratio_safety_margin: Some(Decimal::percent(50)),
+        max_tvl_uusd_amount: Some(Uint128::from(2000u128)),
+        ..MirrorAssetStrategyParamOverrides::default()
+    };
+    update_overrides(deps.as_mut(), overrides.clone());
+    assert_eq!(
+        from_binary::<MirrorAssetStrategyParamsResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetMirrorAssetStrategyParams {
+                    mirror_asset_cw20_addr: String::from("mTSLA"),
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        MirrorAssetStrategyParamsResponse {
+            entry: Some(MirrorAssetAllowlistEntry {
+                allowed: true,
+                overrides,
+            }),
+            params: MirrorAssetStrategyParams {
+                collateral_ratio_safety_margin: Decimal::percent(50),
+                min_target_collateral_ratio_range_width: Decimal::percent(40),
+                min_open_uusd_amount: Uint128::from(500u128),
+                slippage_tolerance: None,
+                max_tvl_uusd_amount: Some(Uint128::from(2000u128)),
+            },
+        }
+    );
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        env.clone(),
+        "admin",
+        AdminOperation::UpdateGlobalTvlCap {
+            uusd_cap: Some(Uint128::from(2000u128)),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        open(
+            deps.as_mut(),
+            vec![leg_params(1, "mAAPL"), leg_params(3, "mTSLA")],
+            2001u128
+        )
+        .unwrap_err(),
+        StdError::generic_err("global TVL cap exceeded")
+    );
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        env.clone(),
+        "admin",
+        AdminOperation::UpdateGlobalTvlCap { uusd_cap: None },
+    )
+    .unwrap();
+
+    // Open a diversified position with two legs weighted 1:3.
+    let legs = vec![leg_params(1, "mAAPL"), leg_params(3, "mTSLA")];
+    let response = open(deps.as_mut(), legs.clone(), 2001u128).unwrap();
+    assert_eq!(response.messages.len(), 3);
+    for message in response.messages[0..2].iter() {
+        assert_eq!(
+            message,
+            &create_position_contract_instantiate_submessage(
+                &env,
+                123,
+                DIVERSIFIED_POSITION_LEG_INSTANTIATE_REPLY_ID
+            )
+            .unwrap()
+        );
+    }
+    assert_eq!(
+        response.messages[2].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::Internal(
+                InternalExecuteMsg::SendOpenPositionToDiversifiedPositionLegs {
+                    position: position.clone(),
+                    params: DiversifiedDeltaNeutralParams { legs: legs.clone() },
+                    uusd_amounts: vec![Uint128::from(500u128), Uint128::from(1501u128)],
+                },
+            ))
+            .unwrap(),
+        })
+    );
+
+    // Record leg contracts upon instantiation.
+    for contract_address in ["terra1leg0", "terra1leg1"] {
+        let mut instantiate_response = MsgInstantiateContractResponse::new();
+        instantiate_response.set_contract_address(String::from(contract_address));
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: DIVERSIFIED_POSITION_LEG_INSTANTIATE_REPLY_ID,
+                result: ContractResult::Ok(SubMsgExecutionResponse {
+                    events: vec![],
+                    data: Some(Binary::from(instantiate_response.write_to_bytes().unwrap())),
+                }),
+            },
+        )
+        .unwrap();
+    }
+    let expected_legs = vec![
+        DiversifiedPositionLeg {
+            contract: Addr::unchecked("terra1leg0"),
+            weight: 1,
+        },
+        DiversifiedPositionLeg {
+            contract: Addr::unchecked("terra1leg1"),
+            weight: 3,
+        },
+    ];
+    assert_eq!(
+        from_binary::<Vec<DiversifiedPositionLeg>>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetDiversifiedPositionLegs {
+                    position: position.clone()
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        expected_legs
+    );
+
+    // Send uusd to leg contracts to open their positions.
+    let response = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Internal(
+            InternalExecuteMsg::SendOpenPositionToDiversifiedPositionLegs {
+                position: position.clone(),
+                params: DiversifiedDeltaNeutralParams { legs: legs.clone() },
+                uusd_amounts: vec![Uint128::from(500u128), Uint128::from(1501u128)],
+            },
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        response.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("terra1leg0"),
+                msg: to_binary(&delta_neutral_position::ExecuteMsg::OpenPosition {
+                    params: legs[0].params.clone(),
+                })
+                .unwrap(),
+                funds: vec![Coin {
+                    denom: String::from("uusd"),
+                    amount: Uint128::from(500u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("terra1leg1"),
+                msg: to_binary(&delta_neutral_position::ExecuteMsg::OpenPosition {
+                    params: legs[1].params.clone(),
+                })
+                .unwrap(),
+                funds: vec![Coin {
+                    denom: String::from("uusd"),
+                    amount: Uint128::from(1501u128),
+                }],
+            })),
+        ]
+    );
+
+    // Each leg is indexed by mAsset and status.
+    let query_records = |deps: Deps, msg: QueryMsg| -> Vec<PositionRecordsResponseItem> {
+        from_binary::<PositionRecordsResponse>(&query(deps, mock_env(), msg).unwrap())
+            .unwrap()
+            .items
+    };
+    let leg_record = |leg_index: u32, mirror_asset: &str, status: PositionStatus| PositionRecord {
+        position: position.clone(),
+        diversified_leg_index: Some(leg_index),
+        mirror_asset_cw20_addr: Addr::unchecked(mirror_asset),
+        status,
+        open_uusd_amount: Uint128::from([500u128, 1501u128][leg_index as usize]),
+    };
+    assert_eq!(
+        query_records(
+            deps.as_ref(),
+            QueryMsg::GetPositionsByMirrorAsset {
+                mirror_asset_cw20_addr: legs[1].params.mirror_asset_cw20_addr.clone(),
+                start_after: None,
+                limit: None,
+            }
+        ),
+        vec![PositionRecordsResponseItem {
+            contract: Addr::unchecked("terra1leg1"),
+            record: leg_record(
+                1,
+                &legs[1].params.mirror_asset_cw20_addr,
+                PositionStatus::PendingDeltaNeutralSetup
+            ),
+        }]
+    );
+
+    // Leg contracts report status changes; reports from unknown contracts are ignored.
+    for sender in ["terra1leg0", "terra1unknown"] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::UpdatePositionStatus {
+                status: PositionStatus::Active,
+            },
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        query_records(
+            deps.as_ref(),
+            QueryMsg::GetPositionsByStatus {
+                status: PositionStatus::Active,
+                start_after: None,
+                limit: None,
+            }
+        ),
+        vec![PositionRecordsResponseItem {
+            contract: Addr::unchecked("terra1leg0"),
+            record: leg_record(
+                0,
+                &legs[0].params.mirror_asset_cw20_addr,
+                PositionStatus::Active
+            ),
+        }]
+    );
+    assert_eq!(
+        query_records(
+            deps.as_ref(),
+            QueryMsg::GetPositionsByStatus {
+                status: PositionStatus::PendingDeltaNeutralSetup,
+                start_after: None,
+                limit: None,
+            }
+        )
+        .into_iter()
+        .map(|item| item.contract)
+        .collect::<Vec<Addr>>(),
+        vec![Addr::unchecked("terra1leg1")]
+    );
+    assert!(query_records(
+        deps.as_ref(),
+        QueryMsg::GetPositionsByStatus {
+            status: PositionStatus::Active,
+            start_after: Some(String::from("terra1leg0")),
+            limit: None,
+        }
+    )
+    .is_empty());
+
+    // Deployed uusd is tracked against TVL caps until each leg is closed.
+    let query_tvl_utilization = |deps: Deps| -> TvlUtilizationResponse {
+        from_binary(&query(deps, mock_env(), QueryMsg::GetTvlUtilization {}).unwrap()).unwrap()
+    };
+    let mirror_asset_utilization =
+        |mirror_asset: &str, amount: u128, uusd_cap: Option<u128>| MirrorAssetTvlUtilization {
+            mirror_asset_cw20_addr: String::from(mirror_asset),
+            utilization: TvlUtilization {
+                deployed_uusd_amount: Uint128::from(amount),
+                uusd_cap: uusd_cap.map(Uint128::from),
+            },
+        };
+    assert_eq!(
+        query_tvl_utilization(deps.as_ref()),
+        TvlUtilizationResponse {
+            global: TvlUtilization {
+                deployed_uusd_amount: Uint128::from(2001u128),
+                uusd_cap: None,
+            },
+            mirror_assets: vec![
+                mirror_asset_utilization("mAAPL", 500, None),
+                mirror_asset_utilization("mTSLA", 1501, Some(2000)),
+            ],
+        }
+    );
+    for _ in 0..2 {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("terra1leg1", &[]),
+            ExecuteMsg::UpdatePositionStatus {
+                status: PositionStatus::Closed,
+            },
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        query_tvl_utilization(deps.as_ref()),
+        TvlUtilizationResponse {
+            global: TvlUtilization {
+                deployed_uusd_amount: Uint128::from(500u128),
+                uusd_cap: None,
+            },
+            mirror_assets: vec![
+                mirror_asset_utilization("mAAPL", 500, None),
+                mirror_asset_utilization("mTSLA", 0, Some(2000)),
+            ],
+        }
+    );
+
+    // Preemptively close CDPs on an mAsset in batches; closed positions are skipped.
+    let preemptively_close = |deps: DepsMut, mirror_asset: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::PreemptivelyCloseMirrorAssetCdps {
+                mirror_asset: String::from(mirror_asset),
+                limit: Some(1),
+            },
+        )
+        .unwrap()
+    };
+    let query_progress = |deps: Deps, mirror_asset: &str| -> Option<PreemptiveCloseProgress> {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::GetPreemptiveCloseProgress {
+                    mirror_asset: String::from(mirror_asset),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(query_progress(deps.as_ref(), "mAAPL"), None);
+    let response = preemptively_close(deps.as_mut(), "mAAPL");
+    assert_eq!(
+        response.messages,
+        vec![SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: String::from("terra1leg0"),
+                msg: to_binary(&delta_neutral_position::ExecuteMsg::PreemptivelyCloseCdp {})
+                    .unwrap(),
+                funds: vec![],
+            },
+            PREEMPTIVE_CLOSE_REPLY_ID_BASE,
+        )]
+    );
+    assert!(SHOULD_PREEMPTIVELY_CLOSE_CDP_MIRROR_ASSETS
+        .load(deps.as_ref().storage, Addr::unchecked("mAAPL"))
+        .unwrap());
+    reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: PREEMPTIVE_CLOSE_REPLY_ID_BASE,
+            result: ContractResult::Err(String::from("CDP not found")),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_progress(deps.as_ref(), "mAAPL"),
+        Some(PreemptiveCloseProgress {
+            cursor: Some(Addr::unchecked("terra1leg0")),
+            requested_count: 1,
+            skipped_count: 0,
+            failed_contracts: vec![Addr::unchecked("terra1leg0")],
+            done: true,
+        })
+    );
+    assert!(preemptively_close(deps.as_mut(), "mAAPL")
+        .messages
+        .is_empty());
+    assert!(preemptively_close(deps.as_mut(), "mTSLA")
+        .messages
+        .is_empty());
+    assert_eq!(
+        query_progress(deps.as_ref(), "mTSLA"),
+        Some(PreemptiveCloseProgress {
+            cursor: Some(Addr::unchecked("terra1leg1")),
+            requested_count: 0,
+            skipped_count: 1,
+            failed_contracts: vec![],
+            done: true,
+        })
+    );
+
+    // Close every leg.
+    let recipient = Recipient::TerraChain {
+        recipient: String::from("terra1recipient"),
+    };
+    let response = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("manager", &[]),
+        ExecuteMsg::PerformAction {
+            position: position.clone(),
+            action: Action::ClosePosition {
+                recipient: recipient.clone(),
+            },
+            assets: vec![],
+        },
+    )
+    .unwrap();
+    let close_msg =
+        to_binary(&delta_neutral_position::ExecuteMsg::ClosePosition { recipient }).unwrap();
+    assert_eq!(
+        response.messages,
+        expected_legs
+            .iter()
+            .map(|leg| SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: leg.contract.to_string(),
+                msg: close_msg.clone(),
+                funds: vec![],
+            })))
+            .collect::<Vec<SubMsg>>()
+    );
+
+    // Migrate every leg.
+    let response = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::MigratePositionContracts {
+            positions: vec![position.clone()],
+            position_contracts: vec![],
+        },
+    )
+    .unwrap();
+    assert_eq!(response.messages.len(), 2);
+    assert_eq!(
+        response.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: String::from("terra1leg1"),
+            new_code_id: 123,
+            msg: to_binary(&delta_neutral_position::MigrateMsg { new_code_id: 123 }).unwrap(),
+        })
+    );
+
+    // Cursor-based migration is restricted to the admin.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::MigratePositionContractsFromCursor { limit: Some(1) },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // Cursor-based migration moves past the (empty) standalone positions to the diversified position within the same batch.
+    let migrate_from_cursor = |deps: DepsMut| {
+        execute(
+            deps,
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::MigratePositionContractsFromCursor { limit: Some(1) },
+        )
+        .unwrap()
+    };
+    let query_migration_progress = |deps: Deps| -> Option<PositionMigrationProgress> {
+        from_binary(&query(deps, env.clone(), QueryMsg::GetPositionMigrationProgress {}).unwrap())
+            .unwrap()
+    };
+    assert_eq!(query_migration_progress(deps.as_ref()), None);
+    let response = migrate_from_cursor(deps.as_mut());
+    assert_eq!(
+        response.messages,
+        ["terra1leg0", "terra1leg1"]
+            .iter()
+            .enumerate()
+            .map(|(i, contract)| SubMsg::reply_on_error(
+                WasmMsg::Migrate {
+                    contract_addr: contract.to_string(),
+                    new_code_id: 123,
+                    msg: to_binary(&delta_neutral_position::MigrateMsg { new_code_id: 123 })
+                        .unwrap(),
+                },
+                POSITION_MIGRATION_REPLY_ID_BASE + i as u64,
+            ))
+            .collect::<Vec<SubMsg>>()
+    );
+    reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: POSITION_MIGRATION_REPLY_ID_BASE + 1,
+            result: ContractResult::Err(String::from("migration failed")),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_migration_progress(deps.as_ref()),
+        Some(PositionMigrationProgress {
+            new_code_id: 123,
+            processing_diversified_positions: true,
+            cursor: Some(position.clone()),
+            requested_count: 2,
+            skipped_count: 0,
+            failed_contracts: vec![Addr::unchecked("terra1leg1")],
+            done: false,
+        })
+    );
+
+    // The next batch finds no more positions.
+    assert!(migrate_from_cursor(deps.as_mut()).messages.is_empty());
+    let progress = query_migration_progress(deps.as_ref()).unwrap();
+    assert!(progress.done);
+    assert_eq!(progress.cursor, None);
+    assert_eq!(progress.requested_count, 2);
+
+    // Progress restarts once the position code id changes.
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        env.clone(),
+        "admin",
+        AdminOperation::UpdateAdminConfig {
+            admin_addr: None,
+            terra_manager_addr: None,
+            delta_neutral_position_code_id: Some(124),
+        },
+    )
+    .unwrap();
+    assert_eq!(migrate_from_cursor(deps.as_mut()).messages.len(), 2);
+    let progress = query_migration_progress(deps.as_ref()).unwrap();
+    assert_eq!(progress.new_code_id, 124);
+    assert!(progress.failed_contracts.is_empty());
+
+    // Contracts already on the current code id are skipped, and those whose code id cannot be queried are recorded as failed.
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        env.clone(),
+        "admin",
+        AdminOperation::UpdateAdminConfig {
+            admin_addr: None,
+            terra_manager_addr: None,
+            delta_neutral_position_code_id: Some(125),
+        },
+    )
+    .unwrap();
+    deps.querier
+        .position_code_ids
+        .insert(String::from("terra1leg0"), Some(125));
+    deps.querier.position_code_ids.remove("terra1leg1");
+    assert!(migrate_from_cursor(deps.as_mut()).messages.is_empty());
+    let progress = query_migration_progress(deps.as_ref()).unwrap();
+    assert_eq!(progress.requested_count, 0);
+    assert_eq!(progress.skipped_count, 1);
+    assert_eq!(
+        progress.failed_contracts,
+        vec![Addr::unchecked("terra1leg1")]
+    );
 }
@@ -1,4 +1,8 @@
 pub mod contract;
 pub mod state;
 
+mod apr_util;
 mod msg_instantiate_contract_response;
+
+#[cfg(test)]
+mod mock_querier;
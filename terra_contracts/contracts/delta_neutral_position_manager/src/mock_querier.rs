@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::testing::{mock_dependencies, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_slice, to_vec, Binary, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+
+// Serves raw queries for the code id stored by position contracts upon migration, and defers other queries to `MockQuerier`.
+// Contracts absent from `position_code_ids` do not exist; a `None` code id denotes a position contract that has never been migrated.
+pub struct WasmMockQuerier {
+    base: MockQuerier,
+    pub position_code_ids: HashMap<String, Option<u64>>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        match from_slice(bin_request) {
+            Ok(QueryRequest::<Empty>::Wasm(WasmQuery::Raw { contract_addr, key }))
+                if key.as_slice() == b"ci" =>
+            {
+                match self.position_code_ids.get(&contract_addr) {
+                    Some(Some(code_id)) => {
+                        SystemResult::Ok(ContractResult::Ok(Binary::from(to_vec(code_id).unwrap())))
+                    }
+                    Some(None) => SystemResult::Ok(ContractResult::Ok(Binary::default())),
+                    None => SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr,
+                    }),
+                }
+            }
+            _ => self.base.raw_query(bin_request),
+        }
+    }
+}
+
+pub fn mock_deps(
+    position_code_ids: &[(&str, Option<u64>)],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let deps = mock_dependencies(&[]);
+    OwnedDeps {
+        storage: deps.storage,
+        api: deps.api,
+        querier: WasmMockQuerier {
+            base: deps.querier,
+            position_code_ids: position_code_ids
+                .iter()
+                .map(|(contract, code_id)| (contract.to_string(), *code_id))
+                .collect(),
+        },
+    }
+}
@@ -1,14 +1,84 @@
 use aperture_common::{
     common::{Position, PositionKey},
-    delta_neutral_position_manager::{AdminConfig, Context, FeeCollectionConfig},
+    delta_neutral_position_manager::{
+        AdminConfig, AdminOperation, Context, DiversifiedPositionLeg, EmergencyCloseConfig,
+        FeeCollectionConfig, MirrorAssetAllowlistEntry, PositionMigrationProgress, PositionRecord,
+        PositionStatus, PreemptiveCloseProgress,
+    },
+    timelock::Timelock,
 };
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 pub const CONTEXT: Item<Context> = Item::new("context");
 pub const FEE_COLLECTION_CONFIG: Item<FeeCollectionConfig> = Item::new("fee_collection_config");
+// Read by position contracts through a raw query; `EmergencyCloseConfig::default()` applies if not set.
+pub const EMERGENCY_CLOSE_CONFIG: Item<EmergencyCloseConfig> = Item::new("ecc");
 pub const ADMIN_CONFIG: Item<AdminConfig> = Item::new("admin_config");
+// Admin nominated through `UpdateAdminConfig`, who takes over once they call `AcceptAdmin`.
+pub const PENDING_ADMIN: Item<Addr> = Item::new("pa");
+pub const TIMELOCK: Timelock<AdminOperation> = Timelock::new("tld", "tlnpi", "tlp");
 pub const TMP_POSITION: Item<Position> = Item::new("tmp_position");
 pub const POSITION_TO_CONTRACT_ADDR: Map<PositionKey, Addr> = Map::new("position_to_contract_addr");
-pub const POSITION_OPEN_ALLOWED_MIRROR_ASSETS: Map<String, bool> = Map::new("poama");
+// Position contracts read entries directly from storage; see `get_mirror_asset_strategy_params()` in the position contract.
+pub const POSITION_OPEN_ALLOWED_MIRROR_ASSETS: Map<String, MirrorAssetAllowlistEntry> =
+    Map::new("poamae");
+// Allowlist of the previous contract version, which is converted into POSITION_OPEN_ALLOWED_MIRROR_ASSETS upon migration.
+pub const LEGACY_POSITION_OPEN_ALLOWED_MIRROR_ASSETS: Map<String, bool> = Map::new("poama");
 pub const SHOULD_PREEMPTIVELY_CLOSE_CDP_MIRROR_ASSETS: Map<Addr, bool> = Map::new("spccma");
+// Legs of diversified positions; a diversified position has no entry in POSITION_TO_CONTRACT_ADDR.
+pub const DIVERSIFIED_POSITION_LEGS: Map<PositionKey, Vec<DiversifiedPositionLeg>> =
+    Map::new("dpl");
+// Weights of the legs being instantiated for TMP_POSITION, in order.
+pub const TMP_DIVERSIFIED_POSITION_LEG_WEIGHTS: Item<Vec<u32>> = Item::new("tdplw");
+// Cap on deployed uusd, i.e. uusd deposited into positions that are not yet closed. Uncapped if absent.
+// Per-mAsset caps are part of the strategy parameter overrides in POSITION_OPEN_ALLOWED_MIRROR_ASSETS.
+pub const GLOBAL_TVL_CAP: Item<Uint128> = Item::new("gtc");
+// Deployed uusd, tracked through `PositionRecord::open_uusd_amount` of the records in `position_records()`.
+pub const TOTAL_DEPLOYED_UUSD_AMOUNT: Item<Uint128> = Item::new("tdua");
+pub const MIRROR_ASSET_DEPLOYED_UUSD_AMOUNTS: Map<String, Uint128> = Map::new("madua");
+// Progress of `ExecuteMsg::PreemptivelyCloseMirrorAssetCdps` for each mAsset.
+pub const PREEMPTIVE_CLOSE_PROGRESS: Map<String, PreemptiveCloseProgress> = Map::new("pcp");
+// mAsset and position contracts of the latest preemptive close batch; a failed request is identified by the index of its contract in the batch.
+pub const TMP_PREEMPTIVE_CLOSE_BATCH: Item<(String, Vec<Addr>)> = Item::new("tpcb");
+// Progress of `ExecuteMsg::MigratePositionContractsFromCursor`.
+pub const POSITION_MIGRATION_PROGRESS: Item<PositionMigrationProgress> = Item::new("pmp");
+// Position contracts of the latest cursor-based migration batch; a failed migration is identified by the index of its contract in the batch.
+pub const TMP_POSITION_MIGRATION_BATCH: Item<Vec<Addr>> = Item::new("tpmb");
+
+pub struct PositionRecordIndexes<'a> {
+    pub mirror_asset: MultiIndex<'a, (Vec<u8>, Vec<u8>), PositionRecord>,
+    pub status: MultiIndex<'a, (Vec<u8>, Vec<u8>), PositionRecord>,
+}
+
+impl<'a> IndexList<PositionRecord> for PositionRecordIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PositionRecord>> + '_> {
+        let v: Vec<&dyn Index<PositionRecord>> = vec![&self.mirror_asset, &self.status];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn get_position_status_index_key(status: PositionStatus) -> Vec<u8> {
+    vec![status as u8]
+}
+
+// Position contracts keyed by contract address, indexed by mAsset and lifecycle status.
+// Each leg of a diversified position has its own record.
+pub fn position_records<'a>() -> IndexedMap<'a, &'a Addr, PositionRecord, PositionRecordIndexes<'a>>
+{
+    IndexedMap::new(
+        "pr",
+        PositionRecordIndexes {
+            mirror_asset: MultiIndex::new(
+                |record, pk| (record.mirror_asset_cw20_addr.as_bytes().to_vec(), pk),
+                "pr",
+                "pr__ma",
+            ),
+            status: MultiIndex::new(
+                |record, pk| (get_position_status_index_key(record.status), pk),
+                "pr",
+                "pr__s",
+            ),
+        },
+    )
+}
@@ -1,26 +1,44 @@
 use aperture_common::byte_util::ByteUtils;
 use aperture_common::common::{get_position_key, Position, PositionId, StrategyLocation};
+use aperture_common::cw721::AllNftInfoResponse;
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
-    Uint64,
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    Uint128, Uint64,
 };
 use cw_storage_plus::{Bound, PrimaryKey, U128Key};
 
 use crate::cross_chain::{
-    get_parsed_vaa, initiate_outgoing_token_transfer, process_cross_chain_instruction,
-    register_external_chain_manager,
+    compute_cross_chain_outgoing_fee, get_parsed_vaa, initiate_outgoing_token_transfer,
+    process_cross_chain_instruction, register_external_chain_manager,
+    update_cross_chain_outgoing_fee_schedule,
 };
+use crate::cw721::{
+    approve, approve_all, query_all_operators, query_all_tokens, query_approval, query_approvals,
+    query_contract_info, query_nft_info, query_num_tokens, query_owner_of, query_tokens, revoke,
+    revoke_all, send_nft, transfer_nft,
+};
+use crate::pause::{pause, query_paused_operations, query_pauser, unpause, update_pauser};
 use crate::state::{
     get_strategy_id_key, CrossChainOutgoingFeeConfig, ADMIN, COMPLETED_INSTRUCTIONS,
     CROSS_CHAIN_OUTGOING_FEE_CONFIG, HOLDER_POSITION_ID_PAIR_SET, NEXT_POSITION_ID,
-    NEXT_STRATEGY_ID, POSITION_ID_TO_HOLDER, POSITION_TO_STRATEGY_LOCATION_MAP,
-    STRATEGY_ID_TO_METADATA_MAP, WORMHOLE_CORE_BRIDGE_ADDR, WORMHOLE_TOKEN_BRIDGE_ADDR,
+    NEXT_STRATEGY_ID, PENDING_ADMIN_ADDR, POSITION_ID_TO_HOLDER,
+    POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT, POSITION_TO_STRATEGY_LOCATION_MAP,
+    STRATEGY_ID_TO_METADATA_MAP, STRATEGY_POSITION_INDEX_PROGRESS, TIMELOCK,
+    WORMHOLE_CORE_BRIDGE_ADDR, WORMHOLE_TOKEN_BRIDGE_ADDR,
+};
+use crate::terra_chain::{
+    accept_position_transfer, add_strategy, cancel_position_transfer, create_position,
+    execute_strategy, get_strategy_status, grant_position_operator,
+    index_existing_strategy_positions, query_position_operator_grant, query_strategies,
+    query_strategy_position_count, query_strategy_positions, remove_strategy,
+    revoke_position_operator, transfer_position, update_strategy,
 };
-use crate::terra_chain::{add_strategy, create_position, execute_strategy, remove_strategy};
 use aperture_common::terra_manager::{
-    ExecuteMsg, InstantiateMsg, MigrateMsg, NextPositionIdResponse, PositionInfoResponse,
-    PositionsResponse, QueryMsg, TERRA_CHAIN_ID,
+    AdminOperation, ExecuteMsg, InstantiateMsg, MigrateMsg, NextPositionIdResponse,
+    PositionInfoResponse, PositionsResponse, QueryMsg, StrategyPositionIndexProgress,
+    TERRA_CHAIN_ID,
 };
+use aperture_common::timelock::assert_valid_timelock_delay;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -48,25 +66,87 @@ pub fn instantiate(
                 .addr_validate(&msg.cross_chain_outgoing_fee_collector_addr)?,
         },
     )?;
+    TIMELOCK.set_delay_seconds(deps.storage, msg.timelock_delay_seconds)?;
     NEXT_STRATEGY_ID.save(deps.storage, &Uint64::zero())?;
     NEXT_POSITION_ID.save(deps.storage, &Uint128::zero())?;
+    // There are no existing positions to index.
+    STRATEGY_POSITION_INDEX_PROGRESS.save(
+        deps.storage,
+        &StrategyPositionIndexProgress {
+            done: true,
+            ..StrategyPositionIndexProgress::default()
+        },
+    )?;
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
-        ExecuteMsg::UpdateAdminAddr { new_admin_addr } => update_admin(deps, info, new_admin_addr),
-        ExecuteMsg::AddStrategy {
-            name,
-            version,
-            manager_addr,
-        } => add_strategy(deps, info, name, version, manager_addr),
-        ExecuteMsg::RemoveStrategy { strategy_id } => remove_strategy(deps, info, strategy_id),
-        ExecuteMsg::RegisterExternalChainManager {
-            chain_id,
-            aperture_manager_addr,
-        } => register_external_chain_manager(deps, info, chain_id, aperture_manager_addr),
+        // Sensitive administrative operations can only be performed through `ProposeAdminOperation` and `ExecuteAdminOperation`.
+        ExecuteMsg::UpdateAdminAddr { .. }
+        | ExecuteMsg::AddStrategy { .. }
+        | ExecuteMsg::RemoveStrategy { .. }
+        | ExecuteMsg::UpdateStrategy { .. }
+        | ExecuteMsg::RegisterExternalChainManager { .. }
+        | ExecuteMsg::UpdateCrossChainOutgoingFeeSchedule { .. } => Err(StdError::generic_err(
+            "operation is subject to timelock and must be proposed",
+        )),
+        ExecuteMsg::AcceptAdmin {} => accept_admin(deps, info),
+        ExecuteMsg::IndexExistingStrategyPositions { limit } => {
+            index_existing_strategy_positions(deps, info, limit)
+        }
+        ExecuteMsg::UpdatePauser { pauser_addr } => update_pauser(deps, info, pauser_addr),
+        ExecuteMsg::Pause { scope, operations } => pause(deps, info, scope, operations),
+        ExecuteMsg::Unpause { scope, operations } => unpause(deps, info, scope, operations),
+        ExecuteMsg::ProposeAdminOperation { operation } => {
+            propose_admin_operation(deps, env, info, operation)
+        }
+        ExecuteMsg::ExecuteAdminOperation { proposal_id } => {
+            execute_admin_operation(deps, env, info, proposal_id)
+        }
+        ExecuteMsg::CancelAdminOperation { proposal_id } => {
+            ADMIN
+                .assert_admin(deps.as_ref(), &info.sender)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            TIMELOCK.cancel_proposal(deps.storage, proposal_id)?;
+            Ok(Response::default())
+        }
+        ExecuteMsg::TransferPosition {
+            position_id,
+            recipient,
+            require_acceptance,
+        } => transfer_position(
+            deps,
+            info,
+            position_id,
+            recipient,
+            require_acceptance.unwrap_or(false),
+        ),
+        ExecuteMsg::AcceptPositionTransfer { position_id } => {
+            accept_position_transfer(deps, info, position_id)
+        }
+        ExecuteMsg::CancelPositionTransfer { position_id } => {
+            cancel_position_transfer(deps, info, position_id)
+        }
+        ExecuteMsg::GrantPositionOperator {
+            operator,
+            position_id,
+            allowed_actions,
+            expires,
+        } => grant_position_operator(
+            deps,
+            env,
+            info,
+            operator,
+            position_id,
+            allowed_actions,
+            expires,
+        ),
+        ExecuteMsg::RevokePositionOperator {
+            operator,
+            position_id,
+        } => revoke_position_operator(deps, info, operator, position_id),
         ExecuteMsg::CreatePosition {
             strategy,
             data,
@@ -84,14 +164,102 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::InitiateOutgoingTokenTransfer { assets, recipient } => {
             initiate_outgoing_token_transfer(deps.as_ref(), env, info, assets, recipient)
         }
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => transfer_nft(deps, env, info, recipient, token_id),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => send_nft(deps, env, info, contract, token_id, msg),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => approve(deps, env, info, spender, token_id, expires),
+        ExecuteMsg::Revoke { spender, token_id } => revoke(deps, env, info, spender, token_id),
+        ExecuteMsg::ApproveAll { operator, expires } => {
+            approve_all(deps, env, info, operator, expires)
+        }
+        ExecuteMsg::RevokeAll { operator } => revoke_all(deps, info, operator),
     }
 }
 
 fn update_admin(deps: DepsMut, info: MessageInfo, new_admin_addr: String) -> StdResult<Response> {
+    ADMIN
+        .assert_admin(deps.as_ref(), &info.sender)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
     let new_admin = deps.api.addr_validate(&new_admin_addr)?;
-    Ok(ADMIN
-        .execute_update_admin(deps, info, Some(new_admin))
-        .unwrap())
+    PENDING_ADMIN_ADDR.save(deps.storage, &new_admin)?;
+    Ok(Response::new().add_attribute("pending_admin", new_admin))
+}
+
+fn accept_admin(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    if PENDING_ADMIN_ADDR.may_load(deps.storage)? != Some(info.sender.clone()) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    PENDING_ADMIN_ADDR.remove(deps.storage);
+    ADMIN.set(deps, Some(info.sender.clone()))?;
+    Ok(Response::new().add_attribute("admin", info.sender))
+}
+
+fn propose_admin_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operation: AdminOperation,
+) -> StdResult<Response> {
+    ADMIN
+        .assert_admin(deps.as_ref(), &info.sender)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    if let AdminOperation::UpdateTimelockDelay { delay_seconds } = operation {
+        assert_valid_timelock_delay(delay_seconds)?;
+    }
+    let proposal = TIMELOCK.propose(deps.storage, &env, operation)?;
+    Ok(Response::new()
+        .add_attribute("proposal_id", proposal.proposal_id.to_string())
+        .add_attribute("executable_after", proposal.executable_after.to_string()))
+}
+
+fn execute_admin_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> StdResult<Response> {
+    ADMIN
+        .assert_admin(deps.as_ref(), &info.sender)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let proposal = TIMELOCK.take_executable_proposal(deps.storage, &env, proposal_id)?;
+    match proposal.operation {
+        AdminOperation::UpdateAdminAddr { new_admin_addr } => {
+            update_admin(deps, info, new_admin_addr)
+        }
+        AdminOperation::AddStrategy {
+            name,
+            version,
+            manager_addr,
+        } => add_strategy(deps, info, name, version, manager_addr),
+        AdminOperation::RemoveStrategy { strategy_id } => remove_strategy(deps, info, strategy_id),
+        AdminOperation::UpdateStrategy {
+            strategy_id,
+            version,
+            manager_addr,
+            status,
+        } => update_strategy(deps, info, strategy_id, version, manager_addr, status),
+        AdminOperation::RegisterExternalChainManager {
+            chain_id,
+            aperture_manager_addr,
+        } => register_external_chain_manager(deps, info, chain_id, aperture_manager_addr),
+        AdminOperation::UpdateCrossChainOutgoingFeeSchedule { update } => {
+            update_cross_chain_outgoing_fee_schedule(deps, info, update)
+        }
+        AdminOperation::UpdateTimelockDelay { delay_seconds } => {
+            TIMELOCK.set_delay_seconds(deps.storage, delay_seconds)?;
+            Ok(Response::default())
+        }
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -100,6 +268,37 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetStrategyMetadata { strategy_id } => to_binary(
             &STRATEGY_ID_TO_METADATA_MAP.load(deps.storage, get_strategy_id_key(strategy_id))?,
         ),
+        QueryMsg::GetStrategyStatus { strategy_id } => {
+            to_binary(&get_strategy_status(deps, strategy_id)?)
+        }
+        QueryMsg::GetTimelockDelay {} => to_binary(&TIMELOCK.get_delay_seconds(deps.storage)?),
+        QueryMsg::GetAdminOperationProposals { start_after, limit } => {
+            to_binary(&TIMELOCK.query_proposals(deps.storage, start_after, limit)?)
+        }
+        QueryMsg::GetPendingAdmin {} => to_binary(&PENDING_ADMIN_ADDR.may_load(deps.storage)?),
+        QueryMsg::GetPauser {} => to_binary(&query_pauser(deps)?),
+        QueryMsg::GetPausedOperations { scope } => {
+            to_binary(&query_paused_operations(deps, scope)?)
+        }
+        QueryMsg::ListStrategies { start_after, limit } => {
+            to_binary(&query_strategies(deps, start_after, limit)?)
+        }
+        QueryMsg::GetStrategyPositions {
+            strategy_id,
+            start_after,
+            limit,
+        } => to_binary(&query_strategy_positions(
+            deps,
+            strategy_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GetStrategyPositionCount { strategy_id } => {
+            to_binary(&query_strategy_position_count(deps, strategy_id)?)
+        }
+        QueryMsg::GetStrategyPositionIndexProgress {} => {
+            to_binary(&STRATEGY_POSITION_INDEX_PROGRESS.load(deps.storage)?)
+        }
         QueryMsg::GetNextPositionId {} => to_binary(&NextPositionIdResponse {
             next_position_id: NEXT_POSITION_ID.load(deps.storage)?,
         }),
@@ -115,6 +314,20 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 }),
             )?,
         }),
+        QueryMsg::GetPendingPositionTransfer { position_id } => to_binary(
+            &POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT
+                .may_load(deps.storage, U128Key::from(position_id.u128()))?,
+        ),
+        QueryMsg::GetPositionOperatorGrant {
+            holder,
+            operator,
+            position_id,
+        } => to_binary(&query_position_operator_grant(
+            deps,
+            holder,
+            operator,
+            position_id,
+        )?),
         QueryMsg::GetTerraPositionsByHolder {
             holder,
             start_after,
@@ -163,10 +376,94 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                     .unwrap_or(false),
             )
         }
+        QueryMsg::PreviewCrossChainOutgoingFee {
+            sender,
+            recipient_chain_id,
+            asset,
+        } => to_binary(&compute_cross_chain_outgoing_fee(
+            deps,
+            &deps.api.addr_validate(&sender)?,
+            recipient_chain_id,
+            &asset,
+        )?),
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => to_binary(&query_owner_of(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => to_binary(&query_approval(
+            deps,
+            env,
+            token_id,
+            spender,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => to_binary(&query_approvals(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => to_binary(&query_all_operators(
+            deps,
+            env,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::NumTokens {} => to_binary(&query_num_tokens(deps)?),
+        QueryMsg::ContractInfo {} => to_binary(&query_contract_info()),
+        QueryMsg::NftInfo { token_id } => to_binary(&query_nft_info(deps, token_id)?),
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => to_binary(&AllNftInfoResponse {
+            access: query_owner_of(
+                deps,
+                env,
+                token_id.clone(),
+                include_expired.unwrap_or(false),
+            )?,
+            info: query_nft_info(deps, token_id)?,
+        }),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_tokens(deps, owner, start_after, limit)?),
+        QueryMsg::AllTokens { start_after, limit } => {
+            to_binary(&query_all_tokens(deps, start_after, limit)?)
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    TIMELOCK.set_delay_seconds(deps.storage, msg.timelock_delay_seconds)?;
+    // Positions opened before the strategy -> position index was introduced are indexed afterwards through `IndexExistingStrategyPositions`.
+    if STRATEGY_POSITION_INDEX_PROGRESS
+        .may_load(deps.storage)?
+        .is_none()
+    {
+        STRATEGY_POSITION_INDEX_PROGRESS
+            .save(deps.storage, &StrategyPositionIndexProgress::default())?;
+    }
     Ok(Response::default())
 }
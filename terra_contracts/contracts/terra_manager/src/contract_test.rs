@@ -1,17 +1,61 @@
-use crate::contract::{execute, instantiate, query};
-use crate::state::{NEXT_STRATEGY_ID, POSITION_TO_STRATEGY_LOCATION_MAP};
-use aperture_common::terra_manager::{ExecuteMsg, InstantiateMsg, QueryMsg, TERRA_CHAIN_ID};
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::state::{
+    NEXT_STRATEGY_ID, POSITION_TO_STRATEGY_LOCATION_MAP, STRATEGY_ID_TO_POSITION_COUNT_MAP,
+    STRATEGY_POSITION_INDEX_PROGRESS, STRATEGY_POSITION_SET, TIMELOCK,
+};
+use crate::terra_chain::save_new_position_info_and_open_it;
+use aperture_common::terra_manager::{
+    AdminOperation, CrossChainOutgoingFeeScheduleUpdate, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    PausableOperation, PauseScope, PositionInfoResponse, PositionsResponse, QueryMsg,
+    StrategiesResponse, StrategyInfo, StrategyPositionIndexProgress, StrategyPositionInfo,
+    StrategyPositionsResponse, StrategyStatus, TERRA_CHAIN_ID,
+};
 
 use aperture_common::common::{
     get_position_key, Action, Position, Recipient, Strategy, StrategyLocation, StrategyMetadata,
     StrategyPositionManagerExecuteMsg,
 };
 use aperture_common::delta_neutral_position_manager::DeltaNeutralParams;
+use aperture_common::timelock::{AdminOperationProposal, MIN_TIMELOCK_DELAY_SECONDS};
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, CosmosMsg, Decimal, ReplyOn, StdError, SubMsg, Uint128, Uint64,
-    WasmMsg,
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, DepsMut, Env, Event, ReplyOn,
+    Response, StdError, StdResult, SubMsg, Uint128, Uint64, WasmMsg,
 };
+use cw_storage_plus::U64Key;
+
+// Proposes `operation` on behalf of `sender`, and executes it once the timelock delay has elapsed.
+fn propose_and_execute_admin_operation(
+    mut deps: DepsMut,
+    sender: &str,
+    operation: AdminOperation,
+) -> StdResult<Response> {
+    let response = execute(
+        deps.branch(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::ProposeAdminOperation { operation },
+    )?;
+    let proposal_id = response
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "proposal_id")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+    let mut env = mock_env();
+    env.block.time = env
+        .block
+        .time
+        .plus_seconds(TIMELOCK.get_delay_seconds(deps.storage)?);
+    execute(
+        deps,
+        env,
+        mock_info(sender, &[]),
+        ExecuteMsg::ExecuteAdminOperation { proposal_id },
+    )
+}
 
 #[test]
 fn test_initialization() {
@@ -25,6 +69,7 @@ fn test_initialization() {
         wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
         cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
         cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+        timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
     };
     let init_response = instantiate(
         deps.as_mut(),
@@ -49,11 +94,12 @@ fn test_manipuate_strategy() {
             wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
             cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
             cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
         },
     )
     .unwrap();
 
-    let msg = ExecuteMsg::AddStrategy {
+    let operation = AdminOperation::AddStrategy {
         name: "test_strat".to_string(),
         version: "1.0.1".to_string(),
         manager_addr: "terra1ads6zkvpq0dvy99hzj6dmk0peevzkxvvufd76g".to_string(),
@@ -65,12 +111,7 @@ fn test_manipuate_strategy() {
         Uint64::from(0u64)
     );
 
-    let _res = execute(
-        deps.as_mut(),
-        mock_env(),
-        mock_info(MOCK_CONTRACT_ADDR, &[]),
-        msg,
-    );
+    let _res = propose_and_execute_admin_operation(deps.as_mut(), MOCK_CONTRACT_ADDR, operation);
     // Position id should be 1 now.
     assert_eq!(
         NEXT_STRATEGY_ID.load(deps.as_mut().storage).unwrap(),
@@ -98,11 +139,10 @@ fn test_manipuate_strategy() {
     );
 
     // Now, we remove the strategy.
-    let _remove_res = execute(
+    let _remove_res = propose_and_execute_admin_operation(
         deps.as_mut(),
-        mock_env(),
-        mock_info(MOCK_CONTRACT_ADDR, &[]),
-        ExecuteMsg::RemoveStrategy {
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::RemoveStrategy {
             strategy_id: Uint64::from(0u64),
         },
     )
@@ -114,11 +154,41 @@ fn test_manipuate_strategy() {
         Uint64::from(1u64)
     );
 
+    // Removal deprecates the strategy but keeps its metadata so that existing positions can be closed.
+    assert_eq!(
+        from_binary::<StrategyStatus>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetStrategyStatus {
+                    strategy_id: Uint64::from(0u64),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StrategyStatus::Deprecated
+    );
+    assert_eq!(
+        from_binary::<StrategyMetadata>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetStrategyMetadata {
+                    strategy_id: Uint64::from(0u64),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        parsed_query_response
+    );
+
     let bad_query_response = query(
         deps.as_ref(),
         mock_env(),
         QueryMsg::GetStrategyMetadata {
-            strategy_id: Uint64::from(0u64),
+            strategy_id: Uint64::from(1u64),
         },
     );
     assert!(
@@ -128,9 +198,9 @@ fn test_manipuate_strategy() {
 }
 
 #[test]
-fn test_create_position() {
+fn test_strategy_lifecycle() {
     let mut deps = mock_dependencies(&[]);
-    let _res = instantiate(
+    instantiate(
         deps.as_mut(),
         mock_env(),
         mock_info(MOCK_CONTRACT_ADDR, &[]),
@@ -140,15 +210,465 @@ fn test_create_position() {
             wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
             cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
             cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::AddStrategy {
+            name: "test_strat".to_string(),
+            version: "1.0.1".to_string(),
+            manager_addr: "strategy_manager".to_string(),
+        },
+    )
+    .unwrap();
+    let create_position = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::CreatePosition {
+                strategy: Strategy {
+                    chain_id: TERRA_CHAIN_ID,
+                    strategy_id: Uint64::zero(),
+                },
+                data: None,
+                assets: vec![],
+            },
+        )
+    };
+    let execute_action = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, action: Action| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::ExecuteStrategy {
+                position_id: Uint128::zero(),
+                action,
+                assets: vec![],
+            },
+        )
+    };
+    let update_status = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, status: StrategyStatus| {
+        propose_and_execute_admin_operation(
+            deps.as_mut(),
+            MOCK_CONTRACT_ADDR,
+            AdminOperation::UpdateStrategy {
+                strategy_id: Uint64::zero(),
+                version: None,
+                manager_addr: None,
+                status: Some(status),
+            },
+        )
+        .unwrap();
+    };
+    let recipient = Recipient::TerraChain {
+        recipient: String::from("alice"),
+    };
+    let not_allowed = StdError::generic_err("action is not allowed in the current strategy status");
+    create_position(&mut deps).unwrap();
+
+    // Only the administrator can update a strategy.
+    assert_eq!(
+        propose_and_execute_admin_operation(
+            deps.as_mut(),
+            "alice",
+            AdminOperation::UpdateStrategy {
+                strategy_id: Uint64::zero(),
+                version: None,
+                manager_addr: None,
+                status: Some(StrategyStatus::Paused),
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("Caller is not admin")
+    );
+
+    // Deprecated: no new positions or increases, but decrease and close are allowed.
+    update_status(&mut deps, StrategyStatus::Deprecated);
+    assert_eq!(create_position(&mut deps).unwrap_err(), not_allowed);
+    assert_eq!(
+        execute_action(&mut deps, Action::IncreasePosition { data: None }).unwrap_err(),
+        not_allowed
+    );
+    execute_action(
+        &mut deps,
+        Action::DecreasePosition {
+            proportion: Decimal::percent(50),
+            recipient: recipient.clone(),
+        },
+    )
+    .unwrap();
+    execute_action(
+        &mut deps,
+        Action::ClosePosition {
+            recipient: recipient.clone(),
+        },
+    )
+    .unwrap();
+
+    // Paused: only emergency close is allowed.
+    update_status(&mut deps, StrategyStatus::Paused);
+    assert_eq!(
+        execute_action(
+            &mut deps,
+            Action::ClosePosition {
+                recipient: recipient.clone(),
+            },
+        )
+        .unwrap_err(),
+        not_allowed
+    );
+    execute_action(
+        &mut deps,
+        Action::EmergencyClosePosition {
+            recipient: recipient.clone(),
+        },
+    )
+    .unwrap();
+
+    // Reactivate the strategy with a redeployed manager; actions are routed to the new manager.
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::UpdateStrategy {
+            strategy_id: Uint64::zero(),
+            version: Some("1.0.2".to_string()),
+            manager_addr: Some("new_strategy_manager".to_string()),
+            status: Some(StrategyStatus::Active),
         },
     )
     .unwrap();
+    assert_eq!(
+        from_binary::<StrategyMetadata>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetStrategyMetadata {
+                    strategy_id: Uint64::zero(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        StrategyMetadata {
+            name: "test_strat".to_string(),
+            version: "1.0.2".to_string(),
+            manager_addr: Addr::unchecked("new_strategy_manager"),
+        }
+    );
+    let response = execute_action(&mut deps, Action::IncreasePosition { data: None }).unwrap();
+    assert_eq!(
+        response.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "new_strategy_manager".to_string(),
+            msg: to_binary(&StrategyPositionManagerExecuteMsg::PerformAction {
+                position: Position {
+                    chain_id: TERRA_CHAIN_ID,
+                    position_id: Uint128::zero(),
+                },
+                action: Action::IncreasePosition { data: None },
+                assets: vec![],
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    create_position(&mut deps).unwrap();
+}
 
-    let _res = execute(
+#[test]
+fn test_list_strategies_and_strategy_positions() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
         deps.as_mut(),
         mock_env(),
         mock_info(MOCK_CONTRACT_ADDR, &[]),
-        ExecuteMsg::AddStrategy {
+        InstantiateMsg {
+            admin_addr: MOCK_CONTRACT_ADDR.to_string(),
+            wormhole_core_bridge_addr: String::from("mock_wormhole_core_bridge"),
+            wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
+            cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
+            cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    for name in ["strat_a", "strat_b"] {
+        propose_and_execute_admin_operation(
+            deps.as_mut(),
+            MOCK_CONTRACT_ADDR,
+            AdminOperation::AddStrategy {
+                name: name.to_string(),
+                version: "1.0.0".to_string(),
+                manager_addr: format!("{}_manager", name),
+            },
+        )
+        .unwrap();
+    }
+    for (holder, strategy_id) in [("alice", 0u64), ("bob", 1u64), ("alice", 0u64)] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(holder, &[]),
+            ExecuteMsg::CreatePosition {
+                strategy: Strategy {
+                    chain_id: TERRA_CHAIN_ID,
+                    strategy_id: Uint64::from(strategy_id),
+                },
+                data: None,
+                assets: vec![],
+            },
+        )
+        .unwrap();
+    }
+    // A position held on another chain, opened via a cross-chain instruction.
+    let external_position = Position {
+        chain_id: 5,
+        position_id: Uint128::from(7u128),
+    };
+    save_new_position_info_and_open_it(
+        deps.as_mut(),
+        mock_env(),
+        None,
+        external_position.clone(),
+        Uint64::zero(),
+        None,
+        vec![],
+    )
+    .unwrap();
+
+    let list_strategies =
+        |deps: &cosmwasm_std::OwnedDeps<_, _, _>, start_after: Option<u64>, limit: Option<u32>| {
+            from_binary::<StrategiesResponse>(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::ListStrategies {
+                        start_after: start_after.map(Uint64::from),
+                        limit,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+            .strategies
+        };
+    let strategies = list_strategies(&deps, None, None);
+    assert_eq!(strategies.len(), 2);
+    assert_eq!(
+        strategies[0],
+        StrategyInfo {
+            strategy_id: Uint64::zero(),
+            metadata: StrategyMetadata {
+                name: "strat_a".to_string(),
+                version: "1.0.0".to_string(),
+                manager_addr: Addr::unchecked("strat_a_manager"),
+            },
+            status: StrategyStatus::Active,
+            position_count: Uint64::from(3u64),
+        }
+    );
+    assert_eq!(strategies[1].position_count, Uint64::from(1u64));
+    let strategies = list_strategies(&deps, Some(0), Some(1));
+    assert_eq!(strategies.len(), 1);
+    assert_eq!(strategies[0].strategy_id, Uint64::from(1u64));
+    assert!(list_strategies(&deps, Some(1), None).is_empty());
+
+    let terra_position = |position_id: u128| Position {
+        chain_id: TERRA_CHAIN_ID,
+        position_id: Uint128::from(position_id),
+    };
+    let strategy_positions = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                              start_after: Option<Position>,
+                              limit: Option<u32>| {
+        from_binary::<StrategyPositionsResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetStrategyPositions {
+                    strategy_id: Uint64::zero(),
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .positions
+    };
+    let expected_positions = vec![
+        StrategyPositionInfo {
+            position: terra_position(0),
+            holder: Some("alice".to_string()),
+        },
+        StrategyPositionInfo {
+            position: terra_position(2),
+            holder: Some("alice".to_string()),
+        },
+        StrategyPositionInfo {
+            position: external_position.clone(),
+            holder: None,
+        },
+    ];
+    assert_eq!(strategy_positions(&deps, None, None), expected_positions);
+    assert_eq!(
+        strategy_positions(&deps, Some(terra_position(0)), Some(1)),
+        expected_positions[1..2].to_vec()
+    );
+    assert!(query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::GetStrategyPositions {
+            strategy_id: Uint64::from(2u64),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .is_err());
+
+    let position_count = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, strategy_id: u64| {
+        from_binary::<Uint64>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetStrategyPositionCount {
+                    strategy_id: Uint64::from(strategy_id),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(position_count(&deps, 0), Uint64::from(3u64));
+    assert_eq!(position_count(&deps, 1), Uint64::from(1u64));
+
+    let index_progress = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        from_binary::<StrategyPositionIndexProgress>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetStrategyPositionIndexProgress {},
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let index_existing_positions =
+        |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, sender: &str, limit: Option<u32>| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::IndexExistingStrategyPositions { limit },
+            )
+        };
+    // A newly instantiated contract has no positions to index.
+    assert_eq!(
+        index_progress(&deps),
+        StrategyPositionIndexProgress {
+            cursor: None,
+            indexed_count: 0,
+            done: true,
+        }
+    );
+
+    // Simulate positions opened before the index existed, which are indexed in batches after migration.
+    for position in [terra_position(2), external_position.clone()] {
+        STRATEGY_POSITION_SET.remove(
+            deps.as_mut().storage,
+            (U64Key::from(0u64), get_position_key(&position)),
+        );
+    }
+    STRATEGY_ID_TO_POSITION_COUNT_MAP
+        .save(deps.as_mut().storage, U64Key::from(0u64), &1u64)
+        .unwrap();
+    STRATEGY_POSITION_INDEX_PROGRESS.remove(deps.as_mut().storage);
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        index_progress(&deps),
+        StrategyPositionIndexProgress::default()
+    );
+    assert_eq!(
+        index_existing_positions(&mut deps, "alice", None).unwrap_err(),
+        StdError::generic_err("Caller is not admin")
+    );
+
+    // Positions 0 and 1 were indexed upon creation.
+    index_existing_positions(&mut deps, MOCK_CONTRACT_ADDR, Some(2)).unwrap();
+    assert_eq!(
+        index_progress(&deps),
+        StrategyPositionIndexProgress {
+            cursor: Some(terra_position(1)),
+            indexed_count: 0,
+            done: false,
+        }
+    );
+    index_existing_positions(&mut deps, MOCK_CONTRACT_ADDR, Some(2)).unwrap();
+    assert_eq!(
+        index_progress(&deps),
+        StrategyPositionIndexProgress {
+            cursor: Some(external_position.clone()),
+            indexed_count: 2,
+            done: false,
+        }
+    );
+    index_existing_positions(&mut deps, MOCK_CONTRACT_ADDR, Some(2)).unwrap();
+    let done_progress = StrategyPositionIndexProgress {
+        cursor: Some(external_position),
+        indexed_count: 2,
+        done: true,
+    };
+    assert_eq!(index_progress(&deps), done_progress);
+    assert_eq!(strategy_positions(&deps, None, None), expected_positions);
+    assert_eq!(position_count(&deps, 0), Uint64::from(3u64));
+    assert_eq!(position_count(&deps, 1), Uint64::from(1u64));
+
+    // Further calls and migrations leave the completed index as is.
+    index_existing_positions(&mut deps, MOCK_CONTRACT_ADDR, None).unwrap();
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    assert_eq!(index_progress(&deps), done_progress);
+    assert_eq!(position_count(&deps, 0), Uint64::from(3u64));
+}
+
+#[test]
+fn test_create_position() {
+    let mut deps = mock_dependencies(&[]);
+    let _res = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        InstantiateMsg {
+            admin_addr: MOCK_CONTRACT_ADDR.to_string(),
+            wormhole_core_bridge_addr: String::from("mock_wormhole_core_bridge"),
+            wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
+            cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
+            cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+
+    let _res = propose_and_execute_admin_operation(
+        deps.as_mut(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::AddStrategy {
             name: "test_strat".to_string(),
             version: "1.0.1".to_string(),
             manager_addr: "terra1ads6zkvpq0dvy99hzj6dmk0peevzkxvvufd76g".to_string(),
@@ -160,6 +680,9 @@ fn test_create_position() {
         target_max_collateral_ratio: Decimal::one(),
         mirror_asset_cw20_addr: MOCK_CONTRACT_ADDR.to_string(),
         allow_off_market_position_open: None,
+        liquidity_venue: None,
+        collateral_asset_info: None,
+        spec_reward_policy: None,
     })
     .unwrap();
 
@@ -304,3 +827,1377 @@ fn test_query_strategy_location_by_position() {
     )
     .is_err());
 }
+
+#[test]
+fn test_transfer_position() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        InstantiateMsg {
+            admin_addr: MOCK_CONTRACT_ADDR.to_string(),
+            wormhole_core_bridge_addr: String::from("mock_wormhole_core_bridge"),
+            wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
+            cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
+            cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::AddStrategy {
+            name: "test_strat".to_string(),
+            version: "1.0.1".to_string(),
+            manager_addr: "strategy_manager".to_string(),
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::CreatePosition {
+            strategy: Strategy {
+                chain_id: TERRA_CHAIN_ID,
+                strategy_id: Uint64::zero(),
+            },
+            data: None,
+            assets: vec![],
+        },
+    )
+    .unwrap();
+    let get_holder = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> String {
+        from_binary::<PositionInfoResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetTerraPositionInfo {
+                    position_id: Uint128::zero(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .holder
+    };
+    let get_position_ids = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, holder: &str| {
+        from_binary::<PositionsResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetTerraPositionsByHolder {
+                    holder: holder.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .position_id_vec
+    };
+
+    // Only the holder can transfer the position.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::TransferPosition {
+                position_id: Uint128::zero(),
+                recipient: String::from("bob"),
+                require_acceptance: None,
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // Direct transfer from alice to bob.
+    let response = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::TransferPosition {
+            position_id: Uint128::zero(),
+            recipient: String::from("bob"),
+            require_acceptance: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        response.events,
+        vec![Event::new("position_transfer")
+            .add_attribute("position_id", "0")
+            .add_attribute("from", "alice")
+            .add_attribute("to", "bob")]
+    );
+    assert_eq!(get_holder(&deps), "bob");
+    assert_eq!(get_position_ids(&deps, "alice"), vec![]);
+    assert_eq!(get_position_ids(&deps, "bob"), vec![Uint128::zero()]);
+
+    // Two-step transfer from bob to carol; bob remains the holder until carol accepts.
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::TransferPosition {
+            position_id: Uint128::zero(),
+            recipient: String::from("carol"),
+            require_acceptance: Some(true),
+        },
+    )
+    .unwrap();
+    assert_eq!(get_holder(&deps), "bob");
+    assert_eq!(
+        from_binary::<Option<Addr>>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetPendingPositionTransfer {
+                    position_id: Uint128::zero(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        Some(Addr::unchecked("carol"))
+    );
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::AcceptPositionTransfer {
+                position_id: Uint128::zero(),
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // Cancelled transfers can no longer be accepted.
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::CancelPositionTransfer {
+            position_id: Uint128::zero(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("carol", &[]),
+            ExecuteMsg::AcceptPositionTransfer {
+                position_id: Uint128::zero(),
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::TransferPosition {
+            position_id: Uint128::zero(),
+            recipient: String::from("carol"),
+            require_acceptance: Some(true),
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("carol", &[]),
+        ExecuteMsg::AcceptPositionTransfer {
+            position_id: Uint128::zero(),
+        },
+    )
+    .unwrap();
+    assert_eq!(get_holder(&deps), "carol");
+    assert_eq!(get_position_ids(&deps, "bob"), vec![]);
+    assert_eq!(get_position_ids(&deps, "carol"), vec![Uint128::zero()]);
+    assert_eq!(
+        from_binary::<Option<Addr>>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetPendingPositionTransfer {
+                    position_id: Uint128::zero(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        None
+    );
+
+    // The new holder can act on the position while the previous holder cannot.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::ExecuteStrategy {
+                position_id: Uint128::zero(),
+                action: Action::IncreasePosition { data: None },
+                assets: vec![],
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("carol", &[]),
+        ExecuteMsg::ExecuteStrategy {
+            position_id: Uint128::zero(),
+            action: Action::IncreasePosition { data: None },
+            assets: vec![],
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_cw721_interface() {
+    use aperture_common::cw721::{
+        AllNftInfoResponse, Approval, ApprovalsResponse, ContractInfoResponse, NftInfoResponse,
+        NumTokensResponse, OperatorsResponse, OwnerOfResponse, PositionNftExtension,
+        TokensResponse,
+    };
+    use cosmwasm_std::Binary;
+    use cw20::Expiration;
+
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        InstantiateMsg {
+            admin_addr: MOCK_CONTRACT_ADDR.to_string(),
+            wormhole_core_bridge_addr: String::from("mock_wormhole_core_bridge"),
+            wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
+            cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
+            cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::AddStrategy {
+            name: "test_strat".to_string(),
+            version: "1.0.1".to_string(),
+            manager_addr: "strategy_manager".to_string(),
+        },
+    )
+    .unwrap();
+    for _ in 0..2 {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::CreatePosition {
+                strategy: Strategy {
+                    chain_id: TERRA_CHAIN_ID,
+                    strategy_id: Uint64::zero(),
+                },
+                data: None,
+                assets: vec![],
+            },
+        )
+        .unwrap();
+    }
+
+    assert_eq!(
+        from_binary::<ContractInfoResponse>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap()
+        )
+        .unwrap(),
+        ContractInfoResponse {
+            name: String::from("Aperture Position"),
+            symbol: String::from("APOS"),
+        }
+    );
+    assert_eq!(
+        from_binary::<NumTokensResponse>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap()
+        )
+        .unwrap(),
+        NumTokensResponse { count: 2 }
+    );
+    assert_eq!(
+        from_binary::<TokensResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Tokens {
+                    owner: String::from("alice"),
+                    start_after: Some(String::from("0")),
+                    limit: None,
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        TokensResponse {
+            tokens: vec![String::from("1")],
+        }
+    );
+
+    // Only the holder or its operators can approve.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::Approve {
+                spender: String::from("bob"),
+                token_id: String::from("0"),
+                expires: None,
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Approve {
+            spender: String::from("bob"),
+            token_id: String::from("0"),
+            expires: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<ApprovalsResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Approvals {
+                    token_id: String::from("0"),
+                    include_expired: None,
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        ApprovalsResponse {
+            approvals: vec![Approval {
+                spender: String::from("bob"),
+                expires: Expiration::Never {},
+            }],
+        }
+    );
+
+    // The approved spender transfers position 0 to carol, which clears the approval.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("carol"),
+                token_id: String::from("1"),
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::TransferNft {
+            recipient: String::from("carol"),
+            token_id: String::from("0"),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<OwnerOfResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OwnerOf {
+                    token_id: String::from("0"),
+                    include_expired: None,
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        OwnerOfResponse {
+            owner: String::from("carol"),
+            approvals: vec![],
+        }
+    );
+
+    // An operator of alice sends position 1 to a contract.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::ApproveAll {
+                operator: String::from("dave"),
+                expires: Some(Expiration::AtHeight(1)),
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("expiration already passed")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::ApproveAll {
+            operator: String::from("dave"),
+            expires: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<OperatorsResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllOperators {
+                    owner: String::from("alice"),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        OperatorsResponse {
+            operators: vec![Approval {
+                spender: String::from("dave"),
+                expires: Expiration::Never {},
+            }],
+        }
+    );
+    let response = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("dave", &[]),
+        ExecuteMsg::SendNft {
+            contract: String::from("vault"),
+            token_id: String::from("1"),
+            msg: Binary::from(vec![1, 2, 3]),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        response.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("vault"),
+            msg: Binary::from(
+                br#"{"receive_nft":{"sender":"dave","token_id":"1","msg":"AQID"}}"#.to_vec()
+            ),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        from_binary::<TokensResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllTokens {
+                    start_after: None,
+                    limit: None,
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        TokensResponse {
+            tokens: vec![String::from("0"), String::from("1")],
+        }
+    );
+
+    // After revoking, dave can no longer transfer alice's positions.
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::RevokeAll {
+            operator: String::from("dave"),
+        },
+    )
+    .unwrap();
+    assert!(from_binary::<OperatorsResponse>(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllOperators {
+                owner: String::from("alice"),
+                include_expired: None,
+                start_after: None,
+                limit: None,
+            }
+        )
+        .unwrap()
+    )
+    .unwrap()
+    .operators
+    .is_empty());
+
+    // Metadata comes from the strategy; the mock strategy manager does not report position info.
+    assert_eq!(
+        from_binary::<AllNftInfoResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllNftInfo {
+                    token_id: String::from("1"),
+                    include_expired: None,
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        AllNftInfoResponse {
+            access: OwnerOfResponse {
+                owner: String::from("vault"),
+                approvals: vec![],
+            },
+            info: NftInfoResponse {
+                token_uri: None,
+                extension: PositionNftExtension {
+                    strategy_location: StrategyLocation::TerraChain(Uint64::zero()),
+                    strategy_metadata: Some(StrategyMetadata {
+                        name: "test_strat".to_string(),
+                        version: "1.0.1".to_string(),
+                        manager_addr: Addr::unchecked("strategy_manager"),
+                    }),
+                    position_info: None,
+                },
+            },
+        }
+    );
+    assert_eq!(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo {
+                token_id: String::from("abc"),
+            }
+        )
+        .unwrap_err(),
+        StdError::generic_err("invalid token_id")
+    );
+}
+
+#[test]
+fn test_position_operators() {
+    use aperture_common::terra_manager::{PositionActionKind, PositionOperatorGrant};
+    use cw20::Expiration;
+
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        InstantiateMsg {
+            admin_addr: MOCK_CONTRACT_ADDR.to_string(),
+            wormhole_core_bridge_addr: String::from("mock_wormhole_core_bridge"),
+            wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
+            cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
+            cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::AddStrategy {
+            name: "test_strat".to_string(),
+            version: "1.0.1".to_string(),
+            manager_addr: "strategy_manager".to_string(),
+        },
+    )
+    .unwrap();
+    for _ in 0..2 {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::CreatePosition {
+                strategy: Strategy {
+                    chain_id: TERRA_CHAIN_ID,
+                    strategy_id: Uint64::zero(),
+                },
+                data: None,
+                assets: vec![],
+            },
+        )
+        .unwrap();
+    }
+    let execute_as_bot =
+        |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, position_id: u128, action: Action| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bot", &[]),
+                ExecuteMsg::ExecuteStrategy {
+                    position_id: Uint128::from(position_id),
+                    action,
+                    assets: vec![],
+                },
+            )
+        };
+    let close_to = |recipient: &str| Action::ClosePosition {
+        recipient: Recipient::TerraChain {
+            recipient: recipient.to_string(),
+        },
+    };
+
+    // Without a grant, the bot cannot act on alice's positions.
+    assert_eq!(
+        execute_as_bot(&mut deps, 0, Action::IncreasePosition { data: None }).unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // Only the holder can make a position-specific grant.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot", &[]),
+            ExecuteMsg::GrantPositionOperator {
+                operator: String::from("bot"),
+                position_id: Some(Uint128::zero()),
+                allowed_actions: vec![PositionActionKind::ClosePosition],
+                expires: None,
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // Grant increase rights on all of alice's positions and close rights on position 0 only.
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::GrantPositionOperator {
+            operator: String::from("bot"),
+            position_id: None,
+            allowed_actions: vec![PositionActionKind::IncreasePosition],
+            expires: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::GrantPositionOperator {
+            operator: String::from("bot"),
+            position_id: Some(Uint128::zero()),
+            allowed_actions: vec![PositionActionKind::ClosePosition],
+            expires: Some(Expiration::AtHeight(mock_env().block.height + 1)),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<Option<PositionOperatorGrant>>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetPositionOperatorGrant {
+                    holder: String::from("alice"),
+                    operator: String::from("bot"),
+                    position_id: None,
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        Some(PositionOperatorGrant {
+            allowed_actions: vec![PositionActionKind::IncreasePosition],
+            expires: Expiration::Never {},
+        })
+    );
+
+    execute_as_bot(&mut deps, 0, Action::IncreasePosition { data: None }).unwrap();
+    execute_as_bot(&mut deps, 1, Action::IncreasePosition { data: None }).unwrap();
+    execute_as_bot(&mut deps, 0, close_to("alice")).unwrap();
+    assert_eq!(
+        execute_as_bot(&mut deps, 1, close_to("alice")).unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+    assert_eq!(
+        execute_as_bot(&mut deps, 0, close_to("bot")).unwrap_err(),
+        StdError::generic_err("operator can only disburse assets to the position holder")
+    );
+    assert_eq!(
+        execute_as_bot(
+            &mut deps,
+            0,
+            Action::DecreasePosition {
+                proportion: Decimal::percent(50),
+                recipient: Recipient::TerraChain {
+                    recipient: String::from("alice"),
+                },
+            }
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // The position-specific grant expires.
+    let mut env = mock_env();
+    env.block.height += 1;
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("bot", &[]),
+            ExecuteMsg::ExecuteStrategy {
+                position_id: Uint128::zero(),
+                action: close_to("alice"),
+                assets: vec![],
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // Revoking the holder-wide grant removes the increase rights.
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::RevokePositionOperator {
+            operator: String::from("bot"),
+            position_id: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        execute_as_bot(&mut deps, 1, Action::IncreasePosition { data: None }).unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // Position-specific grants do not survive a position transfer.
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::TransferPosition {
+            position_id: Uint128::zero(),
+            recipient: String::from("carol"),
+            require_acceptance: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<Option<PositionOperatorGrant>>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetPositionOperatorGrant {
+                    holder: String::from("carol"),
+                    operator: String::from("bot"),
+                    position_id: Some(Uint128::zero()),
+                }
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_pause() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        InstantiateMsg {
+            admin_addr: MOCK_CONTRACT_ADDR.to_string(),
+            wormhole_core_bridge_addr: String::from("mock_wormhole_core_bridge"),
+            wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
+            cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
+            cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+            timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    propose_and_execute_admin_operation(
+        deps.as_mut(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::AddStrategy {
+            name: "test_strat".to_string(),
+            version: "1.0.1".to_string(),
+            manager_addr: "strategy_manager".to_string(),
+        },
+    )
+    .unwrap();
+    let create_position = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::CreatePosition {
+                strategy: Strategy {
+                    chain_id: TERRA_CHAIN_ID,
+                    strategy_id: Uint64::zero(),
+                },
+                data: None,
+                assets: vec![],
+            },
+        )
+    };
+    let pause_msg = |scope: PauseScope, operation: PausableOperation| ExecuteMsg::Pause {
+        scope,
+        operations: vec![operation],
+    };
+    let unpause_msg = |scope: PauseScope, operation: PausableOperation| ExecuteMsg::Unpause {
+        scope,
+        operations: vec![operation],
+    };
+    let paused = StdError::generic_err("operation is paused");
+
+    // Only the administrator can set the pauser.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::UpdatePauser {
+                pauser_addr: Some("alice".to_string()),
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("Caller is not admin")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::UpdatePauser {
+            pauser_addr: Some("pauser".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<Option<Addr>>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::GetPauser {}).unwrap()
+        )
+        .unwrap(),
+        Some(Addr::unchecked("pauser"))
+    );
+
+    // Pause position creation in the strategy; only the pauser and the administrator can pause.
+    let strategy_scope = PauseScope::Strategy {
+        strategy_id: Uint64::zero(),
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            pause_msg(strategy_scope.clone(), PausableOperation::CreatePosition),
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pauser", &[]),
+        pause_msg(strategy_scope.clone(), PausableOperation::CreatePosition),
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<Vec<PausableOperation>>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetPausedOperations {
+                    scope: strategy_scope.clone(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        vec![PausableOperation::CreatePosition]
+    );
+    assert_eq!(create_position(&mut deps).unwrap_err(), paused);
+
+    // Only the administrator can unpause.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("pauser", &[]),
+            unpause_msg(strategy_scope.clone(), PausableOperation::CreatePosition),
+        )
+        .unwrap_err(),
+        StdError::generic_err("Caller is not admin")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        unpause_msg(strategy_scope, PausableOperation::CreatePosition),
+    )
+    .unwrap();
+    create_position(&mut deps).unwrap();
+
+    // Pause strategy execution on Terra chain.
+    let terra_scope = PauseScope::Chain {
+        chain_id: TERRA_CHAIN_ID,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pauser", &[]),
+        pause_msg(terra_scope.clone(), PausableOperation::ExecuteStrategy),
+    )
+    .unwrap();
+    let execute_strategy_msg = ExecuteMsg::ExecuteStrategy {
+        position_id: Uint128::zero(),
+        action: Action::ClosePosition {
+            recipient: Recipient::TerraChain {
+                recipient: String::from("alice"),
+            },
+        },
+        assets: vec![],
+    };
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            execute_strategy_msg.clone(),
+        )
+        .unwrap_err(),
+        paused
+    );
+    // Other operations are unaffected.
+    create_position(&mut deps).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        unpause_msg(terra_scope, PausableOperation::ExecuteStrategy),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        execute_strategy_msg,
+    )
+    .unwrap();
+
+    // Pause outgoing token transfers globally.
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        pause_msg(
+            PauseScope::Global,
+            PausableOperation::InitiateOutgoingTokenTransfer,
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::InitiateOutgoingTokenTransfer {
+                assets: vec![],
+                recipient: Recipient::TerraChain {
+                    recipient: String::from("bob"),
+                },
+            },
+        )
+        .unwrap_err(),
+        paused
+    );
+}
+
+#[test]
+fn test_timelock_admin_operations() {
+    let mut deps = mock_dependencies(&[]);
+    let instantiate_msg = InstantiateMsg {
+        admin_addr: MOCK_CONTRACT_ADDR.to_string(),
+        wormhole_core_bridge_addr: String::from("mock_wormhole_core_bridge"),
+        wormhole_token_bridge_addr: String::from("mock_wormhole_token_bridge"),
+        cross_chain_outgoing_fee_rate: Decimal::from_ratio(1u128, 1000u128),
+        cross_chain_outgoing_fee_collector_addr: String::from("mock_fee_collector"),
+        timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+    };
+    let delay_too_short = StdError::generic_err("timelock delay must be at least 86400 seconds");
+    for delay_seconds in [0, MIN_TIMELOCK_DELAY_SECONDS - 1].iter() {
+        assert_eq!(
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(MOCK_CONTRACT_ADDR, &[]),
+                InstantiateMsg {
+                    timelock_delay_seconds: *delay_seconds,
+                    ..instantiate_msg.clone()
+                },
+            )
+            .unwrap_err(),
+            delay_too_short
+        );
+    }
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        instantiate_msg,
+    )
+    .unwrap();
+    let propose = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>,
+                   env: Env,
+                   sender: &str,
+                   operation: AdminOperation| {
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(sender, &[]),
+            ExecuteMsg::ProposeAdminOperation { operation },
+        )
+    };
+    let execute_proposal =
+        |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, env: Env, sender: &str, proposal_id: u64| {
+            execute(
+                deps.as_mut(),
+                env,
+                mock_info(sender, &[]),
+                ExecuteMsg::ExecuteAdminOperation { proposal_id },
+            )
+        };
+    let query_delay = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        from_binary::<u64>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::GetTimelockDelay {}).unwrap(),
+        )
+        .unwrap()
+    };
+    let query_pending_admin = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        from_binary::<Option<Addr>>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::GetPendingAdmin {}).unwrap(),
+        )
+        .unwrap()
+    };
+    let env_after = |seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        env
+    };
+    let add_strategy = AdminOperation::AddStrategy {
+        name: "test_strat".to_string(),
+        version: "1.0.0".to_string(),
+        manager_addr: "strategy_manager".to_string(),
+    };
+    assert_eq!(query_delay(&deps), MIN_TIMELOCK_DELAY_SECONDS);
+
+    // Sensitive operations cannot be performed directly, even right after instantiation.
+    let direct_msgs = vec![
+        ExecuteMsg::UpdateAdminAddr {
+            new_admin_addr: String::from("new_admin"),
+        },
+        ExecuteMsg::AddStrategy {
+            name: "test_strat".to_string(),
+            version: "1.0.0".to_string(),
+            manager_addr: "strategy_manager".to_string(),
+        },
+        ExecuteMsg::RemoveStrategy {
+            strategy_id: Uint64::zero(),
+        },
+        ExecuteMsg::UpdateStrategy {
+            strategy_id: Uint64::zero(),
+            version: None,
+            manager_addr: None,
+            status: Some(StrategyStatus::Paused),
+        },
+        ExecuteMsg::RegisterExternalChainManager {
+            chain_id: 5,
+            aperture_manager_addr: Binary::from(&[1u8; 32][..]),
+        },
+        ExecuteMsg::UpdateCrossChainOutgoingFeeSchedule {
+            update: CrossChainOutgoingFeeScheduleUpdate {
+                default_rate: Some(Decimal::zero()),
+                ..CrossChainOutgoingFeeScheduleUpdate::default()
+            },
+        },
+    ];
+    for msg in direct_msgs {
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(MOCK_CONTRACT_ADDR, &[]),
+                msg,
+            )
+            .unwrap_err(),
+            StdError::generic_err("operation is subject to timelock and must be proposed")
+        );
+    }
+    assert_eq!(query_pending_admin(&deps), None);
+    assert_eq!(
+        NEXT_STRATEGY_ID.load(deps.as_ref().storage).unwrap(),
+        Uint64::zero()
+    );
+
+    // Only the administrator can propose.
+    assert_eq!(
+        propose(&mut deps, mock_env(), "alice", add_strategy.clone()).unwrap_err(),
+        StdError::generic_err("Caller is not admin")
+    );
+    propose(
+        &mut deps,
+        mock_env(),
+        MOCK_CONTRACT_ADDR,
+        add_strategy.clone(),
+    )
+    .unwrap();
+    propose(
+        &mut deps,
+        mock_env(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::UpdateAdminAddr {
+            new_admin_addr: String::from("new_admin"),
+        },
+    )
+    .unwrap();
+    propose(
+        &mut deps,
+        mock_env(),
+        MOCK_CONTRACT_ADDR,
+        AdminOperation::RegisterExternalChainManager {
+            chain_id: 5,
+            aperture_manager_addr: Binary::from(&[1u8; 32][..]),
+        },
+    )
+    .unwrap();
+    let proposals: Vec<AdminOperationProposal<AdminOperation>> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetAdminOperationProposals {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(proposals.len(), 3);
+    assert_eq!(
+        proposals[0],
+        AdminOperationProposal {
+            proposal_id: 0,
+            operation: add_strategy,
+            executable_after: env_after(MIN_TIMELOCK_DELAY_SECONDS).block.time,
+        }
+    );
+
+    // Proposals can only be executed once the delay has elapsed.
+    assert_eq!(
+        execute_proposal(
+            &mut deps,
+            env_after(MIN_TIMELOCK_DELAY_SECONDS - 1),
+            MOCK_CONTRACT_ADDR,
+            0
+        )
+        .unwrap_err(),
+        StdError::generic_err("timelock has not elapsed")
+    );
+    let later_env = env_after(MIN_TIMELOCK_DELAY_SECONDS);
+    execute_proposal(&mut deps, later_env.clone(), MOCK_CONTRACT_ADDR, 0).unwrap();
+    assert_eq!(
+        from_binary::<StrategyMetadata>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetStrategyMetadata {
+                    strategy_id: Uint64::zero(),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap()
+        .name,
+        "test_strat"
+    );
+
+    // Proposals can be cancelled by the administrator.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::CancelAdminOperation { proposal_id: 2 },
+        )
+        .unwrap_err(),
+        StdError::generic_err("Caller is not admin")
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::CancelAdminOperation { proposal_id: 2 },
+    )
+    .unwrap();
+    assert_eq!(
+        execute_proposal(&mut deps, later_env.clone(), MOCK_CONTRACT_ADDR, 2).unwrap_err(),
+        StdError::generic_err("proposal not found")
+    );
+
+    // Executing the admin handover nominates the new administrator; the handover takes effect once accepted.
+    assert_eq!(
+        execute_proposal(&mut deps, later_env.clone(), "new_admin", 1).unwrap_err(),
+        StdError::generic_err("Caller is not admin")
+    );
+    execute_proposal(&mut deps, later_env.clone(), MOCK_CONTRACT_ADDR, 1).unwrap();
+    assert_eq!(
+        query_pending_admin(&deps),
+        Some(Addr::unchecked("new_admin"))
+    );
+    for sender in [MOCK_CONTRACT_ADDR, "alice"].iter() {
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::AcceptAdmin {},
+            )
+            .unwrap_err(),
+            StdError::generic_err("unauthorized")
+        );
+    }
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("new_admin", &[]),
+        ExecuteMsg::AcceptAdmin {},
+    )
+    .unwrap();
+    assert_eq!(query_pending_admin(&deps), None);
+
+    // A change to the delay is itself subject to the current delay, and cannot go below the minimum.
+    for delay_seconds in [0, MIN_TIMELOCK_DELAY_SECONDS - 1].iter() {
+        assert_eq!(
+            propose(
+                &mut deps,
+                mock_env(),
+                "new_admin",
+                AdminOperation::UpdateTimelockDelay {
+                    delay_seconds: *delay_seconds,
+                },
+            )
+            .unwrap_err(),
+            delay_too_short
+        );
+    }
+    assert_eq!(
+        propose(
+            &mut deps,
+            mock_env(),
+            MOCK_CONTRACT_ADDR,
+            AdminOperation::UpdateTimelockDelay {
+                delay_seconds: 2 * MIN_TIMELOCK_DELAY_SECONDS,
+            },
+        )
+        .unwrap_err(),
+        StdError::generic_err("Caller is not admin")
+    );
+    propose(
+        &mut deps,
+        mock_env(),
+        "new_admin",
+        AdminOperation::UpdateTimelockDelay {
+            delay_seconds: 2 * MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        execute_proposal(
+            &mut deps,
+            env_after(MIN_TIMELOCK_DELAY_SECONDS - 1),
+            "new_admin",
+            3
+        )
+        .unwrap_err(),
+        StdError::generic_err("timelock has not elapsed")
+    );
+    execute_proposal(&mut deps, later_env.clone(), "new_admin", 3).unwrap();
+    assert_eq!(query_delay(&deps), 2 * MIN_TIMELOCK_DELAY_SECONDS);
+
+    // Shortening the delay again takes the longer delay now in effect.
+    let response = propose(
+        &mut deps,
+        later_env,
+        "new_admin",
+        AdminOperation::UpdateTimelockDelay {
+            delay_seconds: MIN_TIMELOCK_DELAY_SECONDS,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        response.attributes[1].value,
+        env_after(3 * MIN_TIMELOCK_DELAY_SECONDS)
+            .block
+            .time
+            .to_string()
+    );
+    assert_eq!(
+        execute_proposal(
+            &mut deps,
+            env_after(2 * MIN_TIMELOCK_DELAY_SECONDS),
+            "new_admin",
+            4
+        )
+        .unwrap_err(),
+        StdError::generic_err("timelock has not elapsed")
+    );
+    execute_proposal(
+        &mut deps,
+        env_after(3 * MIN_TIMELOCK_DELAY_SECONDS),
+        "new_admin",
+        4,
+    )
+    .unwrap();
+    assert_eq!(query_delay(&deps), MIN_TIMELOCK_DELAY_SECONDS);
+
+    // Migration requires a valid delay as well.
+    assert_eq!(
+        migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                timelock_delay_seconds: MIN_TIMELOCK_DELAY_SECONDS - 1,
+            },
+        )
+        .unwrap_err(),
+        delay_too_short
+    );
+}
@@ -1,9 +1,12 @@
 use aperture_common::{
     byte_util::{extend_terra_address_to_32, ByteUtils},
-    common::{ChainId, Position, Recipient},
+    common::{get_position_key, Action, ChainId, Position, Recipient, Strategy, StrategyLocation},
     constants::WORMHOLE_NONCE,
-    instruction::ApertureInstruction,
-    terra_manager::TERRA_CHAIN_ID,
+    instruction::{ApertureInstruction, StrategyInstructionInfo},
+    terra_manager::{
+        CrossChainOutgoingFeePreviewResponse, CrossChainOutgoingFeeScheduleUpdate,
+        PausableOperation, TERRA_CHAIN_ID,
+    },
     token_util::{forward_assets_direct, validate_and_accept_incoming_asset_transfer},
     wormhole::{
         ParsedVAA, TokenBridgeMessage, TransferInfo, WormholeCoreBridgeExecuteMsg,
@@ -11,16 +14,19 @@ use aperture_common::{
     },
 };
 use cosmwasm_std::{
-    entry_point, to_binary, BankMsg, Binary, Coin, ContractResult, CosmosMsg, Deps, DepsMut, Env,
-    MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    entry_point, to_binary, Addr, BankMsg, Binary, Coin, ContractResult, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw_storage_plus::U16Key;
 use terraswap::asset::{Asset, AssetInfo};
 
 use crate::{
+    pause::assert_not_paused,
     state::{
-        ADMIN, CHAIN_ID_TO_APERTURE_MANAGER_ADDRESS_MAP, COMPLETED_INSTRUCTIONS,
-        CROSS_CHAIN_OUTGOING_FEE_CONFIG, WORMHOLE_CORE_BRIDGE_ADDR, WORMHOLE_TOKEN_BRIDGE_ADDR,
+        get_asset_info_key, ADMIN, CHAIN_ID_TO_APERTURE_MANAGER_ADDRESS_MAP,
+        COMPLETED_INSTRUCTIONS, CROSS_CHAIN_OUTGOING_FEE_BOUNDS, CROSS_CHAIN_OUTGOING_FEE_CONFIG,
+        CROSS_CHAIN_OUTGOING_FEE_EXEMPT_ADDRS, CROSS_CHAIN_OUTGOING_FEE_RATE_OVERRIDES,
+        POSITION_TO_STRATEGY_LOCATION_MAP, WORMHOLE_CORE_BRIDGE_ADDR, WORMHOLE_TOKEN_BRIDGE_ADDR,
     },
     terra_chain::{create_execute_strategy_messages, save_new_position_info_and_open_it},
     util::get_next_sequence,
@@ -35,6 +41,19 @@ pub fn initiate_outgoing_token_transfer(
     assets: Vec<Asset>,
     recipient: Recipient,
 ) -> StdResult<Response> {
+    let recipient_chain_id = match &recipient {
+        Recipient::TerraChain { .. } => TERRA_CHAIN_ID,
+        Recipient::ExternalChain {
+            recipient_chain_id, ..
+        } => *recipient_chain_id,
+    };
+    assert_not_paused(
+        deps,
+        PausableOperation::InitiateOutgoingTokenTransfer,
+        recipient_chain_id,
+        None,
+    )?;
+    let sender = info.sender.clone();
     let mut response = Response::new().add_messages(validate_and_accept_incoming_asset_transfer(
         deps, env, info, &assets,
     )?);
@@ -63,8 +82,11 @@ pub fn initiate_outgoing_token_transfer(
             let mut fee_collection_assets = vec![];
             let mut cross_chain_assets = vec![];
             for asset in assets.iter() {
-                let fee_amount = asset.amount * cross_chain_outgoing_fee_config.rate;
-                let cross_chain_amount = asset.amount - fee_amount;
+                let CrossChainOutgoingFeePreviewResponse {
+                    fee_amount,
+                    cross_chain_amount,
+                    ..
+                } = compute_cross_chain_outgoing_fee(deps, &sender, recipient_chain_id, asset)?;
                 if !fee_amount.is_zero() {
                     fee_collection_assets.push(Asset {
                         amount: fee_amount,
@@ -95,21 +117,15 @@ pub fn initiate_outgoing_token_transfer(
             }
 
             // Publish serialized token disbursement instruction.
-            let instruction_bytes = ApertureInstruction::SingleTokenDisbursementInstruction {
-                sequence: get_next_sequence(deps, &wormhole_token_bridge_addr)?,
-                recipient_chain_id,
-                recipient_addr: recipient_addr.to_array()?,
-                swap_info,
-            }
-            .serialize()?;
-            response = response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: WORMHOLE_CORE_BRIDGE_ADDR.load(deps.storage)?.to_string(),
-                funds: vec![],
-                msg: to_binary(&WormholeCoreBridgeExecuteMsg::PostMessage {
-                    message: Binary::from(instruction_bytes),
-                    nonce: WORMHOLE_NONCE,
-                })?,
-            }));
+            response = response.add_message(create_post_instruction_message(
+                deps,
+                ApertureInstruction::SingleTokenDisbursementInstruction {
+                    sequence: get_next_sequence(deps, &wormhole_token_bridge_addr)?,
+                    recipient_chain_id,
+                    recipient_addr: recipient_addr.to_array()?,
+                    swap_info,
+                },
+            )?);
 
             // Transfer token to Aperture manager contract on the recipient chain.
             if cross_chain_assets.len() != 1 {
@@ -117,47 +133,493 @@ pub fn initiate_outgoing_token_transfer(
                     "only single-token cross-chain disbursement is supported at this time",
                 ));
             }
-            let asset = &cross_chain_assets[0];
-            match &asset.info {
-                AssetInfo::NativeToken { denom } => {
-                    response = response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                        contract_addr: wormhole_token_bridge_addr.to_string(),
-                        msg: to_binary(&WormholeTokenBridgeExecuteMsg::DepositTokens {})?,
-                        funds: vec![Coin {
-                            amount: asset.amount,
-                            denom: denom.clone(),
-                        }],
-                    }));
-                }
-                AssetInfo::Token { contract_addr } => {
-                    response = response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                        contract_addr: contract_addr.clone(),
-                        msg: to_binary(&cw20::Cw20ExecuteMsg::IncreaseAllowance {
-                            spender: wormhole_token_bridge_addr.to_string(),
-                            amount: asset.amount,
-                            expires: None,
-                        })?,
-                        funds: vec![],
-                    }));
-                }
+            let (transfer_messages, _) = create_token_transfer_to_external_chain_manager_messages(
+                deps,
+                recipient_chain_id,
+                &cross_chain_assets,
+            )?;
+            response = response.add_messages(transfer_messages);
+        }
+    }
+    Ok(response)
+}
+
+// Returns a message that publishes the serialized `instruction` via Wormhole core bridge.
+fn create_post_instruction_message(
+    deps: Deps,
+    instruction: ApertureInstruction,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: WORMHOLE_CORE_BRIDGE_ADDR.load(deps.storage)?.to_string(),
+        funds: vec![],
+        msg: to_binary(&WormholeCoreBridgeExecuteMsg::PostMessage {
+            message: Binary::from(instruction.serialize()?),
+            nonce: WORMHOLE_NONCE,
+        })?,
+    }))
+}
+
+// Returns messages that transfer `assets` held by this contract to the registered Aperture manager on `recipient_chain_id` via Wormhole token bridge,
+// along with the token bridge sequence numbers of these transfers in the same order as `assets`.
+fn create_token_transfer_to_external_chain_manager_messages(
+    deps: Deps,
+    recipient_chain_id: ChainId,
+    assets: &[Asset],
+) -> StdResult<(Vec<CosmosMsg>, Vec<u64>)> {
+    let wormhole_token_bridge_addr = WORMHOLE_TOKEN_BRIDGE_ADDR.load(deps.storage)?;
+    let recipient = Binary::from(
+        CHAIN_ID_TO_APERTURE_MANAGER_ADDRESS_MAP
+            .load(deps.storage, U16Key::from(recipient_chain_id))
+            .map_err(|_| StdError::generic_err("external chain manager not registered"))?,
+    );
+    // Each token transfer initiated by Wormhole token bridge consumes the next sequence number in order.
+    let next_sequence = get_next_sequence(deps, &wormhole_token_bridge_addr)?;
+    let mut messages = vec![];
+    let mut sequences = vec![];
+    for (index, asset) in assets.iter().enumerate() {
+        match &asset.info {
+            AssetInfo::NativeToken { denom } => {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: wormhole_token_bridge_addr.to_string(),
+                    msg: to_binary(&WormholeTokenBridgeExecuteMsg::DepositTokens {})?,
+                    funds: vec![Coin {
+                        amount: asset.amount,
+                        denom: denom.clone(),
+                    }],
+                }));
             }
-            response = response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: wormhole_token_bridge_addr.to_string(),
+            AssetInfo::Token { contract_addr } => {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.clone(),
+                    msg: to_binary(&cw20::Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: wormhole_token_bridge_addr.to_string(),
+                        amount: asset.amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                }));
+            }
+        }
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: wormhole_token_bridge_addr.to_string(),
+            msg: to_binary(&WormholeTokenBridgeExecuteMsg::InitiateTransfer {
+                asset: asset.clone(),
+                recipient_chain: recipient_chain_id,
+                recipient: recipient.clone(),
+                fee: Uint128::zero(),
+                nonce: WORMHOLE_NONCE,
+            })?,
+            funds: vec![],
+        }));
+        sequences.push(next_sequence + index as u64);
+    }
+    Ok((messages, sequences))
+}
+
+// Accepts `assets` from the position holder, transfers them to the Aperture manager on the strategy chain,
+// and publishes a position-open instruction that references these token transfers.
+pub fn open_external_chain_position(
+    deps: Deps,
+    env: Env,
+    info: MessageInfo,
+    position: Position,
+    strategy: Strategy,
+    data: Option<Binary>,
+    assets: Vec<Asset>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = validate_and_accept_incoming_asset_transfer(deps, env, info, &assets)?;
+    let (transfer_messages, token_transfer_sequences) =
+        create_token_transfer_to_external_chain_manager_messages(deps, strategy.chain_id, &assets)?;
+    messages.push(create_post_instruction_message(
+        deps,
+        ApertureInstruction::PositionOpenInstruction {
+            strategy_info: StrategyInstructionInfo {
+                position_id: position.position_id,
+                strategy_chain_id: strategy.chain_id,
+                token_transfer_sequences,
+            },
+            strategy_id: strategy.strategy_id,
+            open_position_action_data: data,
+        },
+    )?);
+    messages.extend(transfer_messages);
+    Ok(messages)
+}
+
+#[test]
+fn test_create_external_chain_position() {
+    use crate::mock_querier::custom_mock_dependencies;
+    use crate::state::{NEXT_POSITION_ID, POSITION_TO_STRATEGY_LOCATION_MAP};
+    use crate::terra_chain::create_position;
+    use aperture_common::common::{get_position_key, StrategyLocation};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, Addr, Uint64};
+
+    let mut deps = custom_mock_dependencies("wormhole_core_bridge");
+    WORMHOLE_CORE_BRIDGE_ADDR
+        .save(
+            deps.as_mut().storage,
+            &Addr::unchecked("wormhole_core_bridge"),
+        )
+        .unwrap();
+    WORMHOLE_TOKEN_BRIDGE_ADDR
+        .save(
+            deps.as_mut().storage,
+            &Addr::unchecked("wormhole_token_bridge"),
+        )
+        .unwrap();
+    NEXT_POSITION_ID
+        .save(deps.as_mut().storage, &Uint128::from(7u128))
+        .unwrap();
+
+    let strategy_chain_id = 5u16;
+    let strategy = Strategy {
+        chain_id: strategy_chain_id,
+        strategy_id: Uint64::from(3u64),
+    };
+    let uusd_coin = Coin {
+        denom: String::from("uusd"),
+        amount: Uint128::from(1000u128),
+    };
+    let assets = vec![
+        Asset {
+            amount: Uint128::from(1000u128),
+            info: AssetInfo::NativeToken {
+                denom: String::from("uusd"),
+            },
+        },
+        Asset {
+            amount: Uint128::from(500u128),
+            info: AssetInfo::Token {
+                contract_addr: String::from("terra1cw20"),
+            },
+        },
+    ];
+    let data = Some(Binary::from(vec![1, 2, 3]));
+
+    let aperture_manager = [2u8; 32];
+    CHAIN_ID_TO_APERTURE_MANAGER_ADDRESS_MAP
+        .save(
+            deps.as_mut().storage,
+            U16Key::from(strategy_chain_id),
+            &aperture_manager,
+        )
+        .unwrap();
+    let response = create_position(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("sender", &coins(1000, "uusd")),
+        strategy.clone(),
+        data.clone(),
+        assets.clone(),
+    )
+    .unwrap();
+    let position = Position {
+        chain_id: TERRA_CHAIN_ID,
+        position_id: Uint128::from(7u128),
+    };
+    assert_eq!(
+        POSITION_TO_STRATEGY_LOCATION_MAP
+            .load(deps.as_ref().storage, get_position_key(&position))
+            .unwrap(),
+        StrategyLocation::ExternalChain(strategy_chain_id)
+    );
+
+    // The mock core bridge reports 10 as the next token bridge sequence, so the two transfers are assigned sequences 10 and 11.
+    let instruction_bytes = ApertureInstruction::PositionOpenInstruction {
+        strategy_info: StrategyInstructionInfo {
+            position_id: position.position_id,
+            strategy_chain_id,
+            token_transfer_sequences: vec![10u64, 11u64],
+        },
+        strategy_id: Uint64::from(3u64),
+        open_position_action_data: data,
+    }
+    .serialize()
+    .unwrap();
+    let messages: Vec<CosmosMsg> = response.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(
+        messages,
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("terra1cw20"),
+                msg: to_binary(&cw20::Cw20ExecuteMsg::TransferFrom {
+                    owner: String::from("sender"),
+                    recipient: mock_env().contract.address.to_string(),
+                    amount: Uint128::from(500u128)
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("wormhole_core_bridge"),
+                msg: to_binary(&WormholeCoreBridgeExecuteMsg::PostMessage {
+                    message: Binary::from(instruction_bytes),
+                    nonce: WORMHOLE_NONCE,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("wormhole_token_bridge"),
+                msg: to_binary(&WormholeTokenBridgeExecuteMsg::DepositTokens {}).unwrap(),
+                funds: vec![uusd_coin.clone()],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("wormhole_token_bridge"),
                 msg: to_binary(&WormholeTokenBridgeExecuteMsg::InitiateTransfer {
-                    asset: asset.clone(),
-                    recipient_chain: recipient_chain_id,
-                    recipient: Binary::from(
-                        CHAIN_ID_TO_APERTURE_MANAGER_ADDRESS_MAP
-                            .load(deps.storage, U16Key::from(recipient_chain_id))?,
+                    asset: assets[0].clone(),
+                    recipient_chain: strategy_chain_id,
+                    recipient: Binary::from(aperture_manager),
+                    fee: Uint128::zero(),
+                    nonce: WORMHOLE_NONCE,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("terra1cw20"),
+                msg: to_binary(&cw20::Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: String::from("wormhole_token_bridge"),
+                    amount: Uint128::from(500u128),
+                    expires: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("wormhole_token_bridge"),
+                msg: to_binary(&WormholeTokenBridgeExecuteMsg::InitiateTransfer {
+                    asset: assets[1].clone(),
+                    recipient_chain: strategy_chain_id,
+                    recipient: Binary::from(aperture_manager),
+                    fee: Uint128::zero(),
+                    nonce: WORMHOLE_NONCE,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // The Aperture manager on the strategy chain must be registered.
+    assert_eq!(
+        create_position(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[uusd_coin]),
+            Strategy {
+                chain_id: 6u16,
+                ..strategy
+            },
+            None,
+            assets,
+        )
+        .unwrap_err(),
+        StdError::generic_err("external chain manager not registered")
+    );
+}
+
+// Accepts `assets` from the position holder, transfers them to the Aperture manager on the strategy chain,
+// and publishes an execute-strategy instruction that carries `action` and references these token transfers.
+pub fn execute_external_chain_strategy(
+    deps: Deps,
+    env: Env,
+    info: MessageInfo,
+    position: Position,
+    strategy_chain_id: ChainId,
+    action: Action,
+    assets: Vec<Asset>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = validate_and_accept_incoming_asset_transfer(deps, env, info, &assets)?;
+    let (transfer_messages, token_transfer_sequences) =
+        create_token_transfer_to_external_chain_manager_messages(deps, strategy_chain_id, &assets)?;
+    messages.push(create_post_instruction_message(
+        deps,
+        ApertureInstruction::ExecuteStrategyInstruction {
+            strategy_info: StrategyInstructionInfo {
+                position_id: position.position_id,
+                strategy_chain_id,
+                token_transfer_sequences,
+            },
+            action,
+        },
+    )?);
+    messages.extend(transfer_messages);
+    Ok(messages)
+}
+
+#[test]
+fn test_execute_external_chain_strategy() {
+    use crate::mock_querier::custom_mock_dependencies;
+    use crate::state::{
+        HOLDER_POSITION_ID_PAIR_SET, POSITION_ID_TO_HOLDER, POSITION_TO_STRATEGY_LOCATION_MAP,
+    };
+    use crate::terra_chain::execute_strategy;
+    use aperture_common::common::{get_position_key, Action, StrategyLocation};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, Addr};
+    use cw_storage_plus::U128Key;
+
+    let mut deps = custom_mock_dependencies("wormhole_core_bridge");
+    WORMHOLE_CORE_BRIDGE_ADDR
+        .save(
+            deps.as_mut().storage,
+            &Addr::unchecked("wormhole_core_bridge"),
+        )
+        .unwrap();
+    WORMHOLE_TOKEN_BRIDGE_ADDR
+        .save(
+            deps.as_mut().storage,
+            &Addr::unchecked("wormhole_token_bridge"),
+        )
+        .unwrap();
+    let strategy_chain_id = 5u16;
+    let aperture_manager = [2u8; 32];
+    CHAIN_ID_TO_APERTURE_MANAGER_ADDRESS_MAP
+        .save(
+            deps.as_mut().storage,
+            U16Key::from(strategy_chain_id),
+            &aperture_manager,
+        )
+        .unwrap();
+    let position = Position {
+        chain_id: TERRA_CHAIN_ID,
+        position_id: Uint128::from(4u128),
+    };
+    let holder = Addr::unchecked("holder");
+    POSITION_ID_TO_HOLDER
+        .save(deps.as_mut().storage, U128Key::from(4u128), &holder)
+        .unwrap();
+    HOLDER_POSITION_ID_PAIR_SET
+        .save(deps.as_mut().storage, (holder, U128Key::from(4u128)), &())
+        .unwrap();
+    POSITION_TO_STRATEGY_LOCATION_MAP
+        .save(
+            deps.as_mut().storage,
+            get_position_key(&position),
+            &StrategyLocation::ExternalChain(strategy_chain_id),
+        )
+        .unwrap();
+
+    // Only the position holder may execute the strategy.
+    let close_action = Action::ClosePosition {
+        recipient: Recipient::TerraChain {
+            recipient: String::from("holder"),
+        },
+    };
+    assert_eq!(
+        execute_strategy(
+            deps.as_ref(),
+            mock_env(),
+            mock_info("sender", &[]),
+            position.position_id,
+            close_action.clone(),
+            vec![],
+        )
+        .unwrap_err(),
+        StdError::generic_err("unauthorized")
+    );
+
+    // Closing the position requires no token transfer; only the instruction is published.
+    let response = execute_strategy(
+        deps.as_ref(),
+        mock_env(),
+        mock_info("holder", &[]),
+        position.position_id,
+        close_action.clone(),
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(response.messages.len(), 1);
+    assert_eq!(
+        response.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("wormhole_core_bridge"),
+            msg: to_binary(&WormholeCoreBridgeExecuteMsg::PostMessage {
+                message: Binary::from(
+                    ApertureInstruction::ExecuteStrategyInstruction {
+                        strategy_info: StrategyInstructionInfo {
+                            position_id: position.position_id,
+                            strategy_chain_id,
+                            token_transfer_sequences: vec![],
+                        },
+                        action: close_action,
+                    }
+                    .serialize()
+                    .unwrap()
+                ),
+                nonce: WORMHOLE_NONCE,
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // Increasing the position transfers the attached assets with the next token bridge sequence.
+    let uusd_coin = Coin {
+        denom: String::from("uusd"),
+        amount: Uint128::from(1000u128),
+    };
+    let assets = vec![Asset {
+        amount: Uint128::from(1000u128),
+        info: AssetInfo::NativeToken {
+            denom: String::from("uusd"),
+        },
+    }];
+    let increase_action = Action::IncreasePosition { data: None };
+    let response = execute_strategy(
+        deps.as_ref(),
+        mock_env(),
+        mock_info("holder", &coins(1000, "uusd")),
+        position.position_id,
+        increase_action.clone(),
+        assets.clone(),
+    )
+    .unwrap();
+    let messages: Vec<CosmosMsg> = response.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(
+        messages,
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("wormhole_core_bridge"),
+                msg: to_binary(&WormholeCoreBridgeExecuteMsg::PostMessage {
+                    message: Binary::from(
+                        ApertureInstruction::ExecuteStrategyInstruction {
+                            strategy_info: StrategyInstructionInfo {
+                                position_id: position.position_id,
+                                strategy_chain_id,
+                                token_transfer_sequences: vec![10u64],
+                            },
+                            action: increase_action,
+                        }
+                        .serialize()
+                        .unwrap()
                     ),
+                    nonce: WORMHOLE_NONCE,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("wormhole_token_bridge"),
+                msg: to_binary(&WormholeTokenBridgeExecuteMsg::DepositTokens {}).unwrap(),
+                funds: vec![uusd_coin],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("wormhole_token_bridge"),
+                msg: to_binary(&WormholeTokenBridgeExecuteMsg::InitiateTransfer {
+                    asset: assets[0].clone(),
+                    recipient_chain: strategy_chain_id,
+                    recipient: Binary::from(aperture_manager),
                     fee: Uint128::zero(),
                     nonce: WORMHOLE_NONCE,
-                })?,
+                })
+                .unwrap(),
                 funds: vec![],
-            }));
-        }
-    }
-    Ok(response)
+            }),
+        ]
+    );
 }
 
 #[test]
@@ -461,6 +923,298 @@ fn test_register_external_chain_manager() {
     );
 }
 
+// Returns the fee charged on a cross-chain outgoing transfer of `asset` to `recipient_chain_id` initiated by `sender`.
+// Exempt senders are not charged; otherwise, the rate overridden for the recipient chain and asset, or the default rate,
+// is applied to the transfer amount and the result is clamped to the fee bounds of the asset, if any.
+pub fn compute_cross_chain_outgoing_fee(
+    deps: Deps,
+    sender: &Addr,
+    recipient_chain_id: ChainId,
+    asset: &Asset,
+) -> StdResult<CrossChainOutgoingFeePreviewResponse> {
+    if CROSS_CHAIN_OUTGOING_FEE_EXEMPT_ADDRS.has(deps.storage, sender) {
+        return Ok(CrossChainOutgoingFeePreviewResponse {
+            rate: Decimal::zero(),
+            fee_amount: Uint128::zero(),
+            cross_chain_amount: asset.amount,
+        });
+    }
+    let asset_info_key = get_asset_info_key(&asset.info);
+    let rate = match CROSS_CHAIN_OUTGOING_FEE_RATE_OVERRIDES.may_load(
+        deps.storage,
+        (U16Key::from(recipient_chain_id), &asset_info_key),
+    )? {
+        Some(rate) => rate,
+        None => CROSS_CHAIN_OUTGOING_FEE_CONFIG.load(deps.storage)?.rate,
+    };
+    let mut fee_amount = asset.amount * rate;
+    if let Some(bounds) = CROSS_CHAIN_OUTGOING_FEE_BOUNDS.may_load(deps.storage, &asset_info_key)? {
+        fee_amount = fee_amount.max(bounds.min_fee);
+        if let Some(max_fee) = bounds.max_fee {
+            fee_amount = fee_amount.min(max_fee);
+        }
+    }
+    let cross_chain_amount = asset.amount.checked_sub(fee_amount).map_err(|_| {
+        StdError::generic_err("transfer amount is less than the cross-chain outgoing fee")
+    })?;
+    Ok(CrossChainOutgoingFeePreviewResponse {
+        rate,
+        fee_amount,
+        cross_chain_amount,
+    })
+}
+
+fn validate_cross_chain_outgoing_fee_rate(rate: Decimal) -> StdResult<()> {
+    if rate > Decimal::one() {
+        return Err(StdError::generic_err("fee rate must not exceed 1"));
+    }
+    Ok(())
+}
+
+pub fn update_cross_chain_outgoing_fee_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    update: CrossChainOutgoingFeeScheduleUpdate,
+) -> StdResult<Response> {
+    ADMIN
+        .assert_admin(deps.as_ref(), &info.sender)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let mut config = CROSS_CHAIN_OUTGOING_FEE_CONFIG.load(deps.storage)?;
+    if let Some(rate) = update.default_rate {
+        validate_cross_chain_outgoing_fee_rate(rate)?;
+        config.rate = rate;
+    }
+    if let Some(fee_collector_addr) = update.fee_collector_addr {
+        config.fee_collector_addr = deps.api.addr_validate(&fee_collector_addr)?;
+    }
+    CROSS_CHAIN_OUTGOING_FEE_CONFIG.save(deps.storage, &config)?;
+
+    for rate_override in update.rate_overrides {
+        let asset_info_key = get_asset_info_key(&rate_override.asset_info);
+        let key = (
+            U16Key::from(rate_override.recipient_chain_id),
+            &asset_info_key[..],
+        );
+        match rate_override.rate {
+            Some(rate) => {
+                validate_cross_chain_outgoing_fee_rate(rate)?;
+                CROSS_CHAIN_OUTGOING_FEE_RATE_OVERRIDES.save(deps.storage, key, &rate)?;
+            }
+            None => CROSS_CHAIN_OUTGOING_FEE_RATE_OVERRIDES.remove(deps.storage, key),
+        }
+    }
+    for fee_bounds in update.fee_bounds {
+        let asset_info_key = get_asset_info_key(&fee_bounds.asset_info);
+        match fee_bounds.bounds {
+            Some(bounds) => {
+                if matches!(bounds.max_fee, Some(max_fee) if max_fee < bounds.min_fee) {
+                    return Err(StdError::generic_err(
+                        "max_fee must not be less than min_fee",
+                    ));
+                }
+                CROSS_CHAIN_OUTGOING_FEE_BOUNDS.save(deps.storage, &asset_info_key, &bounds)?;
+            }
+            None => CROSS_CHAIN_OUTGOING_FEE_BOUNDS.remove(deps.storage, &asset_info_key),
+        }
+    }
+    for addr in update.exempt_addrs_to_add {
+        CROSS_CHAIN_OUTGOING_FEE_EXEMPT_ADDRS.save(
+            deps.storage,
+            &deps.api.addr_validate(&addr)?,
+            &(),
+        )?;
+    }
+    for addr in update.exempt_addrs_to_remove {
+        CROSS_CHAIN_OUTGOING_FEE_EXEMPT_ADDRS.remove(deps.storage, &deps.api.addr_validate(&addr)?);
+    }
+    Ok(Response::default())
+}
+
+#[test]
+fn test_cross_chain_outgoing_fee_schedule() {
+    use crate::state::CrossChainOutgoingFeeConfig;
+    use aperture_common::terra_manager::{
+        CrossChainOutgoingFeeBounds, CrossChainOutgoingFeeBoundsUpdate,
+        CrossChainOutgoingFeeRateOverride,
+    };
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+
+    let mut deps = mock_dependencies(&[]);
+    ADMIN
+        .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+        .unwrap();
+    CROSS_CHAIN_OUTGOING_FEE_CONFIG
+        .save(
+            deps.as_mut().storage,
+            &CrossChainOutgoingFeeConfig {
+                rate: Decimal::from_ratio(1u128, 1000u128),
+                fee_collector_addr: Addr::unchecked("terra1collector"),
+            },
+        )
+        .unwrap();
+    let uusd = AssetInfo::NativeToken {
+        denom: String::from("uusd"),
+    };
+    let uusd_asset = |amount: u128| Asset {
+        info: uusd.clone(),
+        amount: Uint128::from(amount),
+    };
+    let fee = |deps: Deps, sender: &str, recipient_chain_id: ChainId, amount: u128| {
+        compute_cross_chain_outgoing_fee(
+            deps,
+            &Addr::unchecked(sender),
+            recipient_chain_id,
+            &uusd_asset(amount),
+        )
+    };
+    assert_eq!(
+        fee(deps.as_ref(), "sender", 5, 10000),
+        Ok(CrossChainOutgoingFeePreviewResponse {
+            rate: Decimal::from_ratio(1u128, 1000u128),
+            fee_amount: Uint128::from(10u128),
+            cross_chain_amount: Uint128::from(9990u128),
+        })
+    );
+
+    // Unauthorized call.
+    assert_eq!(
+        update_cross_chain_outgoing_fee_schedule(
+            deps.as_mut(),
+            mock_info("sender", &[]),
+            CrossChainOutgoingFeeScheduleUpdate::default(),
+        ),
+        Err(StdError::generic_err("Caller is not admin"))
+    );
+
+    // Invalid rate and bounds.
+    assert_eq!(
+        update_cross_chain_outgoing_fee_schedule(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            CrossChainOutgoingFeeScheduleUpdate {
+                default_rate: Some(Decimal::from_ratio(2u128, 1u128)),
+                ..CrossChainOutgoingFeeScheduleUpdate::default()
+            },
+        ),
+        Err(StdError::generic_err("fee rate must not exceed 1"))
+    );
+    assert_eq!(
+        update_cross_chain_outgoing_fee_schedule(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            CrossChainOutgoingFeeScheduleUpdate {
+                fee_bounds: vec![CrossChainOutgoingFeeBoundsUpdate {
+                    asset_info: uusd.clone(),
+                    bounds: Some(CrossChainOutgoingFeeBounds {
+                        min_fee: Uint128::from(10u128),
+                        max_fee: Some(Uint128::from(9u128)),
+                    }),
+                }],
+                ..CrossChainOutgoingFeeScheduleUpdate::default()
+            },
+        ),
+        Err(StdError::generic_err(
+            "max_fee must not be less than min_fee"
+        ))
+    );
+
+    // Override the rate of uusd transfers to chain 5 with 1%, and bound uusd fees to [5, 50].
+    update_cross_chain_outgoing_fee_schedule(
+        deps.as_mut(),
+        mock_info("admin", &[]),
+        CrossChainOutgoingFeeScheduleUpdate {
+            default_rate: Some(Decimal::from_ratio(2u128, 1000u128)),
+            fee_collector_addr: Some(String::from("terra1newcollector")),
+            rate_overrides: vec![CrossChainOutgoingFeeRateOverride {
+                recipient_chain_id: 5,
+                asset_info: uusd.clone(),
+                rate: Some(Decimal::percent(1)),
+            }],
+            fee_bounds: vec![CrossChainOutgoingFeeBoundsUpdate {
+                asset_info: uusd.clone(),
+                bounds: Some(CrossChainOutgoingFeeBounds {
+                    min_fee: Uint128::from(5u128),
+                    max_fee: Some(Uint128::from(50u128)),
+                }),
+            }],
+            exempt_addrs_to_add: vec![String::from("terra1strategy")],
+            ..CrossChainOutgoingFeeScheduleUpdate::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        CROSS_CHAIN_OUTGOING_FEE_CONFIG
+            .load(deps.as_ref().storage)
+            .unwrap(),
+        CrossChainOutgoingFeeConfig {
+            rate: Decimal::from_ratio(2u128, 1000u128),
+            fee_collector_addr: Addr::unchecked("terra1newcollector"),
+        }
+    );
+    assert_eq!(
+        fee(deps.as_ref(), "sender", 5, 1000).unwrap().fee_amount,
+        Uint128::from(10u128)
+    );
+    // The default rate applies to other chains; fees are clamped to the bounds.
+    assert_eq!(
+        fee(deps.as_ref(), "sender", 2, 10000).unwrap().fee_amount,
+        Uint128::from(20u128)
+    );
+    assert_eq!(
+        fee(deps.as_ref(), "sender", 2, 1000).unwrap().fee_amount,
+        Uint128::from(5u128)
+    );
+    assert_eq!(
+        fee(deps.as_ref(), "sender", 5, 100000).unwrap(),
+        CrossChainOutgoingFeePreviewResponse {
+            rate: Decimal::percent(1),
+            fee_amount: Uint128::from(50u128),
+            cross_chain_amount: Uint128::from(99950u128),
+        }
+    );
+    assert_eq!(
+        fee(deps.as_ref(), "sender", 2, 4),
+        Err(StdError::generic_err(
+            "transfer amount is less than the cross-chain outgoing fee"
+        ))
+    );
+    // Exempt senders are not charged, regardless of the minimum fee.
+    assert_eq!(
+        fee(deps.as_ref(), "terra1strategy", 5, 4),
+        Ok(CrossChainOutgoingFeePreviewResponse {
+            rate: Decimal::zero(),
+            fee_amount: Uint128::zero(),
+            cross_chain_amount: Uint128::from(4u128),
+        })
+    );
+
+    // Remove the override, the bounds and the exemption.
+    update_cross_chain_outgoing_fee_schedule(
+        deps.as_mut(),
+        mock_info("admin", &[]),
+        CrossChainOutgoingFeeScheduleUpdate {
+            rate_overrides: vec![CrossChainOutgoingFeeRateOverride {
+                recipient_chain_id: 5,
+                asset_info: uusd.clone(),
+                rate: None,
+            }],
+            fee_bounds: vec![CrossChainOutgoingFeeBoundsUpdate {
+                asset_info: uusd.clone(),
+                bounds: None,
+            }],
+            exempt_addrs_to_remove: vec![String::from("terra1strategy")],
+            ..CrossChainOutgoingFeeScheduleUpdate::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        fee(deps.as_ref(), "terra1strategy", 5, 1000)
+            .unwrap()
+            .fee_amount,
+        Uint128::from(2u128)
+    );
+}
+
 /// Processes an instruction published by Aperture manager on another chain.
 ///
 /// The instruction is a generic message published by the external-chain Aperture manager via Wormhole.
@@ -496,6 +1250,34 @@ pub fn process_cross_chain_instruction(
         ));
     }
 
+    let instruction = ApertureInstruction::deserialize(&parsed_instruction_vaa.payload)?;
+    let strategy_instruction_info = match &instruction {
+        ApertureInstruction::PositionOpenInstruction { strategy_info, .. } => strategy_info,
+        ApertureInstruction::ExecuteStrategyInstruction { strategy_info, .. } => strategy_info,
+        _ => unreachable!("unsupported instruction type should not have been deserialized"),
+    };
+    let position = Position {
+        chain_id: parsed_instruction_vaa.emitter_chain,
+        position_id: strategy_instruction_info.position_id,
+    };
+
+    // Reject paused instructions before marking them as completed, so that they can be processed after unpausing.
+    let strategy_id = match &instruction {
+        ApertureInstruction::PositionOpenInstruction { strategy_id, .. } => Some(*strategy_id),
+        _ => match POSITION_TO_STRATEGY_LOCATION_MAP
+            .may_load(deps.storage, get_position_key(&position))?
+        {
+            Some(StrategyLocation::TerraChain(strategy_id)) => Some(strategy_id),
+            _ => None,
+        },
+    };
+    assert_not_paused(
+        deps.as_ref(),
+        PausableOperation::ProcessCrossChainInstruction,
+        parsed_instruction_vaa.emitter_chain,
+        strategy_id,
+    )?;
+
     // Make sure that each instruction can only be successfully processed at most once.
     let completed = COMPLETED_INSTRUCTIONS
         .load(deps.storage, parsed_instruction_vaa.hash.as_slice())
@@ -505,13 +1287,6 @@ pub fn process_cross_chain_instruction(
     }
     COMPLETED_INSTRUCTIONS.save(deps.storage, parsed_instruction_vaa.hash.as_slice(), &true)?;
 
-    let instruction = ApertureInstruction::deserialize(&parsed_instruction_vaa.payload)?;
-    let strategy_instruction_info = match &instruction {
-        ApertureInstruction::PositionOpenInstruction { strategy_info, .. } => strategy_info,
-        ApertureInstruction::ExecuteStrategyInstruction { strategy_info, .. } => strategy_info,
-        _ => unreachable!("unsupported instruction type should not have been deserialized"),
-    };
-
     if strategy_instruction_info.strategy_chain_id != TERRA_CHAIN_ID {
         return Err(StdError::generic_err(
             "instruction not intended for Terra chain",
@@ -558,10 +1333,6 @@ pub fn process_cross_chain_instruction(
         });
     }
 
-    let position = Position {
-        chain_id: parsed_instruction_vaa.emitter_chain,
-        position_id: strategy_instruction_info.position_id,
-    };
     match instruction {
         ApertureInstruction::PositionOpenInstruction {
             strategy_id,
@@ -776,11 +1547,15 @@ fn test_process_cross_chain_instruction_close_position() {
 #[test]
 fn test_process_cross_chain_instruction_open_position() {
     use crate::mock_querier::custom_mock_dependencies;
-    use crate::state::{POSITION_TO_STRATEGY_LOCATION_MAP, STRATEGY_ID_TO_METADATA_MAP};
+    use crate::state::{
+        get_pause_scope_key, PAUSE_SCOPE_TO_PAUSED_OPERATIONS, POSITION_TO_STRATEGY_LOCATION_MAP,
+        STRATEGY_ID_TO_METADATA_MAP,
+    };
     use aperture_common::common::{
         get_position_key, Action, StrategyLocation, StrategyPositionManagerExecuteMsg,
     };
     use aperture_common::delta_neutral_position_manager::DeltaNeutralParams;
+    use aperture_common::terra_manager::PauseScope;
     use cosmwasm_std::testing::mock_env;
     use cosmwasm_std::{Addr, Decimal, Uint64};
     use cw_storage_plus::U64Key;
@@ -831,10 +1606,44 @@ fn test_process_cross_chain_instruction_open_position() {
         .unwrap();
 
     let token_transfer_vaa = Binary::from_base64("AQAAAAABADhqQkDb0KlwGvLA9fpBZrOKaa4ty35jXC7lG6zz9dNteb73ItRp5UMS5smzOEX4Xi6VwNhU4/dqHNQGrwW6xCMBYeyVsQDzszEnEQAAAAAAAAAAAAAAAPF0+ag3U2xEkyHfHKCTu5aUjVOGAAAAAAAAARYPAQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAjw0YAAQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHV1c2QAAwAAAAAAAAAAAAAAAOAGQQe87Y6/y/IuPqR8pYBQYEmNAAMAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==").unwrap();
+    let instruction_vaa = Binary::from_base64("AQAAAAABAOWWxynoIu8CJjRjj0bHcPFCytTQ4n9XjmciENEboHToc1vvZkvNK706tUbbGDD3cgE9+qdaiktDkhipuquaLPAAYeyVsQAUNfQnEQAAAAAAAAAAAAAAAIK+d4I7Vr6wVD6adpogZ4Zs4Q0OAAAAAAAAAAABAAAAAAAAAAAAAAAAAAAAAAADAAAAAAAAAAAAAAABAAAAAAAAARYAAAFcZXdvSkltOXdaVzVmY0c5emFYUnBiMjRpT2lCN0Nna0pJbVJoZEdFaU9pQWlaWGR2WjBsRFFXZEpibEpvWTIxa2JHUkdPWFJoVnpWbVdUSTVjMkpIUmpCYVdFcG9Za1k1ZVZsWVVuQmllVWsyU1VOSmVVeHFUV2xNUVc5blNVTkJaMGx1VW1oamJXUnNaRVk1ZEZsWWFHWlpNamx6WWtkR01GcFlTbWhpUmpsNVdWaFNjR0o1U1RaSlEwbDVUR3BqYVV4QmIyZEpRMEZuU1cweGNHTnVTblpqYkRsb1l6Tk9iR1JHT1dwa2VrbDNXREpHYTFwSVNXbFBhVUZwWkVkV2VXTnRSWGhsV0Uwd1draGtNMlZ0Um14aWJYQnVUVzFrTlUxRVNuUmpNbmgwV1hwck1scHFTVEpPTTJneVkwaE9jVmxZVVROYU0yZHBRMjR3UFNJS0NYMEtmUT09").unwrap();
+
+    // A paused instruction is rejected without being marked as completed.
+    PAUSE_SCOPE_TO_PAUSED_OPERATIONS
+        .save(
+            deps.as_mut().storage,
+            &get_pause_scope_key(&PauseScope::Chain { chain_id: 10001 }),
+            &vec![PausableOperation::ProcessCrossChainInstruction],
+        )
+        .unwrap();
+    assert_eq!(
+        process_cross_chain_instruction(
+            deps.as_mut(),
+            mock_env(),
+            instruction_vaa.clone(),
+            vec![token_transfer_vaa.clone()],
+        )
+        .unwrap_err(),
+        StdError::generic_err("operation is paused")
+    );
+    assert!(COMPLETED_INSTRUCTIONS
+        .keys(
+            deps.as_ref().storage,
+            None,
+            None,
+            cosmwasm_std::Order::Ascending
+        )
+        .next()
+        .is_none());
+    PAUSE_SCOPE_TO_PAUSED_OPERATIONS.remove(
+        deps.as_mut().storage,
+        &get_pause_scope_key(&PauseScope::Chain { chain_id: 10001 }),
+    );
+
     let response = process_cross_chain_instruction(
         deps.as_mut(),
         mock_env(),
-        Binary::from_base64("AQAAAAABAOWWxynoIu8CJjRjj0bHcPFCytTQ4n9XjmciENEboHToc1vvZkvNK706tUbbGDD3cgE9+qdaiktDkhipuquaLPAAYeyVsQAUNfQnEQAAAAAAAAAAAAAAAIK+d4I7Vr6wVD6adpogZ4Zs4Q0OAAAAAAAAAAABAAAAAAAAAAAAAAAAAAAAAAADAAAAAAAAAAAAAAABAAAAAAAAARYAAAFcZXdvSkltOXdaVzVmY0c5emFYUnBiMjRpT2lCN0Nna0pJbVJoZEdFaU9pQWlaWGR2WjBsRFFXZEpibEpvWTIxa2JHUkdPWFJoVnpWbVdUSTVjMkpIUmpCYVdFcG9Za1k1ZVZsWVVuQmllVWsyU1VOSmVVeHFUV2xNUVc5blNVTkJaMGx1VW1oamJXUnNaRVk1ZEZsWWFHWlpNamx6WWtkR01GcFlTbWhpUmpsNVdWaFNjR0o1U1RaSlEwbDVUR3BqYVV4QmIyZEpRMEZuU1cweGNHTnVTblpqYkRsb1l6Tk9iR1JHT1dwa2VrbDNXREpHYTFwSVNXbFBhVUZwWkVkV2VXTnRSWGhsV0Uwd1draGtNMlZ0Um14aWJYQnVUVzFrTlUxRVNuUmpNbmgwV1hwck1scHFTVEpPTTJneVkwaE9jVmxZVVROYU0yZHBRMjR3UFNJS0NYMEtmUT09").unwrap(),
+        instruction_vaa,
         vec![token_transfer_vaa.clone()],
     )
     .unwrap();
@@ -868,6 +1677,9 @@ fn test_process_cross_chain_instruction_open_position() {
                                 "terra1ys4dwwzaenjg2gy02mslmc96f267xvpsjat7gx"
                             ),
                             allow_off_market_position_open: None,
+                            liquidity_venue: None,
+                            collateral_asset_info: None,
+                            spec_reward_policy: None,
                         })
                         .unwrap()
                     ),
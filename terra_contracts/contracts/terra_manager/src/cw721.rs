@@ -0,0 +1,401 @@
+use aperture_common::byte_util::ByteUtils;
+use aperture_common::common::{get_position_key, Position, PositionId, StrategyLocation};
+use aperture_common::cw721::{
+    Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse, Cw721ReceiveMsg,
+    NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse, PositionNftExtension,
+    TokensResponse,
+};
+use aperture_common::delta_neutral_position_manager::{
+    BatchGetPositionInfoResponse, QueryMsg as DeltaNeutralPositionManagerQueryMsg,
+};
+use aperture_common::terra_manager::TERRA_CHAIN_ID;
+use cosmwasm_std::{
+    Addr, Binary, BlockInfo, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+    Uint128,
+};
+use cw20::Expiration;
+use cw_storage_plus::{Bound, PrimaryKey, U128Key};
+
+use crate::state::{
+    get_strategy_id_key, HOLDER_OPERATORS, HOLDER_POSITION_ID_PAIR_SET, NEXT_POSITION_ID,
+    POSITION_APPROVALS, POSITION_ID_TO_HOLDER, POSITION_TO_STRATEGY_LOCATION_MAP,
+    STRATEGY_ID_TO_METADATA_MAP,
+};
+use crate::terra_chain::change_position_holder;
+
+const CONTRACT_NAME: &str = "Aperture Position";
+const CONTRACT_SYMBOL: &str = "APOS";
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// Token ids are the decimal string representation of position ids.
+fn parse_token_id(token_id: &str) -> StdResult<PositionId> {
+    token_id
+        .parse::<u128>()
+        .map(Uint128::from)
+        .map_err(|_| StdError::generic_err("invalid token_id"))
+}
+
+fn is_operator(deps: Deps, block: &BlockInfo, holder: &Addr, operator: &Addr) -> StdResult<bool> {
+    let expires = HOLDER_OPERATORS.may_load(deps.storage, (holder.clone(), operator.clone()))?;
+    Ok(matches!(expires, Some(expires) if !expires.is_expired(block)))
+}
+
+// The holder and unexpired operators of the holder may approve spenders for the holder's positions.
+fn check_can_approve(deps: Deps, env: &Env, holder: &Addr, sender: &Addr) -> StdResult<()> {
+    if sender == holder || is_operator(deps, &env.block, holder, sender)? {
+        Ok(())
+    } else {
+        Err(StdError::generic_err("unauthorized"))
+    }
+}
+
+// In addition to those who can approve, unexpired approved spenders of the position may transfer it.
+fn check_can_send(
+    deps: Deps,
+    env: &Env,
+    position_id: PositionId,
+    holder: &Addr,
+    sender: &Addr,
+) -> StdResult<()> {
+    if check_can_approve(deps, env, holder, sender).is_ok() {
+        return Ok(());
+    }
+    let expires = POSITION_APPROVALS.may_load(
+        deps.storage,
+        (U128Key::from(position_id.u128()), sender.clone()),
+    )?;
+    if matches!(expires, Some(expires) if !expires.is_expired(&env.block)) {
+        Ok(())
+    } else {
+        Err(StdError::generic_err("unauthorized"))
+    }
+}
+
+fn transfer_position_nft(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: &str,
+    token_id: &str,
+) -> StdResult<Response> {
+    let position_id = parse_token_id(token_id)?;
+    let holder = POSITION_ID_TO_HOLDER.load(deps.storage, U128Key::from(position_id.u128()))?;
+    check_can_send(deps.as_ref(), env, position_id, &holder, &info.sender)?;
+    let recipient = deps.api.addr_validate(recipient)?;
+    change_position_holder(deps, position_id, holder, recipient)
+}
+
+pub fn transfer_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> StdResult<Response> {
+    Ok(
+        transfer_position_nft(deps, &env, &info, &recipient, &token_id)?
+            .add_attribute("action", "transfer_nft")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", recipient)
+            .add_attribute("token_id", token_id),
+    )
+}
+
+pub fn send_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> StdResult<Response> {
+    let response = transfer_position_nft(deps, &env, &info, &contract, &token_id)?;
+    Ok(response
+        .add_message(
+            Cw721ReceiveMsg {
+                sender: info.sender.to_string(),
+                token_id: token_id.clone(),
+                msg,
+            }
+            .into_cosmos_msg(contract.clone())?,
+        )
+        .add_attribute("action", "send_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", contract)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> StdResult<Response> {
+    let position_id = parse_token_id(&token_id)?;
+    let position_id_key = U128Key::from(position_id.u128());
+    let holder = POSITION_ID_TO_HOLDER.load(deps.storage, position_id_key.clone())?;
+    check_can_approve(deps.as_ref(), &env, &holder, &info.sender)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(StdError::generic_err("expiration already passed"));
+    }
+    let spender = deps.api.addr_validate(&spender)?;
+    POSITION_APPROVALS.save(deps.storage, (position_id_key, spender.clone()), &expires)?;
+    Ok(Response::new()
+        .add_attribute("action", "approve")
+        .add_attribute("sender", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+) -> StdResult<Response> {
+    let position_id = parse_token_id(&token_id)?;
+    let position_id_key = U128Key::from(position_id.u128());
+    let holder = POSITION_ID_TO_HOLDER.load(deps.storage, position_id_key.clone())?;
+    check_can_approve(deps.as_ref(), &env, &holder, &info.sender)?;
+    let spender = deps.api.addr_validate(&spender)?;
+    POSITION_APPROVALS.remove(deps.storage, (position_id_key, spender.clone()));
+    Ok(Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("sender", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn approve_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> StdResult<Response> {
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(StdError::generic_err("expiration already passed"));
+    }
+    let operator = deps.api.addr_validate(&operator)?;
+    HOLDER_OPERATORS.save(
+        deps.storage,
+        (info.sender.clone(), operator.clone()),
+        &expires,
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "approve_all")
+        .add_attribute("sender", info.sender)
+        .add_attribute("operator", operator))
+}
+
+pub fn revoke_all(deps: DepsMut, info: MessageInfo, operator: String) -> StdResult<Response> {
+    let operator = deps.api.addr_validate(&operator)?;
+    HOLDER_OPERATORS.remove(deps.storage, (info.sender.clone(), operator.clone()));
+    Ok(Response::new()
+        .add_attribute("action", "revoke_all")
+        .add_attribute("sender", info.sender)
+        .add_attribute("operator", operator))
+}
+
+fn get_position_approvals(
+    deps: Deps,
+    env: &Env,
+    position_id: PositionId,
+    include_expired: bool,
+) -> StdResult<Vec<Approval>> {
+    let mut approvals = vec![];
+    for item in POSITION_APPROVALS
+        .prefix(U128Key::from(position_id.u128()))
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (spender, expires) = item?;
+        if include_expired || !expires.is_expired(&env.block) {
+            approvals.push(Approval {
+                spender: String::from_utf8(spender)?,
+                expires,
+            });
+        }
+    }
+    Ok(approvals)
+}
+
+pub fn query_owner_of(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<OwnerOfResponse> {
+    let position_id = parse_token_id(&token_id)?;
+    Ok(OwnerOfResponse {
+        owner: POSITION_ID_TO_HOLDER
+            .load(deps.storage, U128Key::from(position_id.u128()))?
+            .to_string(),
+        approvals: get_position_approvals(deps, &env, position_id, include_expired)?,
+    })
+}
+
+pub fn query_approval(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    spender: String,
+    include_expired: bool,
+) -> StdResult<ApprovalResponse> {
+    let position_id = parse_token_id(&token_id)?;
+    let holder = POSITION_ID_TO_HOLDER.load(deps.storage, U128Key::from(position_id.u128()))?;
+    // The holder is implicitly approved.
+    if holder == spender {
+        return Ok(ApprovalResponse {
+            approval: Approval {
+                spender,
+                expires: Expiration::Never {},
+            },
+        });
+    }
+    get_position_approvals(deps, &env, position_id, include_expired)?
+        .into_iter()
+        .find(|approval| approval.spender == spender)
+        .map(|approval| ApprovalResponse { approval })
+        .ok_or_else(|| StdError::generic_err("approval not found"))
+}
+
+pub fn query_approvals(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<ApprovalsResponse> {
+    let position_id = parse_token_id(&token_id)?;
+    POSITION_ID_TO_HOLDER.load(deps.storage, U128Key::from(position_id.u128()))?;
+    Ok(ApprovalsResponse {
+        approvals: get_position_approvals(deps, &env, position_id, include_expired)?,
+    })
+}
+
+pub fn query_all_operators(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorsResponse> {
+    let min = start_after.map(|operator| Bound::Exclusive(operator.into_bytes()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut operators = vec![];
+    for item in HOLDER_OPERATORS
+        .prefix(deps.api.addr_validate(&owner)?)
+        .range(deps.storage, min, None, Order::Ascending)
+    {
+        if operators.len() == limit {
+            break;
+        }
+        let (operator, expires) = item?;
+        if include_expired || !expires.is_expired(&env.block) {
+            operators.push(Approval {
+                spender: String::from_utf8(operator)?,
+                expires,
+            });
+        }
+    }
+    Ok(OperatorsResponse { operators })
+}
+
+// Positions are never burned, so every position ever created by a Terra address is counted.
+pub fn query_num_tokens(deps: Deps) -> StdResult<NumTokensResponse> {
+    Ok(NumTokensResponse {
+        count: NEXT_POSITION_ID.load(deps.storage)?.u128() as u64,
+    })
+}
+
+pub fn query_contract_info() -> ContractInfoResponse {
+    ContractInfoResponse {
+        name: String::from(CONTRACT_NAME),
+        symbol: String::from(CONTRACT_SYMBOL),
+    }
+}
+
+pub fn query_nft_info(deps: Deps, token_id: String) -> StdResult<NftInfoResponse> {
+    let position = Position {
+        chain_id: TERRA_CHAIN_ID,
+        position_id: parse_token_id(&token_id)?,
+    };
+    POSITION_ID_TO_HOLDER.load(deps.storage, U128Key::from(position.position_id.u128()))?;
+    let strategy_location =
+        POSITION_TO_STRATEGY_LOCATION_MAP.load(deps.storage, get_position_key(&position))?;
+    let (strategy_metadata, position_info) = match strategy_location {
+        StrategyLocation::TerraChain(strategy_id) => {
+            let strategy_metadata = STRATEGY_ID_TO_METADATA_MAP
+                .may_load(deps.storage, get_strategy_id_key(strategy_id))?;
+            let position_info = strategy_metadata.as_ref().and_then(|metadata| {
+                deps.querier
+                    .query_wasm_smart::<BatchGetPositionInfoResponse>(
+                        metadata.manager_addr.to_string(),
+                        &DeltaNeutralPositionManagerQueryMsg::BatchGetPositionInfo {
+                            positions: Some(vec![position.clone()]),
+                            ranges: None,
+                        },
+                    )
+                    .ok()
+            });
+            (strategy_metadata, position_info)
+        }
+        StrategyLocation::ExternalChain(_) => (None, None),
+    };
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: PositionNftExtension {
+            strategy_location,
+            strategy_metadata,
+            position_info,
+        },
+    })
+}
+
+pub fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let min = match start_after {
+        Some(token_id) => Some(Bound::Exclusive(
+            U128Key::from(parse_token_id(&token_id)?.u128()).joined_key(),
+        )),
+        None => None,
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let tokens = HOLDER_POSITION_ID_PAIR_SET
+        .prefix(deps.api.addr_validate(&owner)?)
+        .keys(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|key| key.as_slice().get_u128_be(0).to_string())
+        .collect();
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_all_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let min = match start_after {
+        Some(token_id) => Some(Bound::Exclusive(
+            U128Key::from(parse_token_id(&token_id)?.u128()).joined_key(),
+        )),
+        None => None,
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let tokens = POSITION_ID_TO_HOLDER
+        .keys(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|key| key.as_slice().get_u128_be(0).to_string())
+        .collect();
+    Ok(TokensResponse { tokens })
+}
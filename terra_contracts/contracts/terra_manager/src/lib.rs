@@ -1,5 +1,7 @@
 pub mod contract;
 pub mod cross_chain;
+pub mod cw721;
+pub mod pause;
 pub mod state;
 pub mod terra_chain;
 pub mod util;
@@ -174,6 +174,9 @@ impl WasmMockQuerier {
                                     target_max_collateral_ratio: Decimal::from_ratio(27u128, 10u128),
                                     mirror_asset_cw20_addr: String::from("terra1ys4dwwzaenjg2gy02mslmc96f267xvpsjat7gx"),
                                     allow_off_market_position_open: None,
+                                    liquidity_venue: None,
+                                    collateral_asset_info: None,
+                                    spec_reward_policy: None,
                                 }).unwrap()),
                             }.serialize().unwrap(),
                             hash: vec![
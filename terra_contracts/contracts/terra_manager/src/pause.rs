@@ -0,0 +1,98 @@
+use aperture_common::common::{ChainId, StrategyId};
+use aperture_common::terra_manager::{PausableOperation, PauseScope};
+use cosmwasm_std::{Addr, Deps, DepsMut, MessageInfo, Response, StdError, StdResult};
+
+use crate::state::{get_pause_scope_key, ADMIN, PAUSER_ADDR, PAUSE_SCOPE_TO_PAUSED_OPERATIONS};
+
+pub fn update_pauser(
+    deps: DepsMut,
+    info: MessageInfo,
+    pauser_addr: Option<String>,
+) -> StdResult<Response> {
+    ADMIN
+        .assert_admin(deps.as_ref(), &info.sender)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    match pauser_addr {
+        Some(pauser_addr) => {
+            PAUSER_ADDR.save(deps.storage, &deps.api.addr_validate(&pauser_addr)?)?
+        }
+        None => PAUSER_ADDR.remove(deps.storage),
+    }
+    Ok(Response::default())
+}
+
+pub fn pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    scope: PauseScope,
+    operations: Vec<PausableOperation>,
+) -> StdResult<Response> {
+    let is_pauser = PAUSER_ADDR.may_load(deps.storage)?.as_ref() == Some(&info.sender);
+    if !is_pauser && !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    let scope_key = get_pause_scope_key(&scope);
+    let mut paused_operations = PAUSE_SCOPE_TO_PAUSED_OPERATIONS
+        .may_load(deps.storage, &scope_key)?
+        .unwrap_or_default();
+    for operation in operations {
+        if !paused_operations.contains(&operation) {
+            paused_operations.push(operation);
+        }
+    }
+    PAUSE_SCOPE_TO_PAUSED_OPERATIONS.save(deps.storage, &scope_key, &paused_operations)?;
+    Ok(Response::default())
+}
+
+pub fn unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    scope: PauseScope,
+    operations: Vec<PausableOperation>,
+) -> StdResult<Response> {
+    ADMIN
+        .assert_admin(deps.as_ref(), &info.sender)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let scope_key = get_pause_scope_key(&scope);
+    let paused_operations: Vec<PausableOperation> = PAUSE_SCOPE_TO_PAUSED_OPERATIONS
+        .may_load(deps.storage, &scope_key)?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|operation| !operations.contains(operation))
+        .collect();
+    if paused_operations.is_empty() {
+        PAUSE_SCOPE_TO_PAUSED_OPERATIONS.remove(deps.storage, &scope_key);
+    } else {
+        PAUSE_SCOPE_TO_PAUSED_OPERATIONS.save(deps.storage, &scope_key, &paused_operations)?;
+    }
+    Ok(Response::default())
+}
+
+pub fn query_pauser(deps: Deps) -> StdResult<Option<Addr>> {
+    PAUSER_ADDR.may_load(deps.storage)
+}
+
+pub fn query_paused_operations(deps: Deps, scope: PauseScope) -> StdResult<Vec<PausableOperation>> {
+    Ok(PAUSE_SCOPE_TO_PAUSED_OPERATIONS
+        .may_load(deps.storage, &get_pause_scope_key(&scope))?
+        .unwrap_or_default())
+}
+
+// Returns an error if `operation` is paused globally, on `chain_id`, or for `strategy_id` if the operation concerns a Terra strategy.
+pub fn assert_not_paused(
+    deps: Deps,
+    operation: PausableOperation,
+    chain_id: ChainId,
+    strategy_id: Option<StrategyId>,
+) -> StdResult<()> {
+    let mut scopes = vec![PauseScope::Global, PauseScope::Chain { chain_id }];
+    if let Some(strategy_id) = strategy_id {
+        scopes.push(PauseScope::Strategy { strategy_id });
+    }
+    for scope in scopes {
+        if query_paused_operations(deps, scope)?.contains(&operation) {
+            return Err(StdError::generic_err("operation is paused"));
+        }
+    }
+    Ok(())
+}
@@ -1,13 +1,29 @@
 use aperture_common::common::{
     PositionId, PositionKey, StrategyId, StrategyLocation, StrategyMetadata,
 };
+use aperture_common::terra_manager::{
+    AdminOperation, CrossChainOutgoingFeeBounds, PausableOperation, PauseScope,
+    PositionOperatorGrant, StrategyPositionIndexProgress, StrategyStatus,
+};
+use aperture_common::timelock::Timelock;
 use cosmwasm_std::{Addr, Decimal};
+use cw20::Expiration;
 use cw_controllers::Admin;
 use cw_storage_plus::{Item, Map, U128Key, U16Key, U64Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use terraswap::asset::AssetInfo;
 
 pub const ADMIN: Admin = Admin::new("admin");
+// Administrator nominated through `UpdateAdminAddr`, who takes over once they call `AcceptAdmin`.
+pub const PENDING_ADMIN_ADDR: Item<Addr> = Item::new("pending_admin_addr");
+pub const TIMELOCK: Timelock<AdminOperation> = Timelock::new(
+    "timelock_delay_seconds",
+    "next_admin_operation_proposal_id",
+    "admin_operation_proposals",
+);
+// Address that can pause operations alongside the administrator; see `crate::pause`.
+pub const PAUSER_ADDR: Item<Addr> = Item::new("pauser_addr");
 pub const WORMHOLE_TOKEN_BRIDGE_ADDR: Item<Addr> = Item::new("wormhole_token_bridge_addr");
 pub const WORMHOLE_CORE_BRIDGE_ADDR: Item<Addr> = Item::new("wormhole_core_bridge_addr");
 
@@ -15,18 +31,56 @@ pub type StrategyIdKey = U64Key;
 pub const NEXT_STRATEGY_ID: Item<StrategyId> = Item::new("next_strategy_id");
 pub const STRATEGY_ID_TO_METADATA_MAP: Map<StrategyIdKey, StrategyMetadata> =
     Map::new("strategy_id_to_metadata_map");
+// Strategies without an entry, e.g. those added before lifecycle statuses were introduced, are active.
+pub const STRATEGY_ID_TO_STATUS_MAP: Map<StrategyIdKey, StrategyStatus> =
+    Map::new("strategy_id_to_status_map");
 
 pub const NEXT_POSITION_ID: Item<PositionId> = Item::new("next_position_id");
 pub const POSITION_TO_STRATEGY_LOCATION_MAP: Map<PositionKey, StrategyLocation> =
     Map::new("position_to_strategy_location_map");
+// Reverse index of POSITION_TO_STRATEGY_LOCATION_MAP for positions in Terra strategies, regardless of the chain the position is held on.
+pub const STRATEGY_POSITION_SET: Map<(StrategyIdKey, PositionKey), ()> =
+    Map::new("strategy_position_set");
+pub const STRATEGY_ID_TO_POSITION_COUNT_MAP: Map<StrategyIdKey, u64> =
+    Map::new("strategy_id_to_position_count_map");
+// Progress of indexing positions opened before STRATEGY_POSITION_SET was introduced; see `crate::terra_chain::index_existing_strategy_positions`.
+pub const STRATEGY_POSITION_INDEX_PROGRESS: Item<StrategyPositionIndexProgress> =
+    Item::new("strategy_position_index_progress");
 pub const POSITION_ID_TO_HOLDER: Map<U128Key, Addr> = Map::new("position_id_to_holder_map");
 pub const HOLDER_POSITION_ID_PAIR_SET: Map<(Addr, U128Key), ()> =
     Map::new("holder_position_id_pair_set");
+// Map from position id to the recipient of its pending transfer that has yet to be accepted.
+pub const POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT: Map<U128Key, Addr> =
+    Map::new("position_id_to_pending_transfer_recipient_map");
+
+// cw721 approvals: (position id, spender) -> expiration, and (holder, operator) -> expiration.
+pub const POSITION_APPROVALS: Map<(U128Key, Addr), Expiration> = Map::new("position_approvals");
+pub const HOLDER_OPERATORS: Map<(Addr, Addr), Expiration> = Map::new("holder_operators");
+
+// Delegated rights to execute strategy actions: (position id, operator) -> grant, and (holder, operator) -> grant covering all positions of the holder.
+pub const POSITION_ACTION_OPERATORS: Map<(U128Key, Addr), PositionOperatorGrant> =
+    Map::new("position_action_operators");
+pub const HOLDER_ACTION_OPERATORS: Map<(Addr, Addr), PositionOperatorGrant> =
+    Map::new("holder_action_operators");
 
 pub fn get_strategy_id_key(strategy_id: StrategyId) -> StrategyIdKey {
     StrategyIdKey::from(strategy_id.u64())
 }
 
+// Map from encoded pause scope to the operations paused within that scope.
+pub const PAUSE_SCOPE_TO_PAUSED_OPERATIONS: Map<&[u8], Vec<PausableOperation>> =
+    Map::new("pause_scope_to_paused_operations");
+
+pub fn get_pause_scope_key(scope: &PauseScope) -> Vec<u8> {
+    match scope {
+        PauseScope::Global => vec![0u8],
+        PauseScope::Strategy { strategy_id } => {
+            [&[1u8], &strategy_id.u64().to_be_bytes()[..]].concat()
+        }
+        PauseScope::Chain { chain_id } => [&[2u8], &chain_id.to_be_bytes()[..]].concat(),
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct CrossChainOutgoingFeeConfig {
@@ -35,6 +89,21 @@ pub struct CrossChainOutgoingFeeConfig {
 }
 pub const CROSS_CHAIN_OUTGOING_FEE_CONFIG: Item<CrossChainOutgoingFeeConfig> =
     Item::new("cross_chain_outgoing_fee_config");
+// Overrides of `CrossChainOutgoingFeeConfig::rate` keyed by (recipient chain id, encoded asset info).
+pub const CROSS_CHAIN_OUTGOING_FEE_RATE_OVERRIDES: Map<(U16Key, &[u8]), Decimal> =
+    Map::new("cross_chain_outgoing_fee_rate_overrides");
+// Map from encoded asset info to the bounds of the fee charged on that asset.
+pub const CROSS_CHAIN_OUTGOING_FEE_BOUNDS: Map<&[u8], CrossChainOutgoingFeeBounds> =
+    Map::new("cross_chain_outgoing_fee_bounds");
+pub const CROSS_CHAIN_OUTGOING_FEE_EXEMPT_ADDRS: Map<&Addr, ()> =
+    Map::new("cross_chain_outgoing_fee_exempt_addrs");
+
+pub fn get_asset_info_key(asset_info: &AssetInfo) -> Vec<u8> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } => [&[0u8], denom.as_bytes()].concat(),
+        AssetInfo::Token { contract_addr } => [&[1u8], contract_addr.as_bytes()].concat(),
+    }
+}
 
 // Map from Wormhole chain id to the Aperture manager contract address (32 bytes) on the keyed chain.
 pub const CHAIN_ID_TO_APERTURE_MANAGER_ADDRESS_MAP: Map<U16Key, [u8; 32]> =
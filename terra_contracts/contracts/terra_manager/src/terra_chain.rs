@@ -1,22 +1,33 @@
+use aperture_common::byte_util::ByteUtils;
 use aperture_common::common::{
-    get_position_key, Action, Position, PositionId, Strategy, StrategyId, StrategyLocation,
-    StrategyMetadata, StrategyPositionManagerExecuteMsg,
+    get_position_from_key, get_position_key, Action, Position, PositionId, Recipient, Strategy,
+    StrategyId, StrategyLocation, StrategyMetadata, StrategyPositionManagerExecuteMsg,
 };
 use aperture_common::token_util::{
     forward_assets_direct, validate_and_accept_incoming_asset_transfer,
 };
 use cosmwasm_std::{
-    to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
-    Uint64, WasmMsg,
+    to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, Event, MessageInfo, Order, Response,
+    StdError, StdResult, Storage, Uint64, WasmMsg,
 };
-use cw_storage_plus::U128Key;
+use cw_storage_plus::{Bound, PrimaryKey, U128Key};
 use terraswap::asset::Asset;
 
+use crate::cross_chain::{execute_external_chain_strategy, open_external_chain_position};
+use crate::pause::assert_not_paused;
 use crate::state::{
-    get_strategy_id_key, ADMIN, HOLDER_POSITION_ID_PAIR_SET, NEXT_POSITION_ID, NEXT_STRATEGY_ID,
-    POSITION_ID_TO_HOLDER, POSITION_TO_STRATEGY_LOCATION_MAP, STRATEGY_ID_TO_METADATA_MAP,
+    get_strategy_id_key, ADMIN, HOLDER_ACTION_OPERATORS, HOLDER_POSITION_ID_PAIR_SET,
+    NEXT_POSITION_ID, NEXT_STRATEGY_ID, POSITION_ACTION_OPERATORS, POSITION_APPROVALS,
+    POSITION_ID_TO_HOLDER, POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT,
+    POSITION_TO_STRATEGY_LOCATION_MAP, STRATEGY_ID_TO_METADATA_MAP,
+    STRATEGY_ID_TO_POSITION_COUNT_MAP, STRATEGY_ID_TO_STATUS_MAP, STRATEGY_POSITION_INDEX_PROGRESS,
+    STRATEGY_POSITION_SET,
 };
-use aperture_common::terra_manager::TERRA_CHAIN_ID;
+use aperture_common::terra_manager::{
+    PausableOperation, PositionActionKind, PositionOperatorGrant, StrategiesResponse, StrategyInfo,
+    StrategyPositionInfo, StrategyPositionsResponse, StrategyStatus, TERRA_CHAIN_ID,
+};
+use cw20::Expiration;
 
 pub fn add_strategy(
     deps: DepsMut,
@@ -40,21 +51,211 @@ pub fn add_strategy(
             manager_addr: deps.api.addr_validate(&manager_addr)?,
         },
     )?;
+    STRATEGY_ID_TO_STATUS_MAP.save(
+        deps.storage,
+        get_strategy_id_key(strategy_id),
+        &StrategyStatus::Active,
+    )?;
     Ok(Response::default())
 }
 
+// Strategies are never deleted so that holders can still exit positions; removal deprecates the strategy instead.
 pub fn remove_strategy(
     deps: DepsMut,
     info: MessageInfo,
     strategy_id: Uint64,
+) -> StdResult<Response> {
+    update_strategy(
+        deps,
+        info,
+        strategy_id,
+        None,
+        None,
+        Some(StrategyStatus::Deprecated),
+    )
+}
+
+pub fn update_strategy(
+    deps: DepsMut,
+    info: MessageInfo,
+    strategy_id: Uint64,
+    version: Option<String>,
+    manager_addr: Option<String>,
+    status: Option<StrategyStatus>,
 ) -> StdResult<Response> {
     ADMIN
         .assert_admin(deps.as_ref(), &info.sender)
         .map_err(|err| StdError::generic_err(err.to_string()))?;
-    STRATEGY_ID_TO_METADATA_MAP.remove(deps.storage, get_strategy_id_key(strategy_id));
+    let strategy_id_key = get_strategy_id_key(strategy_id);
+    let mut metadata = STRATEGY_ID_TO_METADATA_MAP.load(deps.storage, strategy_id_key.clone())?;
+    if let Some(version) = version {
+        metadata.version = version;
+    }
+    if let Some(manager_addr) = manager_addr {
+        metadata.manager_addr = deps.api.addr_validate(&manager_addr)?;
+    }
+    STRATEGY_ID_TO_METADATA_MAP.save(deps.storage, strategy_id_key.clone(), &metadata)?;
+    if let Some(status) = status {
+        STRATEGY_ID_TO_STATUS_MAP.save(deps.storage, strategy_id_key, &status)?;
+    }
     Ok(Response::default())
 }
 
+pub fn get_strategy_status(deps: Deps, strategy_id: StrategyId) -> StdResult<StrategyStatus> {
+    let strategy_id_key = get_strategy_id_key(strategy_id);
+    // Make sure that the strategy exists.
+    STRATEGY_ID_TO_METADATA_MAP.load(deps.storage, strategy_id_key.clone())?;
+    Ok(STRATEGY_ID_TO_STATUS_MAP
+        .may_load(deps.storage, strategy_id_key)?
+        .unwrap_or(StrategyStatus::Active))
+}
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub fn query_strategy_position_count(deps: Deps, strategy_id: StrategyId) -> StdResult<Uint64> {
+    Ok(STRATEGY_ID_TO_POSITION_COUNT_MAP
+        .may_load(deps.storage, get_strategy_id_key(strategy_id))?
+        .unwrap_or(0)
+        .into())
+}
+
+pub fn query_strategies(
+    deps: Deps,
+    start_after: Option<StrategyId>,
+    limit: Option<u32>,
+) -> StdResult<StrategiesResponse> {
+    let min = start_after
+        .map(|strategy_id| Bound::Exclusive(get_strategy_id_key(strategy_id).joined_key()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let strategies = STRATEGY_ID_TO_METADATA_MAP
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (strategy_id_key, metadata) = item?;
+            let strategy_id = Uint64::from(strategy_id_key.as_slice().get_u64(0));
+            Ok(StrategyInfo {
+                strategy_id,
+                metadata,
+                status: get_strategy_status(deps, strategy_id)?,
+                position_count: query_strategy_position_count(deps, strategy_id)?,
+            })
+        })
+        .collect::<StdResult<Vec<StrategyInfo>>>()?;
+    Ok(StrategiesResponse { strategies })
+}
+
+pub fn query_strategy_positions(
+    deps: Deps,
+    strategy_id: StrategyId,
+    start_after: Option<Position>,
+    limit: Option<u32>,
+) -> StdResult<StrategyPositionsResponse> {
+    // Make sure that the strategy exists.
+    STRATEGY_ID_TO_METADATA_MAP.load(deps.storage, get_strategy_id_key(strategy_id))?;
+    let min =
+        start_after.map(|position| Bound::Exclusive(get_position_key(&position).joined_key()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let positions = STRATEGY_POSITION_SET
+        .prefix(get_strategy_id_key(strategy_id))
+        .keys(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|position_key| {
+            let position = get_position_from_key(&position_key)?;
+            // Holders of positions on other chains are only tracked by the Aperture manager on that chain.
+            let holder = if position.chain_id == TERRA_CHAIN_ID {
+                POSITION_ID_TO_HOLDER
+                    .may_load(deps.storage, U128Key::from(position.position_id.u128()))?
+                    .map(|holder| holder.to_string())
+            } else {
+                None
+            };
+            Ok(StrategyPositionInfo { position, holder })
+        })
+        .collect::<StdResult<Vec<StrategyPositionInfo>>>()?;
+    Ok(StrategyPositionsResponse { positions })
+}
+
+// Adds up to `limit` positions opened before the strategy -> position index was introduced to the index, resuming after the last position processed by the previous call.
+// The index is built in batches so that the number of positions does not bound the gas cost of a single transaction.
+pub fn index_existing_strategy_positions(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    const DEFAULT_INDEX_LIMIT: u32 = 100;
+    const MAX_INDEX_LIMIT: u32 = 300;
+    ADMIN
+        .assert_admin(deps.as_ref(), &info.sender)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let mut progress = STRATEGY_POSITION_INDEX_PROGRESS.load(deps.storage)?;
+    if !progress.done {
+        let limit = limit.unwrap_or(DEFAULT_INDEX_LIMIT).min(MAX_INDEX_LIMIT) as usize;
+        let min = progress
+            .cursor
+            .as_ref()
+            .map(|position| Bound::Exclusive(get_position_key(position).joined_key()));
+        let entries = POSITION_TO_STRATEGY_LOCATION_MAP
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        progress.done = entries.len() < limit;
+        for (position_key, strategy_location) in entries {
+            let position = get_position_from_key(&position_key)?;
+            if let StrategyLocation::TerraChain(strategy_id) = strategy_location {
+                if index_strategy_position(deps.storage, strategy_id, &position)? {
+                    progress.indexed_count += 1;
+                }
+            }
+            progress.cursor = Some(position);
+        }
+        STRATEGY_POSITION_INDEX_PROGRESS.save(deps.storage, &progress)?;
+    }
+    Ok(Response::new()
+        .add_attribute("indexed_total", progress.indexed_count.to_string())
+        .add_attribute("done", progress.done.to_string()))
+}
+
+// Returns whether the position was newly added to the index.
+fn index_strategy_position(
+    storage: &mut dyn Storage,
+    strategy_id: StrategyId,
+    position: &Position,
+) -> StdResult<bool> {
+    let strategy_id_key = get_strategy_id_key(strategy_id);
+    let key = (strategy_id_key.clone(), get_position_key(position));
+    if STRATEGY_POSITION_SET.has(storage, key.clone()) {
+        return Ok(false);
+    }
+    STRATEGY_POSITION_SET.save(storage, key, &())?;
+    STRATEGY_ID_TO_POSITION_COUNT_MAP.update(
+        storage,
+        strategy_id_key,
+        |count| -> StdResult<u64> { Ok(count.unwrap_or(0) + 1) },
+    )?;
+    Ok(true)
+}
+
+fn check_strategy_status_allows_action(status: &StrategyStatus, action: &Action) -> StdResult<()> {
+    let allowed = match status {
+        StrategyStatus::Active => true,
+        StrategyStatus::Deprecated => matches!(
+            action,
+            Action::DecreasePosition { .. }
+                | Action::ClosePosition { .. }
+                | Action::EmergencyClosePosition { .. }
+        ),
+        StrategyStatus::Paused => matches!(action, Action::EmergencyClosePosition { .. }),
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(StdError::generic_err(
+            "action is not allowed in the current strategy status",
+        ))
+    }
+}
+
 pub fn create_position(
     deps: DepsMut,
     env: Env,
@@ -63,11 +264,16 @@ pub fn create_position(
     data: Option<Binary>,
     assets: Vec<Asset>,
 ) -> StdResult<Response> {
-    if strategy.chain_id != TERRA_CHAIN_ID {
-        return Err(StdError::generic_err(
-            "external chain strategy not yet supported",
-        ));
-    }
+    assert_not_paused(
+        deps.as_ref(),
+        PausableOperation::CreatePosition,
+        strategy.chain_id,
+        if strategy.chain_id == TERRA_CHAIN_ID {
+            Some(strategy.strategy_id)
+        } else {
+            None
+        },
+    )?;
 
     // Assign position id.
     let position_id = NEXT_POSITION_ID.load(deps.storage)?;
@@ -82,6 +288,23 @@ pub fn create_position(
         chain_id: TERRA_CHAIN_ID,
         position_id,
     };
+    if strategy.chain_id != TERRA_CHAIN_ID {
+        // Save position -> strategy mapping; the strategy id is tracked by the Aperture manager on the strategy chain.
+        POSITION_TO_STRATEGY_LOCATION_MAP.save(
+            deps.storage,
+            get_position_key(&position),
+            &StrategyLocation::ExternalChain(strategy.chain_id),
+        )?;
+        return Ok(Response::new().add_messages(open_external_chain_position(
+            deps.as_ref(),
+            env,
+            info,
+            position,
+            strategy,
+            data,
+            assets,
+        )?));
+    }
     Ok(
         Response::new().add_messages(save_new_position_info_and_open_it(
             deps,
@@ -95,6 +318,127 @@ pub fn create_position(
     )
 }
 
+pub fn transfer_position(
+    deps: DepsMut,
+    info: MessageInfo,
+    position_id: PositionId,
+    recipient: String,
+    require_acceptance: bool,
+) -> StdResult<Response> {
+    let position_id_key = U128Key::from(position_id.u128());
+    let holder = POSITION_ID_TO_HOLDER.load(deps.storage, position_id_key.clone())?;
+    if holder != info.sender {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+    if recipient == holder {
+        return Err(StdError::generic_err(
+            "recipient is already the position holder",
+        ));
+    }
+
+    if require_acceptance {
+        POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT.save(
+            deps.storage,
+            position_id_key,
+            &recipient,
+        )?;
+        return Ok(Response::new().add_event(
+            Event::new("position_transfer_proposed")
+                .add_attribute("position_id", position_id.to_string())
+                .add_attribute("holder", holder)
+                .add_attribute("recipient", recipient),
+        ));
+    }
+    change_position_holder(deps, position_id, holder, recipient)
+}
+
+pub fn accept_position_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    position_id: PositionId,
+) -> StdResult<Response> {
+    let position_id_key = U128Key::from(position_id.u128());
+    let pending_recipient = POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT
+        .may_load(deps.storage, position_id_key.clone())?;
+    if pending_recipient != Some(info.sender.clone()) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    let holder = POSITION_ID_TO_HOLDER.load(deps.storage, position_id_key)?;
+    change_position_holder(deps, position_id, holder, info.sender)
+}
+
+pub fn cancel_position_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    position_id: PositionId,
+) -> StdResult<Response> {
+    let position_id_key = U128Key::from(position_id.u128());
+    let holder = POSITION_ID_TO_HOLDER.load(deps.storage, position_id_key.clone())?;
+    if holder != info.sender {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    if !POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT.has(deps.storage, position_id_key.clone()) {
+        return Err(StdError::generic_err("no pending position transfer"));
+    }
+    POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT.remove(deps.storage, position_id_key);
+    Ok(Response::new().add_event(
+        Event::new("position_transfer_cancelled")
+            .add_attribute("position_id", position_id.to_string())
+            .add_attribute("holder", holder),
+    ))
+}
+
+// Moves the position from `holder` to `recipient`, updating both holder maps and clearing any pending transfer, cw721 approvals and position-specific operator grants.
+pub fn change_position_holder(
+    deps: DepsMut,
+    position_id: PositionId,
+    holder: Addr,
+    recipient: Addr,
+) -> StdResult<Response> {
+    let position_id_key = U128Key::from(position_id.u128());
+    POSITION_ID_TO_HOLDER.save(deps.storage, position_id_key.clone(), &recipient)?;
+    HOLDER_POSITION_ID_PAIR_SET.remove(deps.storage, (holder.clone(), position_id_key.clone()));
+    HOLDER_POSITION_ID_PAIR_SET.save(
+        deps.storage,
+        (recipient.clone(), position_id_key.clone()),
+        &(),
+    )?;
+    POSITION_ID_TO_PENDING_TRANSFER_RECIPIENT.remove(deps.storage, position_id_key.clone());
+    let approved_spenders: Vec<Vec<u8>> = POSITION_APPROVALS
+        .prefix(position_id_key.clone())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect();
+    for spender in approved_spenders {
+        POSITION_APPROVALS.remove(
+            deps.storage,
+            (
+                position_id_key.clone(),
+                Addr::unchecked(String::from_utf8(spender)?),
+            ),
+        );
+    }
+    let operators: Vec<Vec<u8>> = POSITION_ACTION_OPERATORS
+        .prefix(position_id_key.clone())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect();
+    for operator in operators {
+        POSITION_ACTION_OPERATORS.remove(
+            deps.storage,
+            (
+                position_id_key.clone(),
+                Addr::unchecked(String::from_utf8(operator)?),
+            ),
+        );
+    }
+    Ok(Response::new().add_event(
+        Event::new("position_transfer")
+            .add_attribute("position_id", position_id.to_string())
+            .add_attribute("from", holder)
+            .add_attribute("to", recipient),
+    ))
+}
+
 pub fn save_new_position_info_and_open_it(
     deps: DepsMut,
     env: Env,
@@ -111,6 +455,7 @@ pub fn save_new_position_info_and_open_it(
         position_key,
         &StrategyLocation::TerraChain(strategy_id),
     )?;
+    index_strategy_position(deps.storage, strategy_id, &position)?;
 
     create_execute_strategy_messages(
         deps.as_ref(),
@@ -131,33 +476,206 @@ pub fn execute_strategy(
     assets: Vec<Asset>,
 ) -> StdResult<Response> {
     let holder = POSITION_ID_TO_HOLDER.load(deps.storage, U128Key::from(position_id.u128()))?;
-    if holder != info.sender {
-        return Err(StdError::generic_err("unauthorized"));
-    }
     if let Action::OpenPosition { .. } = action {
         return Err(StdError::generic_err(
             "open-position action on an existing position is disallowed",
         ));
     }
+    if holder != info.sender {
+        check_operator_can_execute(deps, &env, position_id, &holder, &info.sender, &action)?;
+    }
+    let position = Position {
+        chain_id: TERRA_CHAIN_ID,
+        position_id,
+    };
+    let strategy_location =
+        POSITION_TO_STRATEGY_LOCATION_MAP.load(deps.storage, get_position_key(&position))?;
+    match strategy_location {
+        StrategyLocation::TerraChain(strategy_id) => assert_not_paused(
+            deps,
+            PausableOperation::ExecuteStrategy,
+            TERRA_CHAIN_ID,
+            Some(strategy_id),
+        )?,
+        StrategyLocation::ExternalChain(strategy_chain_id) => assert_not_paused(
+            deps,
+            PausableOperation::ExecuteStrategy,
+            strategy_chain_id,
+            None,
+        )?,
+    }
+    if let StrategyLocation::ExternalChain(strategy_chain_id) = strategy_location {
+        return Ok(
+            Response::new().add_messages(execute_external_chain_strategy(
+                deps,
+                env,
+                info,
+                position,
+                strategy_chain_id,
+                action,
+                assets,
+            )?),
+        );
+    }
     Ok(
         Response::new().add_messages(create_execute_strategy_messages(
             deps,
             env,
             Some(info),
-            Position {
-                chain_id: TERRA_CHAIN_ID,
-                position_id,
-            },
+            position,
             action,
             assets,
         )?),
     )
 }
 
+pub fn grant_position_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    position_id: Option<PositionId>,
+    allowed_actions: Vec<PositionActionKind>,
+    expires: Option<Expiration>,
+) -> StdResult<Response> {
+    let operator = deps.api.addr_validate(&operator)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(StdError::generic_err("expiration already passed"));
+    }
+    let grant = PositionOperatorGrant {
+        allowed_actions,
+        expires,
+    };
+    match position_id {
+        Some(position_id) => {
+            let position_id_key = U128Key::from(position_id.u128());
+            if POSITION_ID_TO_HOLDER.load(deps.storage, position_id_key.clone())? != info.sender {
+                return Err(StdError::generic_err("unauthorized"));
+            }
+            POSITION_ACTION_OPERATORS.save(
+                deps.storage,
+                (position_id_key, operator.clone()),
+                &grant,
+            )?;
+        }
+        None => {
+            HOLDER_ACTION_OPERATORS.save(
+                deps.storage,
+                (info.sender.clone(), operator.clone()),
+                &grant,
+            )?;
+        }
+    }
+    Ok(Response::new()
+        .add_attribute("action", "grant_position_operator")
+        .add_attribute("holder", info.sender)
+        .add_attribute("operator", operator))
+}
+
+pub fn revoke_position_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+    position_id: Option<PositionId>,
+) -> StdResult<Response> {
+    let operator = deps.api.addr_validate(&operator)?;
+    match position_id {
+        Some(position_id) => {
+            let position_id_key = U128Key::from(position_id.u128());
+            if POSITION_ID_TO_HOLDER.load(deps.storage, position_id_key.clone())? != info.sender {
+                return Err(StdError::generic_err("unauthorized"));
+            }
+            POSITION_ACTION_OPERATORS.remove(deps.storage, (position_id_key, operator.clone()));
+        }
+        None => {
+            HOLDER_ACTION_OPERATORS.remove(deps.storage, (info.sender.clone(), operator.clone()));
+        }
+    }
+    Ok(Response::new()
+        .add_attribute("action", "revoke_position_operator")
+        .add_attribute("holder", info.sender)
+        .add_attribute("operator", operator))
+}
+
+pub fn query_position_operator_grant(
+    deps: Deps,
+    holder: String,
+    operator: String,
+    position_id: Option<PositionId>,
+) -> StdResult<Option<PositionOperatorGrant>> {
+    let holder = deps.api.addr_validate(&holder)?;
+    let operator = deps.api.addr_validate(&operator)?;
+    match position_id {
+        Some(position_id) => {
+            let position_id_key = U128Key::from(position_id.u128());
+            if POSITION_ID_TO_HOLDER.load(deps.storage, position_id_key.clone())? != holder {
+                return Ok(None);
+            }
+            POSITION_ACTION_OPERATORS.may_load(deps.storage, (position_id_key, operator))
+        }
+        None => HOLDER_ACTION_OPERATORS.may_load(deps.storage, (holder, operator)),
+    }
+}
+
+fn get_position_action_kind(action: &Action) -> StdResult<PositionActionKind> {
+    match action {
+        Action::OpenPosition { .. } => Err(StdError::generic_err(
+            "open-position action cannot be delegated",
+        )),
+        Action::IncreasePosition { .. } => Ok(PositionActionKind::IncreasePosition),
+        Action::DecreasePosition { .. } => Ok(PositionActionKind::DecreasePosition),
+        Action::ClosePosition { .. } => Ok(PositionActionKind::ClosePosition),
+        Action::EmergencyClosePosition { .. } => Ok(PositionActionKind::EmergencyClosePosition),
+    }
+}
+
+// Checks that `operator` holds an unexpired grant, either for this position or for all positions of `holder`, that allows `action`,
+// and that any assets disbursed by `action` go to `holder` on Terra.
+fn check_operator_can_execute(
+    deps: Deps,
+    env: &Env,
+    position_id: PositionId,
+    holder: &Addr,
+    operator: &Addr,
+    action: &Action,
+) -> StdResult<()> {
+    let action_kind = get_position_action_kind(action)?;
+    let grants = [
+        POSITION_ACTION_OPERATORS.may_load(
+            deps.storage,
+            (U128Key::from(position_id.u128()), operator.clone()),
+        )?,
+        HOLDER_ACTION_OPERATORS.may_load(deps.storage, (holder.clone(), operator.clone()))?,
+    ];
+    let allowed = grants.iter().flatten().any(|grant| {
+        !grant.expires.is_expired(&env.block) && grant.allowed_actions.contains(&action_kind)
+    });
+    if !allowed {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let recipient = match action {
+        Action::DecreasePosition { recipient, .. }
+        | Action::ClosePosition { recipient }
+        | Action::EmergencyClosePosition { recipient } => Some(recipient),
+        _ => None,
+    };
+    if let Some(recipient) = recipient {
+        let disburses_to_holder = matches!(recipient, Recipient::TerraChain { recipient } if recipient == holder.as_str());
+        if !disburses_to_holder {
+            return Err(StdError::generic_err(
+                "operator can only disburse assets to the position holder",
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn get_terra_strategy_id(strategy_location: StrategyLocation) -> StdResult<StrategyId> {
     match strategy_location {
         StrategyLocation::ExternalChain(_) => Err(StdError::generic_err(
-            "Cross-chain action not yet supported",
+            "strategy is not located on Terra chain",
         )),
         StrategyLocation::TerraChain(strategy_id) => Ok(strategy_id),
     }
@@ -174,6 +692,7 @@ pub fn create_execute_strategy_messages(
     let strategy_location =
         POSITION_TO_STRATEGY_LOCATION_MAP.load(deps.storage, get_position_key(&position))?;
     let strategy_id = get_terra_strategy_id(strategy_location)?;
+    check_strategy_status_allows_action(&get_strategy_status(deps, strategy_id)?, &action)?;
     let strategy_manager_addr = STRATEGY_ID_TO_METADATA_MAP
         .load(deps.storage, get_strategy_id_key(strategy_id))?
         .manager_addr;
@@ -76,7 +76,7 @@ async function migrate_contract(contract_addr, new_code_id) {
         test_wallet.key.accAddress,
         contract_addr,
         new_code_id,
-        {}
+        { timelock_delay_seconds: 24 * 60 * 60 }
       ),
     ],
     sequence: getAndIncrementSequence(),
@@ -105,6 +105,7 @@ async function instantiate_terra_manager(terra_manager_id) {
           cross_chain_outgoing_fee_rate: "0.001",
           cross_chain_outgoing_fee_collector_addr:
             "terra1ads6zkvpq0dvy99hzj6dmk0peevzkxvvufd76g",
+          timelock_delay_seconds: 24 * 60 * 60,
         },
         /*init_coins=*/ {}
       ),
@@ -140,6 +141,7 @@ async function instantiate_delta_neutral_position_manager(
           controller: test_wallet.key.accAddress,
           min_open_uusd_amount: (100 * 1e6).toString(),
           min_reinvest_uusd_amount: (10 * 1e6).toString(),
+          limit_order_imbalance_threshold: "0.01",
           anchor_ust_cw20_addr: "terra1ajt556dpzvjwl0kl5tzku3fc3p3knkg9mkv8jl",
           mirror_cw20_addr: "terra10llyp6v3j3her8u3ce66ragytu45kcmd9asj3u",
           spectrum_cw20_addr: "terra1kvsxd94ue6f4rtchv2l6me5k07uh26s7637cza",
@@ -163,6 +165,7 @@ async function instantiate_delta_neutral_position_manager(
             performance_rate: "0.1",
             collector_addr: test_wallet.key.accAddress,
           },
+          timelock_delay_seconds: 24 * 60 * 60,
         },
         /*init_coins=*/ {}
       ),
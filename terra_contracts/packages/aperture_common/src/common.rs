@@ -1,5 +1,5 @@
 /// This module defines data types commonly used in Aperture contracts.
-use cosmwasm_std::{Addr, Binary, Decimal, Uint128, Uint256, Uint64};
+use cosmwasm_std::{Addr, Binary, Decimal, StdError, StdResult, Uint128, Uint256, Uint64};
 use cw_storage_plus::{U128Key, U16Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -34,6 +34,35 @@ pub fn get_position_key_from_tuple(position: &(ChainId, u128)) -> PositionKey {
     (U16Key::from(position.0), U128Key::from(position.1))
 }
 
+/// Inverse of `get_position_key()`, applied to a raw storage key returned by a range over a map keyed by `PositionKey`.
+/// The raw key is the 2-byte length prefix of the chain id, followed by the big-endian chain id and position id.
+pub fn get_position_from_key(key: &[u8]) -> StdResult<Position> {
+    if key.len() != 20 || key[0..2] != [0, 2] {
+        return Err(StdError::generic_err("invalid position key"));
+    }
+    let mut position_id = [0u8; 16];
+    position_id.copy_from_slice(&key[4..]);
+    Ok(Position {
+        chain_id: u16::from_be_bytes([key[2], key[3]]),
+        position_id: Uint128::from(u128::from_be_bytes(position_id)),
+    })
+}
+
+#[test]
+fn test_get_position_from_key() {
+    use cw_storage_plus::PrimaryKey;
+
+    let position = Position {
+        chain_id: 10001,
+        position_id: Uint128::from(1234567890123u128),
+    };
+    assert_eq!(
+        get_position_from_key(&get_position_key(&position).joined_key()),
+        Ok(position)
+    );
+    assert!(get_position_from_key(&[0, 2, 1]).is_err());
+}
+
 /// The pair (chain id, position id) can uniquely identify a position across
 /// all chains.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -128,4 +157,9 @@ pub enum Action {
         proportion: Decimal,
         recipient: Recipient,
     },
+    // Closes the position without relying on fresh oracle prices; strategies may only allow this once their price source has been unavailable for some time.
+    // Strategies that do not depend on an oracle treat this the same as `ClosePosition`.
+    EmergencyClosePosition {
+        recipient: Recipient,
+    },
 }
@@ -15,8 +15,14 @@ pub enum InternalExecuteMsg {
     CloseCdpAndDisburseUusd {
         recipient: Recipient,
     },
+    // Same as `CloseCdpAndDisburseUusd`, but values the mAsset at the pool price instead of the oracle price.
+    EmergencyCloseCdpAndDisburseUusd {
+        recipient: Recipient,
+    },
     CloseCdpAndDepositToAnchorEarn {},
     DepositUusdBalanceToAnchorEarn {},
+    // Deposits the uusd balance into Anchor Earn while short proceeds are pending unlock; the aUST is redeemed for reinvestment after unlock.
+    ParkUusdBalanceInAnchorEarn {},
     WithdrawCollateralAndRedeemForUusd {
         proportion: Decimal,
     },
@@ -28,6 +34,17 @@ pub enum InternalExecuteMsg {
     DeltaNeutralReinvest {
         mirror_asset_fresh_oracle_uusd_rate: Decimal,
     },
+    // Posts the collateral received from the uusd -> collateral swap issued when investing, i.e. the increase of the collateral balance over `collateral_balance_before` capped at `max_collateral_amount`,
+    // to open (if `cdp_idx` is None) or increase the CDP, and swaps uusd for the amount of mAsset minted against it.
+    OpenOrIncreaseCdpWithCollateralBalance {
+        collateral_balance_before: Uint128,
+        max_collateral_amount: Uint128,
+        collateral_ratio: Decimal,
+        collateral_uusd_price: Decimal,
+        mirror_asset_oracle_uusd_rate: Decimal,
+        cdp_idx: Option<Uint128>,
+        max_spread: Option<Decimal>,
+    },
     // Performs a sanity check at the end of ExecuteMsg::OpenPosition to make sure that the short and long positions hold an equal amount of the mAsset.
     OpenPositionSanityCheck {},
 }
@@ -43,6 +60,12 @@ pub enum ControllerExecuteMsg {
 pub enum ExecuteMsg {
     OpenPosition { params: DeltaNeutralParams },
     ClosePosition { recipient: Recipient },
+    // Same as `ClosePosition`, but unwinds at pool prices with swaps bounded by `EmergencyCloseConfig.max_haircut`.
+    // Only allowed once the mAsset oracle price has been stale for `EmergencyCloseConfig.min_oracle_stale_seconds`.
+    EmergencyClosePosition { recipient: Recipient },
+    // Closes the CDP and keeps the funds in Anchor Earn, as is done on rebalance for an mAsset on the manager's should-preemptively-close list.
+    // Has no effect if the position is closed or its CDP has already been preemptively closed.
+    PreemptivelyCloseCdp {},
     Controller(ControllerExecuteMsg),
     Internal(InternalExecuteMsg),
 }
@@ -60,27 +83,63 @@ pub enum QueryMsg {
     // Returns PositionInfoResponse.
     GetPositionInfo {},
     // Returns bool.
-    CheckSpectrumMirrorFarmExistence { mirror_asset_cw20_addr: String },
+    CheckSpectrumMirrorFarmExistence {
+        mirror_asset_cw20_addr: String,
+    },
+    // Returns PositionSnapshotsResponse.
+    // Snapshots are identified by a sequence number starting from zero; only the most recent snapshots are retained.
+    // Rebalances record a snapshot only if the latest snapshot is at least a day old.
+    GetPositionSnapshots {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+// The DEX where the long leg of a delta-neutral position provides mAsset-UST liquidity.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidityVenue {
+    // Terraswap mAsset-UST pool; LP tokens are staked in Spectrum Mirror farm.
+    Terraswap,
+    // Astroport mAsset-UST pool; LP tokens are staked in Mirror staking.
+    Astroport,
+}
+
+// What a delta-neutral position does with SPEC rewards harvested from Spectrum Mirror farm.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecRewardPolicy {
+    // Swap SPEC rewards for uusd and reinvest.
+    Sell,
+    // Stake SPEC rewards in Spectrum governance to earn its staking yield; staked SPEC is unstaked and sold when the position is closed.
+    Stake,
 }
 
+// Fields keep the names they had when only Terraswap was supported, so that existing clients of `GetPositionInfo` can still parse the response.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct TerraswapPoolInfo {
-    // Amount of LP token staked in Spectrum Mirror farm.
+pub struct LiquidityPoolInfo {
+    // The DEX hosting the mAsset-UST pool.
+    pub venue: LiquidityVenue,
+    // Amount of LP token staked in Spectrum Mirror farm (Terraswap) or Mirror staking (Astroport).
     pub lp_token_amount: Uint128,
     // Address of the LP cw20 token contract.
     pub lp_token_cw20_addr: String,
     // Total supply of the LP token.
     pub lp_token_total_supply: Uint128,
-    // Address of the mAsset-UST Terraswap pair contract.
-    pub terraswap_pair_addr: String,
-    // Balance of mAsset in the mAsset-UST Terraswap pool.
-    pub terraswap_pool_mirror_asset_amount: Uint128,
-    // Balance of uusd in the mAsset-UST Terraswap pool.
-    pub terraswap_pool_uusd_amount: Uint128,
+    // Address of the mAsset-UST pair contract.
+    #[serde(rename = "terraswap_pair_addr")]
+    pub pair_addr: String,
+    // Balance of mAsset in the mAsset-UST pool.
+    #[serde(rename = "terraswap_pool_mirror_asset_amount")]
+    pub pool_mirror_asset_amount: Uint128,
+    // Balance of uusd in the mAsset-UST pool.
+    #[serde(rename = "terraswap_pool_uusd_amount")]
+    pub pool_uusd_amount: Uint128,
     // The number of auto-compound shares in the Spectrum Mirror Farm.
     // These shares can currently be redeemed for `lp_token_amount` amount of LP tokens.
     // This value is represented as `auto_bond_share` in Spectrum Mirror Farm reward info.
+    // Always zero for Astroport positions as Mirror staking has no share accounting.
     pub spectrum_auto_compound_share_amount: Uint128,
 }
 
@@ -97,18 +156,28 @@ pub struct PositionState {
     pub mirror_asset_balance: Uint128,
     // Amount of mAsset redeemable from staked LP tokens.
     pub mirror_asset_long_farm: Uint128,
-    // Sum of `mirror_asset_balance` and `mirror_asset_long_farm`.
+    // Unfilled amount of mAsset offered in an outstanding Mirror limit order.
+    pub limit_order_mirror_asset_amount: Uint128,
+    // Unfilled amount of uusd offered in an outstanding Mirror limit order.
+    pub limit_order_uusd_amount: Uint128,
+    // Sum of `mirror_asset_balance`, `mirror_asset_long_farm` and `limit_order_mirror_asset_amount`.
     pub mirror_asset_long_amount: Uint128,
-    // Amount of aUST collateral.
-    pub collateral_anchor_ust_amount: Uint128,
-    // Value of aUST colleteral in uusd.
+    // Amount of collateral asset in the CDP.
+    // Serialized under its name from when aUST was the only collateral asset, as are `collateral_oracle_price` and `pool_info`.
+    #[serde(rename = "collateral_anchor_ust_amount")]
+    pub collateral_amount: Uint128,
+    // Value of the collateral in uusd.
     pub collateral_uusd_value: Uint128,
     // Oracle price of the mAsset.
     pub mirror_asset_oracle_price: Decimal,
-    // Oracle price of aUST.
-    pub anchor_ust_oracle_price: Decimal,
-    // Information about the Terraswap mAsset-UST pool.
-    pub terraswap_pool_info: TerraswapPoolInfo,
+    // Price of the collateral asset according to Mirror collateral oracle.
+    #[serde(rename = "anchor_ust_oracle_price")]
+    pub collateral_oracle_price: Decimal,
+    // Mirror collateral multiplier of the collateral asset; the mAsset minimum collateral ratio is scaled by this multiplier.
+    pub collateral_multiplier: Decimal,
+    // Information about the mAsset-UST pool where the long leg provides liquidity.
+    #[serde(rename = "terraswap_pool_info")]
+    pub pool_info: LiquidityPoolInfo,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -132,6 +201,57 @@ pub struct PositionActionInfo {
     pub uusd_amount: Uint128,
 }
 
+// The event upon which a position snapshot is recorded.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionSnapshotKind {
+    Open,
+    Rebalance,
+    FeeCollection,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PositionSnapshot {
+    pub kind: PositionSnapshotKind,
+    pub height: u64,
+    pub time_nanoseconds: u64,
+    // Position value in uusd, net of fees charged at this snapshot.
+    pub uusd_value: Uint128,
+    // None if the CDP is inactive.
+    pub collateral_ratio: Option<Decimal>,
+    pub mirror_asset_long_amount: Uint128,
+    pub mirror_asset_short_amount: Uint128,
+    // Value of MIR and SPEC rewards harvested upon this snapshot.
+    pub rewards_harvested_uusd_value: Uint128,
+    pub fees_charged_uusd_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PositionSnapshotItem {
+    pub sequence_number: u64,
+    pub snapshot: PositionSnapshot,
+}
+
+// Simple (non-compounding) annualized return; `rate` is a loss if `negative` is true.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AnnualizedReturn {
+    pub rate: Decimal,
+    pub negative: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PositionSnapshotsResponse {
+    pub snapshots: Vec<PositionSnapshotItem>,
+    // Total number of snapshots ever recorded, including those no longer retained.
+    pub total_count: u64,
+    // Annualized return between the oldest retained and the latest snapshot; None if fewer than two snapshots are retained or no time has elapsed.
+    pub annualized_return: Option<AnnualizedReturn>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct DetailedPositionInfo {
@@ -149,6 +269,10 @@ pub struct DetailedPositionInfo {
     pub claimable_short_proceeds_uusd_amount: Uint128,
     pub claimable_mir_reward_uusd_value: Uint128,
     pub claimable_spec_reward_uusd_value: Uint128,
+    // Value of SPEC staked in Spectrum governance under `SpecRewardPolicy::Stake`.
+    pub staked_spec_uusd_value: Uint128,
+    // Value of uusd parked in Anchor Earn while short proceeds are pending unlock.
+    pub anchor_earn_parked_uusd_value: Uint128,
     pub uusd_value: Uint128,
 }
 
@@ -164,3 +288,49 @@ pub struct PositionInfoResponse {
     // None if position is closed.
     pub detailed_info: Option<DetailedPositionInfo>,
 }
+
+#[test]
+fn test_position_state_serialized_field_names() {
+    let state = PositionState {
+        uusd_balance: Uint128::zero(),
+        uusd_long_farm: Uint128::zero(),
+        mirror_asset_short_amount: Uint128::zero(),
+        mirror_asset_balance: Uint128::zero(),
+        mirror_asset_long_farm: Uint128::zero(),
+        limit_order_mirror_asset_amount: Uint128::zero(),
+        limit_order_uusd_amount: Uint128::zero(),
+        mirror_asset_long_amount: Uint128::zero(),
+        collateral_amount: Uint128::from(1u128),
+        collateral_uusd_value: Uint128::zero(),
+        mirror_asset_oracle_price: Decimal::one(),
+        collateral_oracle_price: Decimal::one(),
+        collateral_multiplier: Decimal::one(),
+        pool_info: LiquidityPoolInfo {
+            venue: LiquidityVenue::Astroport,
+            lp_token_amount: Uint128::zero(),
+            lp_token_cw20_addr: String::from("lp_token"),
+            lp_token_total_supply: Uint128::zero(),
+            pair_addr: String::from("pair"),
+            pool_mirror_asset_amount: Uint128::from(2u128),
+            pool_uusd_amount: Uint128::from(3u128),
+            spectrum_auto_compound_share_amount: Uint128::zero(),
+        },
+    };
+    let json = String::from_utf8(cosmwasm_std::to_vec(&state).unwrap()).unwrap();
+    for field in [
+        r#""collateral_anchor_ust_amount":"1""#,
+        r#""anchor_ust_oracle_price":"1""#,
+        r#""terraswap_pool_info":{"venue":"astroport""#,
+        r#""terraswap_pair_addr":"pair""#,
+        r#""terraswap_pool_mirror_asset_amount":"2""#,
+        r#""terraswap_pool_uusd_amount":"3""#,
+    ]
+    .iter()
+    {
+        assert!(json.contains(field), "{} not in {}", field, json);
+    }
+    assert_eq!(
+        cosmwasm_std::from_slice::<PositionState>(json.as_bytes()),
+        Ok(state)
+    );
+}
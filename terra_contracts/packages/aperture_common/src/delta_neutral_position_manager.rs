@@ -1,10 +1,12 @@
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Decimal, StdResult, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     common::{Action, ChainId, Position, PositionId},
-    delta_neutral_position::PositionInfoResponse,
+    delta_neutral_position::{LiquidityVenue, PositionInfoResponse, SpecRewardPolicy},
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -24,6 +26,8 @@ pub struct InstantiateMsg {
     pub spectrum_cw20_addr: String,
     pub anchor_market_addr: String,
     pub mirror_collateral_oracle_addr: String,
+    // Mirror limit order contract; if not specified, delta-neutral rebalancing always swaps at the pool.
+    pub mirror_limit_order_addr: Option<String>,
     pub mirror_lock_addr: String,
     pub mirror_mint_addr: String,
     pub mirror_oracle_addr: String,
@@ -41,7 +45,11 @@ pub struct InstantiateMsg {
     pub min_open_uusd_amount: Uint128,
     // The minimum uusd amount eligible for delta-neutral reinvestment.
     pub min_reinvest_uusd_amount: Uint128,
+    // The maximum long/short mAsset imbalance, as a fraction of the short amount, that is corrected with a limit order at the oracle price instead of a pool swap.
+    pub limit_order_imbalance_threshold: Decimal,
     pub fee_collection_config: FeeCollectionConfig,
+    // Delay in seconds between proposing and executing a sensitive admin operation; must be at least `MIN_TIMELOCK_DELAY_SECONDS`.
+    pub timelock_delay_seconds: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -52,12 +60,20 @@ pub enum InternalExecuteMsg {
         params: DeltaNeutralParams,
         uusd_amount: Uint128,
     },
+    SendOpenPositionToDiversifiedPositionLegs {
+        position: Position,
+        params: DiversifiedDeltaNeutralParams,
+        // Amount of uusd allocated to each leg, in the same order as `params.legs`.
+        uusd_amounts: Vec<Uint128>,
+    },
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     // Can only be called by the position holder through Terra manager.
+    // For `Action::OpenPosition`, `data` is DiversifiedDeltaNeutralParams if it has a `legs` field, and DeltaNeutralParams otherwise.
     PerformAction {
         position: Position,
         action: Action,
@@ -70,17 +86,34 @@ pub enum ExecuteMsg {
         positions: Vec<Position>,
         position_contracts: Vec<String>,
     },
+    // Can only be called by admin. Migrates up to `limit` positions to the current code id, resuming after the last position processed by the previous call.
+    // Contracts already on the current code id are skipped, and failed migrations (including contracts whose code id cannot be queried) are recorded without aborting the batch.
+    // Call repeatedly until `PositionMigrationProgress.done` is true; see `GetPositionMigrationProgress`. Progress restarts once `delta_neutral_position_code_id` changes.
+    MigratePositionContractsFromCursor {
+        limit: Option<u32>,
+    },
     // Can only be called by admin.
+    // `admin_addr` only nominates the next admin, replacing any pending nomination; the handover takes effect once the nominee calls `AcceptAdmin`.
+    // Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
     UpdateAdminConfig {
         admin_addr: Option<String>,
         terra_manager_addr: Option<String>,
         delta_neutral_position_code_id: Option<u64>,
     },
+    // Can only be called by the admin nominated through `UpdateAdminConfig`.
+    AcceptAdmin {},
     // Can only be called by admin.
+    // Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
     UpdateFeeCollectionConfig {
         fee_collection_config: FeeCollectionConfig,
     },
     // Can only be called by admin.
+    // Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
+    UpdateEmergencyCloseConfig {
+        emergency_close_config: EmergencyCloseConfig,
+    },
+    // Can only be called by admin.
+    // Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
     UpdatePositionOpenMirrorAssetList {
         mirror_assets: Vec<String>,
         allowed: bool,
@@ -90,18 +123,53 @@ pub enum ExecuteMsg {
     // The admin should only utilize this feature when the delisting is certain to happen, either:
     // (1) Due to an announced corporate event with a scheduled date (merger, stock split, etc.);
     // (2) Mirror governance voted to delist an mAsset.
+    // Not subject to the timelock since the CDPs must be closed before the delisting takes effect.
     // The admin should add the mAsset to this list no earlier than ~1 business day before the scheduled effective timestamp. For example, the admin should ideally wait until Mirror governance vote successfully passes (but has not yet executed).
     AddShouldPreemptivelyCloseCdpMirrorAssetList {
         mirror_assets: Vec<String>,
     },
     // Can only be called by admin.
-    UpdateContext {
-        controller: Option<String>,
-        mirror_collateral_oracle_addr: Option<String>,
-        mirror_oracle_addr: Option<String>,
-        collateral_ratio_safety_margin: Option<Decimal>,
-        min_open_uusd_amount: Option<Uint128>,
-        min_reinvest_uusd_amount: Option<Uint128>,
+    // Adds `mirror_asset` to the should-preemptively-close list, and sends `PreemptivelyCloseCdp` to up to `limit` position contracts on `mirror_asset`, resuming after the last contract processed by the previous call.
+    // Call repeatedly until `PreemptiveCloseProgress.done` is true; see `GetPreemptiveCloseProgress`.
+    // Positions not yet in `position_records()` are not covered, but close their CDPs on their next rebalance due to the should-preemptively-close list.
+    // Not subject to the timelock for the same reason as `AddShouldPreemptivelyCloseCdpMirrorAssetList`.
+    PreemptivelyCloseMirrorAssetCdps {
+        mirror_asset: String,
+        limit: Option<u32>,
+    },
+    // Can only be called by admin.
+    // Caps the total uusd deposited into positions that are not yet closed; removes the cap if `uusd_cap` is None.
+    // Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
+    UpdateGlobalTvlCap {
+        uusd_cap: Option<Uint128>,
+    },
+    // Can only be called by admin.
+    // Replaces the strategy parameter overrides on the allowlist entry of each of `mirror_assets`; this does not change whether the mAsset is allowed.
+    // Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
+    UpdateMirrorAssetStrategyParamOverrides {
+        mirror_assets: Vec<String>,
+        overrides: MirrorAssetStrategyParamOverrides,
+    },
+    // Can only be called by admin.
+    // Each contract address specified is validated by querying its config (or cw20 token info).
+    // Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
+    UpdateContext(ContextUpdate),
+    // Can only be called by admin.
+    // Proposes a sensitive admin operation, which can be executed once the timelock delay in effect at proposal time has elapsed.
+    ProposeAdminOperation {
+        operation: AdminOperation,
+    },
+    // Can only be called by admin.
+    ExecuteAdminOperation {
+        proposal_id: u64,
+    },
+    // Can only be called by admin.
+    CancelAdminOperation {
+        proposal_id: u64,
+    },
+    // Can only be called by a position contract to report a change in its lifecycle status.
+    UpdatePositionStatus {
+        status: PositionStatus,
     },
     // Can only be called by this contract itself.
     Internal(InternalExecuteMsg),
@@ -112,6 +180,8 @@ pub enum ExecuteMsg {
 pub struct MigrateMsg {
     pub fee_collection_config: FeeCollectionConfig,
     pub position_open_allowed_mirror_assets: Vec<String>,
+    // Sets the timelock delay, which contracts instantiated before the timelock was introduced lack.
+    pub timelock_delay_seconds: u64,
 }
 
 /// Represents position ids of the range [start, end) on the chain identified by `chain_id`.
@@ -123,22 +193,102 @@ pub struct PositionRange {
     pub end: PositionId,
 }
 
+// Sensitive admin operations subject to the timelock; each mirrors the `ExecuteMsg` variant of the same name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminOperation {
+    UpdateAdminConfig {
+        admin_addr: Option<String>,
+        terra_manager_addr: Option<String>,
+        delta_neutral_position_code_id: Option<u64>,
+    },
+    UpdateFeeCollectionConfig {
+        fee_collection_config: FeeCollectionConfig,
+    },
+    UpdateEmergencyCloseConfig {
+        emergency_close_config: EmergencyCloseConfig,
+    },
+    UpdatePositionOpenMirrorAssetList {
+        mirror_assets: Vec<String>,
+        allowed: bool,
+    },
+    UpdateGlobalTvlCap {
+        uusd_cap: Option<Uint128>,
+    },
+    UpdateMirrorAssetStrategyParamOverrides {
+        mirror_assets: Vec<String>,
+        overrides: MirrorAssetStrategyParamOverrides,
+    },
+    UpdateContext(Box<ContextUpdate>),
+    // Takes effect once the current delay has elapsed; must be at least `MIN_TIMELOCK_DELAY_SECONDS`.
+    UpdateTimelockDelay {
+        delay_seconds: u64,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GetPositionContractAddr {
         position: Position,
     },
+    // Returns Vec<DiversifiedPositionLeg>.
+    GetDiversifiedPositionLegs {
+        position: Position,
+    },
     BatchGetPositionInfo {
         positions: Option<Vec<Position>>,
         ranges: Option<Vec<PositionRange>>,
     },
     GetContext {},
+    // Returns EmergencyCloseConfig.
+    GetEmergencyCloseConfig {},
     GetAdminConfig {},
+    // Returns the admin nominated through `UpdateAdminConfig` that has yet to accept as Option<Addr>.
+    GetPendingAdmin {},
+    // Returns the timelock delay in seconds as u64.
+    GetTimelockDelay {},
+    // Returns pending proposals ordered by id as Vec<AdminOperationProposal<AdminOperation>>.
+    GetAdminOperationProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     // Returns CheckMirrorAssetAllowlistResponse.
     CheckMirrorAssetAllowlist {
         mirror_assets: Vec<String>,
     },
+    // Returns MirrorAssetStrategyParamsResponse.
+    GetMirrorAssetStrategyParams {
+        mirror_asset_cw20_addr: String,
+    },
+    // Returns MirrorAssetAprRankingResponse.
+    // Estimates the expected APR of a delta-neutral position for each mAsset allowed for position open, based on current reward emissions and pool states.
+    GetMirrorAssetAprRanking {
+        // The CDP collateral ratio assumed for the estimate, which determines how a deposit is split between collateral and the long/short legs.
+        collateral_ratio: Decimal,
+    },
+    // Returns PositionRecordsResponse, ordered by position contract address.
+    // `start_after` is a position contract address.
+    GetPositionsByMirrorAsset {
+        mirror_asset_cw20_addr: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Returns PositionRecordsResponse, ordered by position contract address.
+    // `start_after` is a position contract address.
+    GetPositionsByStatus {
+        status: PositionStatus,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Returns TvlUtilizationResponse.
+    GetTvlUtilization {},
+    // Returns Option<PreemptiveCloseProgress>.
+    GetPreemptiveCloseProgress {
+        mirror_asset: String,
+    },
+    // Returns Option<PositionMigrationProgress>.
+    GetPositionMigrationProgress {},
     // Returns ShouldCallRebalanceAndReinvestResponse.
     ShouldCallRebalanceAndReinvest {
         position: Position,
@@ -146,6 +296,8 @@ pub enum QueryMsg {
         mirror_asset_net_amount_tolerance_ratio: Decimal,
         // Should call when liquid uusd / position value is above this threshold.
         liquid_uusd_threshold_ratio: Decimal,
+        // Index of the leg to check if `position` is a diversified position.
+        leg_index: Option<u32>,
     },
 }
 
@@ -164,6 +316,190 @@ pub struct CheckMirrorAssetAllowlistResponse {
     pub allowed: Vec<bool>,
 }
 
+// Minimum width of the target collateral ratio range, unless overridden for the mAsset.
+pub const MIN_TARGET_CR_RANGE_WIDTH: &str = "0.4";
+
+/// Per-mAsset overrides of strategy parameters; each field left as None falls back to the strategy-wide value.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MirrorAssetStrategyParamOverrides {
+    // Overrides `Context.collateral_ratio_safety_margin`; volatile mAssets warrant a wider margin.
+    pub collateral_ratio_safety_margin: Option<Decimal>,
+    // Overrides MIN_TARGET_CR_RANGE_WIDTH.
+    pub min_target_collateral_ratio_range_width: Option<Decimal>,
+    // Overrides `Context.min_open_uusd_amount`.
+    pub min_open_uusd_amount: Option<Uint128>,
+    // Maximum spread accepted by the short sale and the long swap when opening a position; there is no strategy-wide limit.
+    pub slippage_tolerance: Option<Decimal>,
+    // Maximum total uusd deposited into positions on this mAsset that are not yet closed; there is no strategy-wide per-mAsset limit.
+    pub max_tvl_uusd_amount: Option<Uint128>,
+}
+
+/// Strategy parameters in effect for an mAsset, i.e. its overrides applied to the strategy-wide values.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MirrorAssetStrategyParams {
+    pub collateral_ratio_safety_margin: Decimal,
+    pub min_target_collateral_ratio_range_width: Decimal,
+    pub min_open_uusd_amount: Uint128,
+    pub slippage_tolerance: Option<Decimal>,
+    pub max_tvl_uusd_amount: Option<Uint128>,
+}
+
+impl MirrorAssetStrategyParamOverrides {
+    pub fn apply(&self, context: &Context) -> StdResult<MirrorAssetStrategyParams> {
+        Ok(MirrorAssetStrategyParams {
+            collateral_ratio_safety_margin: self
+                .collateral_ratio_safety_margin
+                .unwrap_or(context.collateral_ratio_safety_margin),
+            min_target_collateral_ratio_range_width: match self
+                .min_target_collateral_ratio_range_width
+            {
+                Some(width) => width,
+                None => Decimal::from_str(MIN_TARGET_CR_RANGE_WIDTH)?,
+            },
+            min_open_uusd_amount: self
+                .min_open_uusd_amount
+                .unwrap_or(context.min_open_uusd_amount),
+            slippage_tolerance: self.slippage_tolerance,
+            max_tvl_uusd_amount: self.max_tvl_uusd_amount,
+        })
+    }
+}
+
+/// Entry of the mAsset allowlist.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MirrorAssetAllowlistEntry {
+    // Whether new positions may be opened on the mAsset.
+    pub allowed: bool,
+    pub overrides: MirrorAssetStrategyParamOverrides,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MirrorAssetStrategyParamsResponse {
+    // None if the mAsset has never been on the allowlist.
+    pub entry: Option<MirrorAssetAllowlistEntry>,
+    pub params: MirrorAssetStrategyParams,
+}
+
+/// Lifecycle status of a delta-neutral position contract.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionStatus {
+    // The position was opened when oracle price was stale; funds are in Anchor Earn pending delta-neutral setup.
+    PendingDeltaNeutralSetup,
+    Active,
+    // The CDP has been preemptively closed; funds are in Anchor Earn.
+    CdpPreemptivelyClosed,
+    Closed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PositionRecord {
+    pub position: Position,
+    // Index of the leg if the position contract is a leg of a diversified position; otherwise, None.
+    pub diversified_leg_index: Option<u32>,
+    pub mirror_asset_cw20_addr: Addr,
+    pub status: PositionStatus,
+    // Amount of uusd deposited when the position was opened; counts towards TVL caps until the position is closed.
+    pub open_uusd_amount: Uint128,
+}
+
+/// Progress of `ExecuteMsg::PreemptivelyCloseMirrorAssetCdps` for an mAsset.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PreemptiveCloseProgress {
+    // The last position contract processed; the next batch resumes after it.
+    pub cursor: Option<Addr>,
+    // Number of position contracts sent `PreemptivelyCloseCdp`, including those in `failed_contracts`.
+    pub requested_count: u64,
+    // Number of position contracts skipped because they were closed or had their CDP preemptively closed.
+    pub skipped_count: u64,
+    // Position contracts on which `PreemptivelyCloseCdp` failed; they remain on the should-preemptively-close list and close on their next rebalance.
+    pub failed_contracts: Vec<Addr>,
+    // Whether every indexed position on the mAsset has been processed.
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PositionMigrationProgress {
+    // The code id position contracts are being migrated to.
+    pub new_code_id: u64,
+    // Standalone positions are processed first, followed by diversified positions.
+    pub processing_diversified_positions: bool,
+    // The last position processed in the current phase; the next batch resumes after it.
+    pub cursor: Option<Position>,
+    // Number of position contracts sent a migration, including those in `failed_contracts`.
+    pub requested_count: u64,
+    // Number of position contracts skipped because they were already on `new_code_id`.
+    pub skipped_count: u64,
+    // Position contracts whose migration failed or whose code id could not be queried.
+    pub failed_contracts: Vec<Addr>,
+    // Whether every position has been processed.
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TvlUtilization {
+    // Total uusd deposited into positions that are not yet closed.
+    pub deployed_uusd_amount: Uint128,
+    // None if uncapped.
+    pub uusd_cap: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MirrorAssetTvlUtilization {
+    pub mirror_asset_cw20_addr: String,
+    pub utilization: TvlUtilization,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TvlUtilizationResponse {
+    pub global: TvlUtilization,
+    // One item for each mAsset that has an allowlist entry.
+    pub mirror_assets: Vec<MirrorAssetTvlUtilization>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PositionRecordsResponseItem {
+    pub contract: Addr,
+    pub record: PositionRecord,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PositionRecordsResponse {
+    pub items: Vec<PositionRecordsResponseItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MirrorAssetAprRankingResponse {
+    // Sorted by `expected_apr` in descending order.
+    pub items: Vec<MirrorAssetAprRankingItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MirrorAssetAprRankingItem {
+    pub mirror_asset_cw20_addr: String,
+    // Expected APR of the whole position, i.e. the components below weighted by the share of the deposit each leg receives.
+    pub expected_apr: Decimal,
+    // APR of MIR rewards for the long leg (Terraswap LP bonded in the Spectrum farm), net of Spectrum fees.
+    pub long_mirror_reward_apr: Decimal,
+    // APR of SPEC rewards for the long leg from the Spectrum farm.
+    pub long_spectrum_reward_apr: Decimal,
+    // APR of MIR rewards for the short leg.
+    pub short_mirror_reward_apr: Decimal,
+    // APR of the aUST collateral from Anchor Earn.
+    pub anchor_deposit_apr: Decimal,
+    // Mirror pool premium and the corresponding share of MIR rewards that goes to short positions.
+    pub premium_rate: Decimal,
+    pub short_reward_weight: Decimal,
+    // Amount of uusd in the mAsset-UST Terraswap pool.
+    pub pool_uusd_depth: Uint128,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BatchGetPositionInfoResponseItem {
     pub position: Position,
@@ -171,11 +507,29 @@ pub struct BatchGetPositionInfoResponseItem {
     // If `position.chain_id` is TERRA_CHAIN, then this will be populated with `Some(position holder address)`; otherwise, None.
     pub holder: Option<String>,
     pub info: PositionInfoResponse,
+    // If `position` is a diversified position, then there is one item per leg, where `contract` and `info` describe the leg identified by `Some(leg_index)`; otherwise, None.
+    pub diversified_leg_index: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BatchGetPositionInfoResponse {
     pub items: Vec<BatchGetPositionInfoResponseItem>,
+    // Aggregate state of each diversified position among the requested positions.
+    pub diversified_positions: Vec<DiversifiedPositionAggregateInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DiversifiedPositionAggregateInfo {
+    pub position: Position,
+    pub legs: Vec<DiversifiedPositionLeg>,
+    // Total amount of uusd deposited across all legs when the position was opened.
+    pub open_uusd_amount: Uint128,
+    // Total uusd value of the legs that are not yet closed.
+    pub uusd_value: Uint128,
+    // Current share of each leg in `uusd_value`, in the same order as `legs`.
+    pub leg_value_ratios: Vec<Decimal>,
+    // Whether all legs are closed.
+    pub closed: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -197,6 +551,7 @@ pub struct Context {
     pub spectrum_cw20_addr: Addr,
     pub anchor_market_addr: Addr,
     pub mirror_collateral_oracle_addr: Addr,
+    pub mirror_limit_order_addr: Option<Addr>,
     pub mirror_lock_addr: Addr,
     pub mirror_mint_addr: Addr,
     pub mirror_oracle_addr: Addr,
@@ -209,6 +564,35 @@ pub struct Context {
     pub collateral_ratio_safety_margin: Decimal,
     pub min_open_uusd_amount: Uint128,
     pub min_reinvest_uusd_amount: Uint128,
+    // Defaults to zero, i.e. limit orders disabled, for a context stored before this field was introduced.
+    #[serde(default)]
+    pub limit_order_imbalance_threshold: Decimal,
+}
+
+// Fields of `Context` to update; fields left as None are unchanged.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ContextUpdate {
+    pub controller: Option<String>,
+    pub anchor_ust_cw20_addr: Option<String>,
+    pub mirror_cw20_addr: Option<String>,
+    pub spectrum_cw20_addr: Option<String>,
+    pub anchor_market_addr: Option<String>,
+    pub mirror_collateral_oracle_addr: Option<String>,
+    pub mirror_limit_order_addr: Option<String>,
+    pub mirror_lock_addr: Option<String>,
+    pub mirror_mint_addr: Option<String>,
+    pub mirror_oracle_addr: Option<String>,
+    pub mirror_staking_addr: Option<String>,
+    pub spectrum_gov_addr: Option<String>,
+    pub spectrum_mirror_farms_addr: Option<String>,
+    pub spectrum_staker_addr: Option<String>,
+    pub terraswap_factory_addr: Option<String>,
+    pub astroport_factory_addr: Option<String>,
+    pub collateral_ratio_safety_margin: Option<Decimal>,
+    pub min_open_uusd_amount: Option<Uint128>,
+    pub min_reinvest_uusd_amount: Option<Uint128>,
+    pub limit_order_imbalance_threshold: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -222,6 +606,27 @@ pub struct FeeCollectionConfig {
     pub collector_addr: String,
 }
 
+// Conditions and limits of `Action::EmergencyClosePosition`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct EmergencyCloseConfig {
+    // Minimum number of seconds since the last mAsset oracle price update before a position can be emergency-closed; an oracle that cannot be queried counts as stale.
+    // This should exceed the usual off-market period (e.g. a weekend) so that emergency close is reserved for oracle outages.
+    pub min_oracle_stale_seconds: u64,
+    // Maximum spread against the pool price accepted by the swaps that unwind the position, i.e. the worst-case haircut the holder accepts in place of oracle-based pricing.
+    pub max_haircut: Decimal,
+}
+
+// In effect until the admin sets an emergency close config.
+impl Default for EmergencyCloseConfig {
+    fn default() -> Self {
+        EmergencyCloseConfig {
+            min_oracle_stale_seconds: 3 * 24 * 60 * 60,
+            max_haircut: Decimal::percent(5),
+        }
+    }
+}
+
 /// Parameters of a delta-neutral position specified by the user when opening this position.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -239,4 +644,36 @@ pub struct DeltaNeutralParams {
     // A non-fresh oracle price usually indicates that the off-chain market for the mAsset is currently closed; however, during active market hours there is a possibility of oracle provider delay making the oracle price stale.
     // Funds will be deposited to Anchor Earn at the time of position open; later when oracle price becomes fresh, the controller is able to trigger actual DN position setup by invoking RebalanceAndReinvest.
     pub allow_off_market_position_open: Option<bool>,
+    // The DEX where the long leg provides mAsset-UST liquidity. Defaults to Terraswap if not specified.
+    pub liquidity_venue: Option<LiquidityVenue>,
+    // The asset posted as collateral to the Mirror CDP; must be registered and not revoked in Mirror collateral oracle.
+    // Defaults to aUST if not specified.
+    // Note that `target_min_collateral_ratio` is checked against mAsset_required_colleteral_ratio scaled by the collateral multiplier of this asset.
+    pub collateral_asset_info: Option<terraswap::asset::AssetInfo>,
+    // Whether SPEC rewards are sold for uusd or staked in Spectrum governance. Defaults to selling if not specified.
+    pub spec_reward_policy: Option<SpecRewardPolicy>,
+}
+
+/// Parameters of a diversified delta-neutral position, which spreads a single deposit across a weighted basket of mAssets.
+/// Each leg is opened as a separate delta-neutral position contract with its own CDP and LP stake, so legs are rebalanced independently.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DiversifiedDeltaNeutralParams {
+    pub legs: Vec<DiversifiedDeltaNeutralLegParams>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DiversifiedDeltaNeutralLegParams {
+    // Target weight of this leg; the deposit is split across legs in proportion to their weights.
+    pub weight: u32,
+    pub params: DeltaNeutralParams,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DiversifiedPositionLeg {
+    // Address of the delta-neutral position contract of this leg.
+    pub contract: Addr,
+    pub weight: u32,
 }
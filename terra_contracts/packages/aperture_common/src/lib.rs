@@ -5,10 +5,12 @@ pub mod anchor_util;
 pub mod byte_util;
 pub mod common;
 pub mod constants;
+pub mod cw721;
 pub mod delta_neutral_position;
 pub mod delta_neutral_position_manager;
 pub mod instruction;
 pub mod mirror_util;
 pub mod terra_manager;
+pub mod timelock;
 pub mod token_util;
 pub mod wormhole;
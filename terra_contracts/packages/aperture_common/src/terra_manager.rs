@@ -1,5 +1,9 @@
-use crate::common::{Action, ChainId, Position, PositionId, Recipient, Strategy, StrategyLocation};
-use cosmwasm_std::{Binary, Decimal, Uint64};
+use crate::common::{
+    Action, ChainId, Position, PositionId, Recipient, Strategy, StrategyId, StrategyLocation,
+    StrategyMetadata,
+};
+use cosmwasm_std::{Binary, Decimal, Uint128, Uint64};
+use cw20::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +17,8 @@ pub struct InstantiateMsg {
     pub wormhole_token_bridge_addr: String,
     pub cross_chain_outgoing_fee_rate: Decimal,
     pub cross_chain_outgoing_fee_collector_addr: String,
+    /// Delay in seconds between proposing and executing a sensitive administrative operation; must be at least `MIN_TIMELOCK_DELAY_SECONDS`.
+    pub timelock_delay_seconds: u64,
 }
 
 /// Responsibilities of Aperture Terra manager:
@@ -22,20 +28,74 @@ pub struct InstantiateMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    /// Nominate `new_admin_addr` as the next administrator, replacing any pending nomination.
+    /// The handover only takes effect once `new_admin_addr` calls `AcceptAdmin`.
+    /// Can only be called by the administrator.
+    /// Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
     UpdateAdminAddr {
         new_admin_addr: String,
     },
+    /// Become the administrator nominated through `UpdateAdminAddr`.
+    /// Can only be called by the pending administrator.
+    AcceptAdmin {},
     /// Add strategy with the specified strategy manager address and metadata.
     /// A new, unique strategy_id is assigned to this strategy.
     /// Can only be called by the administrator.
+    /// Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
     AddStrategy {
         name: String,
         version: String,
         manager_addr: String,
     },
-    /// Remove the strategy associated with the specified identifier.
+    /// Deprecate the strategy associated with the specified identifier; equivalent to `UpdateStrategy` with `status` set to `Deprecated`.
+    /// The strategy metadata is kept so that existing positions can still be decreased and closed.
     /// Can only be called by the administrator.
+    /// Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
     RemoveStrategy { strategy_id: Uint64 },
+    /// Update the version, manager address and / or lifecycle status of an existing strategy.
+    /// `manager_addr` is typically updated when the strategy manager contract is redeployed.
+    /// Can only be called by the administrator.
+    /// Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
+    UpdateStrategy {
+        strategy_id: Uint64,
+        version: Option<String>,
+        manager_addr: Option<String>,
+        status: Option<StrategyStatus>,
+    },
+    /// Add up to `limit` positions opened before the strategy -> position index was introduced to the index, resuming after the last position processed by the previous call.
+    /// Call repeatedly until `StrategyPositionIndexProgress.done` is true; see `GetStrategyPositionIndexProgress`.
+    /// Can only be called by the administrator.
+    /// Not subject to the timelock since it only backfills an index derived from existing positions.
+    IndexExistingStrategyPositions { limit: Option<u32> },
+    /// Propose a sensitive administrative operation, which can be executed once the timelock delay in effect at proposal time has elapsed.
+    /// Can only be called by the administrator.
+    ProposeAdminOperation { operation: AdminOperation },
+    /// Execute a proposal whose timelock delay has elapsed.
+    /// Can only be called by the administrator.
+    ExecuteAdminOperation { proposal_id: u64 },
+    /// Cancel a pending proposal.
+    /// Can only be called by the administrator.
+    CancelAdminOperation { proposal_id: u64 },
+    /// Set or clear the pauser, who can pause operations alongside the administrator.
+    /// Can only be called by the administrator.
+    /// Not subject to the timelock: the pauser can do nothing the administrator cannot already do directly, and a compromised pauser must be replaceable right away.
+    UpdatePauser {
+        pauser_addr: Option<String>,
+    },
+    /// Pause the specified operations within `scope`, e.g. to stop new deposits during an incident.
+    /// Can be called by the pauser or the administrator.
+    /// Not subject to the timelock so that an incident can be contained right away.
+    Pause {
+        scope: PauseScope,
+        operations: Vec<PausableOperation>,
+    },
+    /// Resume the specified operations within `scope`.
+    /// Can only be called by the administrator.
+    /// Not subject to the timelock since it only reverts a `Pause`, and operations stay guarded by the checks that apply when they are not paused.
+    Unpause {
+        scope: PauseScope,
+        operations: Vec<PausableOperation>,
+    },
     /// Perform an action on an existing positions held by a Terra address.
     /// Can only be called by the position holder.
     ExecuteStrategy {
@@ -43,6 +103,37 @@ pub enum ExecuteMsg {
         action: Action,
         assets: Vec<terraswap::asset::Asset>,
     },
+    /// Transfer a position held by the caller to `recipient`.
+    /// If `require_acceptance` is true, the transfer only takes effect after `recipient` calls `AcceptPositionTransfer`;
+    /// until then, the caller remains the holder and may replace or cancel the pending transfer.
+    /// Can only be called by the position holder.
+    TransferPosition {
+        position_id: PositionId,
+        recipient: String,
+        require_acceptance: Option<bool>,
+    },
+    /// Accept a pending position transfer and become the position holder.
+    /// Can only be called by the recipient of the pending transfer.
+    AcceptPositionTransfer { position_id: PositionId },
+    /// Cancel a pending position transfer.
+    /// Can only be called by the position holder.
+    CancelPositionTransfer { position_id: PositionId },
+    /// Allow `operator` to perform the listed kinds of actions via `ExecuteStrategy`, either on the specified position
+    /// or, if `position_id` is None, on all positions held by the caller. Replaces any existing grant of the same scope.
+    /// Assets disbursed by operator-initiated actions can only be sent to the position holder on Terra.
+    /// A position-specific grant is cleared when the position is transferred.
+    /// Can be called by any Terra address; a position-specific grant can only be made by the position holder.
+    GrantPositionOperator {
+        operator: String,
+        position_id: Option<PositionId>,
+        allowed_actions: Vec<PositionActionKind>,
+        expires: Option<Expiration>,
+    },
+    /// Remove the grant previously made to `operator` with the same scope.
+    RevokePositionOperator {
+        operator: String,
+        position_id: Option<PositionId>,
+    },
     /// Create a new position with the specified strategy.
     /// Can be called by any Terra address.
     CreatePosition {
@@ -52,6 +143,7 @@ pub enum ExecuteMsg {
     },
     /// Registers the address of Aperture manager contract on an external chain.
     /// Can only be called by the administrator.
+    /// Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
     RegisterExternalChainManager {
         chain_id: ChainId,
         // Wormhole encoded address of the Aperture manager contract (32-byte array).
@@ -59,6 +151,13 @@ pub enum ExecuteMsg {
         // See https://docs.rs/cosmwasm-std/0.16.4/cosmwasm_std/struct.Binary.html.
         aperture_manager_addr: Binary,
     },
+    /// Update the default rate, fee collector, per-chain and per-asset rate overrides, per-asset fee bounds and / or
+    /// fee-exempt senders of cross-chain outgoing transfers.
+    /// Can only be called by the administrator.
+    /// Must be proposed through `ProposeAdminOperation`; direct calls are rejected.
+    UpdateCrossChainOutgoingFeeSchedule {
+        update: CrossChainOutgoingFeeScheduleUpdate,
+    },
     /// Processes a position action request instructed by an Aperture manager contract on an external chain.
     /// This handles actions on Terra strategy positions held by external chain addresses.
     /// Can be called by any Terra address.
@@ -69,17 +168,56 @@ pub enum ExecuteMsg {
         token_transfer_vaas: Vec<Binary>,
     },
     /// Initiates cross-chain transfer via Wormhole token bridge to an external chain, after deduction of fees (e.g. 0.1% of the transfer amount).
+    /// The fee is determined by the cross-chain outgoing fee schedule; see `PreviewCrossChainOutgoingFee`.
     /// Although this can be called by any Terra address, this is only intended to be called by Aperture strategy contracts on Terra when outgoing cross-chain transfer is needed.
     /// Other callers will want to directly use Worhole token bridge to initiate the transfer without Aperture fees.
     InitiateOutgoingTokenTransfer {
         assets: Vec<terraswap::asset::Asset>,
         recipient: Recipient,
     },
+    /// cw721 interface over positions held by Terra addresses; `token_id` is the decimal string of the position id.
+    /// Transfer the position to `recipient`, clearing its approvals and any pending position transfer.
+    /// Can be called by the position holder, an approved spender of the position, or an operator of the holder.
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Same as `TransferNft`, and additionally invokes `ReceiveNft` on the `contract`.
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allow `spender` to transfer the position.
+    /// Can be called by the position holder or an operator of the holder.
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove the approval previously granted to `spender`.
+    /// Can be called by the position holder or an operator of the holder.
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allow `operator` to transfer and approve any position held by the caller.
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove the operator previously granted by the caller.
+    RevokeAll {
+        operator: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// Sets the timelock delay, which contracts instantiated before the timelock was introduced lack.
+    pub timelock_delay_seconds: u64,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -88,12 +226,59 @@ pub enum QueryMsg {
     GetStrategyMetadata {
         strategy_id: Uint64,
     },
+    // Returns `StrategyStatus`.
+    GetStrategyStatus {
+        strategy_id: Uint64,
+    },
+    // Returns `StrategiesResponse` with strategies ordered by id, including deprecated and paused ones.
+    ListStrategies {
+        start_after: Option<Uint64>,
+        limit: Option<u32>,
+    },
+    // Returns `StrategyPositionsResponse` with positions opened in the specified strategy, held either on Terra or on other chains.
+    // Positions opened before the index was introduced are missing until `StrategyPositionIndexProgress.done` is true.
+    GetStrategyPositions {
+        strategy_id: Uint64,
+        start_after: Option<Position>,
+        limit: Option<u32>,
+    },
+    // Returns the number of positions opened in the specified strategy as `Uint64`.
+    GetStrategyPositionCount {
+        strategy_id: Uint64,
+    },
+    // Returns `StrategyPositionIndexProgress`.
+    GetStrategyPositionIndexProgress {},
+    // Returns the timelock delay in seconds as `u64`.
+    GetTimelockDelay {},
+    // Returns pending proposals ordered by id as `Vec<AdminOperationProposal<AdminOperation>>`.
+    GetAdminOperationProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // Returns the administrator nominated through `UpdateAdminAddr` that has yet to accept as `Option<Addr>`.
+    GetPendingAdmin {},
+    // Returns the pauser as `Option<Addr>`.
+    GetPauser {},
+    // Returns operations paused within the specified scope as `Vec<PausableOperation>`.
+    GetPausedOperations {
+        scope: PauseScope,
+    },
     // Returns `NextPositionIdResponse`.
     GetNextPositionId {},
     // Returns `PositionInfoResponse`.
     GetTerraPositionInfo {
         position_id: PositionId,
     },
+    // Returns the recipient of the pending transfer of the specified position as `Option<Addr>`.
+    GetPendingPositionTransfer {
+        position_id: PositionId,
+    },
+    // Returns `Option<PositionOperatorGrant>` made by `holder` to `operator` for the specified position, or for all positions of `holder` if `position_id` is None.
+    GetPositionOperatorGrant {
+        holder: String,
+        operator: String,
+        position_id: Option<PositionId>,
+    },
     // Returns `PositionsResponse`.
     GetTerraPositionsByHolder {
         holder: String,
@@ -112,6 +297,187 @@ pub enum QueryMsg {
     HasInstructionVaaBeenProcessed {
         instruction_vaa: Binary,
     },
+    // Returns `CrossChainOutgoingFeePreviewResponse` for a transfer of `asset` to `recipient_chain_id` initiated by `sender`.
+    PreviewCrossChainOutgoingFee {
+        sender: String,
+        recipient_chain_id: ChainId,
+        asset: terraswap::asset::Asset,
+    },
+    // cw721 queries over positions held by Terra addresses; `token_id` is the decimal string of the position id.
+    // Returns `cw721::OwnerOfResponse`.
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    // Returns `cw721::ApprovalResponse`.
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    // Returns `cw721::ApprovalsResponse`.
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    // Returns `cw721::OperatorsResponse`.
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Returns `cw721::NumTokensResponse`.
+    NumTokens {},
+    // Returns `cw721::ContractInfoResponse`.
+    ContractInfo {},
+    // Returns `cw721::NftInfoResponse`.
+    NftInfo {
+        token_id: String,
+    },
+    // Returns `cw721::AllNftInfoResponse`.
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    // Returns `cw721::TokensResponse` with positions held by `owner`.
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Returns `cw721::TokensResponse` with all positions held by Terra addresses.
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// Lifecycle status of a strategy, which restricts the position actions that can be performed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyStatus {
+    // All actions are allowed.
+    Active,
+    // New positions and increases are disallowed; existing positions can still be decreased and closed.
+    Deprecated,
+    // Only emergency close is allowed.
+    Paused,
+}
+
+/// Sensitive administrative operations subject to the timelock; each mirrors the `ExecuteMsg` variant of the same name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminOperation {
+    UpdateAdminAddr {
+        new_admin_addr: String,
+    },
+    AddStrategy {
+        name: String,
+        version: String,
+        manager_addr: String,
+    },
+    RemoveStrategy { strategy_id: Uint64 },
+    UpdateStrategy {
+        strategy_id: Uint64,
+        version: Option<String>,
+        manager_addr: Option<String>,
+        status: Option<StrategyStatus>,
+    },
+    RegisterExternalChainManager {
+        chain_id: ChainId,
+        aperture_manager_addr: Binary,
+    },
+    UpdateCrossChainOutgoingFeeSchedule {
+        update: CrossChainOutgoingFeeScheduleUpdate,
+    },
+    /// Takes effect once the current delay has elapsed; must be at least `MIN_TIMELOCK_DELAY_SECONDS`.
+    UpdateTimelockDelay {
+        delay_seconds: u64,
+    },
+}
+
+/// Bounds of the fee charged on a cross-chain outgoing transfer of an asset, denominated in that asset.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CrossChainOutgoingFeeBounds {
+    pub min_fee: Uint128,
+    pub max_fee: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CrossChainOutgoingFeeRateOverride {
+    pub recipient_chain_id: ChainId,
+    pub asset_info: terraswap::asset::AssetInfo,
+    // Removes the override if None.
+    pub rate: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CrossChainOutgoingFeeBoundsUpdate {
+    pub asset_info: terraswap::asset::AssetInfo,
+    // Removes the bounds if None.
+    pub bounds: Option<CrossChainOutgoingFeeBounds>,
+}
+
+/// Changes to the cross-chain outgoing fee schedule; fields left unset are unchanged.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CrossChainOutgoingFeeScheduleUpdate {
+    // Rate applied to transfers without an override for the recipient chain and asset.
+    pub default_rate: Option<Decimal>,
+    pub fee_collector_addr: Option<String>,
+    #[serde(default)]
+    pub rate_overrides: Vec<CrossChainOutgoingFeeRateOverride>,
+    #[serde(default)]
+    pub fee_bounds: Vec<CrossChainOutgoingFeeBoundsUpdate>,
+    // Senders, e.g. Aperture strategy managers, whose transfers are not charged any fee.
+    #[serde(default)]
+    pub exempt_addrs_to_add: Vec<String>,
+    #[serde(default)]
+    pub exempt_addrs_to_remove: Vec<String>,
+}
+
+/// Operations that can be paused.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PausableOperation {
+    CreatePosition,
+    ExecuteStrategy,
+    ProcessCrossChainInstruction,
+    InitiateOutgoingTokenTransfer,
+}
+
+/// Scope within which a paused operation is rejected.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseScope {
+    // All strategies and chains.
+    Global,
+    // Positions in the specified Terra strategy.
+    Strategy { strategy_id: StrategyId },
+    // The strategy chain for `CreatePosition` and `ExecuteStrategy`, the source chain for `ProcessCrossChainInstruction`,
+    // and the destination chain for `InitiateOutgoingTokenTransfer`.
+    Chain { chain_id: ChainId },
+}
+
+/// Kinds of position actions that can be delegated to an operator.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionActionKind {
+    IncreasePosition,
+    DecreasePosition,
+    ClosePosition,
+    EmergencyClosePosition,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PositionOperatorGrant {
+    pub allowed_actions: Vec<PositionActionKind>,
+    pub expires: Expiration,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -133,3 +499,50 @@ pub struct PositionsResponse {
     pub position_id_vec: Vec<PositionId>,
     pub strategy_location_vec: Vec<StrategyLocation>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StrategyInfo {
+    pub strategy_id: StrategyId,
+    pub metadata: StrategyMetadata,
+    pub status: StrategyStatus,
+    pub position_count: Uint64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StrategiesResponse {
+    pub strategies: Vec<StrategyInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StrategyPositionInfo {
+    pub position: Position,
+    // Holder of the position; only known for positions held on Terra.
+    pub holder: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StrategyPositionsResponse {
+    pub positions: Vec<StrategyPositionInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StrategyPositionIndexProgress {
+    // The last position processed; the next batch resumes after it.
+    pub cursor: Option<Position>,
+    // Number of positions added to the index, excluding those already indexed upon creation.
+    pub indexed_count: u64,
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CrossChainOutgoingFeePreviewResponse {
+    pub rate: Decimal,
+    pub fee_amount: Uint128,
+    pub cross_chain_amount: Uint128,
+}
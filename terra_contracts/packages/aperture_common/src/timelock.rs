@@ -0,0 +1,219 @@
+/// This module implements a timelock for sensitive administrative operations.
+/// An operation is first proposed by the administrator, and can only be executed once the timelock delay in effect at proposal time has elapsed.
+/// The delay is set upon instantiation and can only be changed through a proposal, i.e. a change takes effect after the current delay.
+/// Each contract defines its own operation type `T` and performs the operation upon execution.
+use cosmwasm_std::{Env, Order, StdError, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Item, Map, PrimaryKey, U64Key};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+// Leaves at least a day to notice and respond to a proposal before it can be executed.
+pub const MIN_TIMELOCK_DELAY_SECONDS: u64 = 24 * 60 * 60;
+
+pub fn assert_valid_timelock_delay(delay_seconds: u64) -> StdResult<()> {
+    if delay_seconds < MIN_TIMELOCK_DELAY_SECONDS {
+        return Err(StdError::generic_err(format!(
+            "timelock delay must be at least {} seconds",
+            MIN_TIMELOCK_DELAY_SECONDS
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AdminOperationProposal<T> {
+    pub proposal_id: u64,
+    pub operation: T,
+    pub executable_after: Timestamp,
+}
+
+pub struct Timelock<'a, T> {
+    delay_seconds: Item<'a, u64>,
+    next_proposal_id: Item<'a, u64>,
+    proposals: Map<'a, U64Key, AdminOperationProposal<T>>,
+}
+
+impl<'a, T> Timelock<'a, T> {
+    pub const fn new(
+        delay_seconds_key: &'a str,
+        next_proposal_id_key: &'a str,
+        proposals_namespace: &'a str,
+    ) -> Self {
+        Timelock {
+            delay_seconds: Item::new(delay_seconds_key),
+            next_proposal_id: Item::new(next_proposal_id_key),
+            proposals: Map::new(proposals_namespace),
+        }
+    }
+}
+
+impl<'a, T: Serialize + DeserializeOwned> Timelock<'a, T> {
+    // Fails if no delay has been set, so that nothing can be proposed without a delay in effect.
+    pub fn get_delay_seconds(&self, storage: &dyn Storage) -> StdResult<u64> {
+        self.delay_seconds
+            .may_load(storage)?
+            .ok_or_else(|| StdError::generic_err("timelock delay is not set"))
+    }
+
+    pub fn set_delay_seconds(
+        &self,
+        storage: &mut dyn Storage,
+        delay_seconds: u64,
+    ) -> StdResult<()> {
+        assert_valid_timelock_delay(delay_seconds)?;
+        self.delay_seconds.save(storage, &delay_seconds)
+    }
+
+    pub fn propose(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        operation: T,
+    ) -> StdResult<AdminOperationProposal<T>> {
+        let executable_after = env
+            .block
+            .time
+            .plus_seconds(self.get_delay_seconds(storage)?);
+        let proposal_id = self.next_proposal_id.may_load(storage)?.unwrap_or(0);
+        self.next_proposal_id.save(storage, &(proposal_id + 1))?;
+        let proposal = AdminOperationProposal {
+            proposal_id,
+            operation,
+            executable_after,
+        };
+        self.proposals
+            .save(storage, U64Key::from(proposal_id), &proposal)?;
+        Ok(proposal)
+    }
+
+    pub fn load_proposal(
+        &self,
+        storage: &dyn Storage,
+        proposal_id: u64,
+    ) -> StdResult<AdminOperationProposal<T>> {
+        self.proposals
+            .may_load(storage, U64Key::from(proposal_id))?
+            .ok_or_else(|| StdError::generic_err("proposal not found"))
+    }
+
+    // Removes and returns the proposal if its timelock has elapsed.
+    pub fn take_executable_proposal(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        proposal_id: u64,
+    ) -> StdResult<AdminOperationProposal<T>> {
+        let proposal = self.load_proposal(storage, proposal_id)?;
+        if env.block.time < proposal.executable_after {
+            return Err(StdError::generic_err("timelock has not elapsed"));
+        }
+        self.proposals.remove(storage, U64Key::from(proposal_id));
+        Ok(proposal)
+    }
+
+    pub fn cancel_proposal(&self, storage: &mut dyn Storage, proposal_id: u64) -> StdResult<()> {
+        self.load_proposal(storage, proposal_id)?;
+        self.proposals.remove(storage, U64Key::from(proposal_id));
+        Ok(())
+    }
+
+    pub fn query_proposals(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<AdminOperationProposal<T>>> {
+        const DEFAULT_LIMIT: u32 = 10;
+        const MAX_LIMIT: u32 = 30;
+        let min =
+            start_after.map(|proposal_id| Bound::Exclusive(U64Key::from(proposal_id).joined_key()));
+        self.proposals
+            .range(storage, min, None, Order::Ascending)
+            .take(limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize)
+            .map(|item| item.map(|(_, proposal)| proposal))
+            .collect()
+    }
+}
+
+#[test]
+fn test_timelock() {
+    use cosmwasm_std::testing::{mock_env, MockStorage};
+
+    let timelock: Timelock<String> = Timelock::new("tld", "tlnpi", "tlp");
+    let mut storage = MockStorage::new();
+    let mut env = mock_env();
+    let delay = MIN_TIMELOCK_DELAY_SECONDS;
+
+    // Nothing can be proposed until a delay is set, and the delay cannot be shorter than the minimum.
+    assert_eq!(
+        timelock.propose(&mut storage, &env, String::from("op0")),
+        Err(StdError::generic_err("timelock delay is not set"))
+    );
+    for invalid_delay in [0, delay - 1].iter() {
+        assert_eq!(
+            timelock.set_delay_seconds(&mut storage, *invalid_delay),
+            Err(StdError::generic_err(
+                "timelock delay must be at least 86400 seconds"
+            ))
+        );
+    }
+    timelock.set_delay_seconds(&mut storage, delay).unwrap();
+    assert_eq!(timelock.get_delay_seconds(&storage), Ok(delay));
+
+    let op0 = timelock
+        .propose(&mut storage, &env, String::from("op0"))
+        .unwrap();
+    assert_eq!(op0.proposal_id, 0);
+    let op1 = timelock
+        .propose(&mut storage, &env, String::from("op1"))
+        .unwrap();
+    let op2 = timelock
+        .propose(&mut storage, &env, String::from("op2"))
+        .unwrap();
+    assert_eq!(op2.executable_after, env.block.time.plus_seconds(delay));
+    assert_eq!(
+        timelock.query_proposals(&storage, None, None),
+        Ok(vec![op0.clone(), op1, op2.clone()])
+    );
+    assert_eq!(
+        timelock.query_proposals(&storage, Some(1), None),
+        Ok(vec![op2.clone()])
+    );
+    timelock.cancel_proposal(&mut storage, 1).unwrap();
+    assert_eq!(
+        timelock.cancel_proposal(&mut storage, 1),
+        Err(StdError::generic_err("proposal not found"))
+    );
+
+    env.block.time = env.block.time.plus_seconds(delay - 1);
+    assert_eq!(
+        timelock.take_executable_proposal(&mut storage, &env, 2),
+        Err(StdError::generic_err("timelock has not elapsed"))
+    );
+    env.block.time = env.block.time.plus_seconds(1);
+    assert_eq!(
+        timelock.take_executable_proposal(&mut storage, &env, 2),
+        Ok(op2)
+    );
+    assert_eq!(
+        timelock.take_executable_proposal(&mut storage, &env, 2),
+        Err(StdError::generic_err("proposal not found"))
+    );
+
+    // A new delay only applies to proposals made after it is set.
+    timelock.set_delay_seconds(&mut storage, 2 * delay).unwrap();
+    let op3 = timelock
+        .propose(&mut storage, &env, String::from("op3"))
+        .unwrap();
+    assert_eq!(op3.executable_after, env.block.time.plus_seconds(2 * delay));
+    assert_eq!(
+        timelock.take_executable_proposal(&mut storage, &env, 0),
+        Ok(op0)
+    );
+    assert_eq!(
+        timelock.query_proposals(&storage, None, None),
+        Ok(vec![op3])
+    );
+}